@@ -0,0 +1,186 @@
+//! Derive macros of libogl, use them through `libogl::vertex::Vertex` and
+//! `libogl::uniform::Uniforms`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, ExprLit, Fields, FieldsNamed, Lit, Meta};
+
+/// Implement `libogl::vertex::Vertex` for a `#[repr(C)]` struct.
+///
+/// Every field is an attribute and needs `#[location = N]`. Integer fields marked with
+/// `#[normalized]` are mapped to [0, 1] or [-1, 1].
+#[proc_macro_derive(Vertex, attributes(location, normalized))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    vertex(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Implement `libogl::uniform::Uniforms`, setting every field to the uniform of the same name.
+///
+/// `#[uniform = "name"]` sets a field to another uniform.
+#[proc_macro_derive(Uniforms, attributes(uniform))]
+pub fn derive_uniforms(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    uniforms(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn vertex(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = named_fields(input, "Vertex")?;
+
+    let repr_c = input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("repr"))
+        .any(|a| {
+            let mut c = false;
+            let _ = a.parse_nested_meta(|m| {
+                c |= m.path.is_ident("C");
+                Ok(())
+            });
+            c
+        });
+    if !repr_c {
+        return Err(syn::Error::new(
+            name.span(),
+            "#[derive(Vertex)] requires #[repr(C)]",
+        ));
+    }
+
+    let mut locations: Vec<(u32, Span)> = Vec::new();
+    let mut attributes = Vec::new();
+
+    for field in &fields.named {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+
+        let mut location = None;
+        let mut normalized = false;
+        for attr in &field.attrs {
+            if attr.path().is_ident("location") {
+                let value = name_value(&attr.meta, "location = N")?;
+                location = Some(match value {
+                    Lit::Int(n) => (n.base10_parse::<u32>()?, n.span()),
+                    other => {
+                        return Err(syn::Error::new(other.span(), "expected #[location = N]"));
+                    }
+                });
+            } else if attr.path().is_ident("normalized") {
+                attr.meta.require_path_only()?;
+                normalized = true;
+            }
+        }
+
+        let (location, span) = location.ok_or(syn::Error::new(
+            ident.span(),
+            format!("{ident} has no #[location = N]"),
+        ))?;
+
+        if locations.iter().any(|(l, _)| *l == location) {
+            return Err(syn::Error::new(
+                span,
+                format!("location {location} is used twice"),
+            ));
+        }
+        locations.push((location, span));
+
+        let method = if normalized {
+            quote!(normalized)
+        } else {
+            quote!(attribute)
+        };
+        attributes.push(quote_spanned! {ty.span()=>
+            .#method::<#ty>(#location, ::std::mem::offset_of!(Self, #ident))
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::libogl::vertex::Vertex for #name #ty_generics #where_clause {
+            fn layout() -> ::libogl::vertex::VertexLayout {
+                ::libogl::vertex::VertexLayout::of::<Self>()
+                    #(#attributes)*
+            }
+        }
+    })
+}
+
+fn uniforms(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = named_fields(input, "Uniforms")?;
+
+    let mut setters = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+
+        let mut uniform = ident.to_string();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("uniform")) {
+            uniform = match name_value(&attr.meta, "uniform = \"name\"")? {
+                Lit::Str(s) => s.value(),
+                other => {
+                    return Err(syn::Error::new(
+                        other.span(),
+                        "expected #[uniform = \"name\"]",
+                    ));
+                }
+            };
+        }
+
+        setters.push(quote_spanned! {ty.span()=>
+            program.set_uniform::<#ty>(#uniform, self.#ident)?;
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::libogl::uniform::Uniforms for #name #ty_generics #where_clause {
+            fn upload(
+                &self,
+                program: &::libogl::shader::Program,
+            ) -> ::libogl::__private::Result<(), ::libogl::error::OglError> {
+                #(#setters)*
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+fn named_fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<&'a FieldsNamed> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields),
+            _ => Err(syn::Error::new(
+                input.ident.span(),
+                format!("#[derive({derive})] requires named fields"),
+            )),
+        },
+        _ => Err(syn::Error::new(
+            input.ident.span(),
+            format!("#[derive({derive})] only supports structs"),
+        )),
+    }
+}
+
+/// Literal of `#[name = literal]`.
+fn name_value<'a>(meta: &'a Meta, expected: &str) -> syn::Result<&'a Lit> {
+    match meta {
+        Meta::NameValue(nv) => match &nv.value {
+            Expr::Lit(ExprLit { lit, .. }) => Ok(lit),
+            other => Err(syn::Error::new(
+                other.span(),
+                format!("expected #[{expected}]"),
+            )),
+        },
+        other => Err(syn::Error::new(
+            other.span(),
+            format!("expected #[{expected}]"),
+        )),
+    }
+}
@@ -0,0 +1,222 @@
+use super::glapi;
+use super::vertex::{Vertex, VertexLayout};
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Buffer object holding elements of T, deleted when dropped.
+///
+/// The default buffer has no GL object (id 0) so that it can be used as a placeholder until the
+/// data is uploaded.
+pub struct Buffer<T> {
+    gl: Option<Rc<glapi::GlFns>>,
+    id: u32,
+    target: glapi::BufferTargetARB,
+    len: usize,
+    _data: PhantomData<T>,
+}
+
+impl<T> Default for Buffer<T> {
+    fn default() -> Self {
+        Self {
+            gl: None,
+            id: 0,
+            target: glapi::GL_ARRAY_BUFFER,
+            len: 0,
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<T: Copy> Buffer<T> {
+    /// Create an empty buffer for `target`.
+    pub fn new(gl: &Rc<glapi::GlFns>, target: glapi::BufferTargetARB) -> Self {
+        let mut id = 0;
+        unsafe { gl.GenBuffers(1, &mut id) };
+
+        Self {
+            gl: Some(gl.clone()),
+            id,
+            target,
+            len: 0,
+            _data: PhantomData,
+        }
+    }
+
+    /// Create a buffer for `target` and upload `data`. The buffer is left bound to `target`.
+    pub fn with_data(
+        gl: &Rc<glapi::GlFns>,
+        target: glapi::BufferTargetARB,
+        data: &[T],
+        usage: glapi::BufferUsageARB,
+    ) -> Self {
+        let mut buffer = Self::new(gl, target);
+        buffer.set_data(data, usage);
+        buffer
+    }
+
+    /// Replace the content of the buffer. The buffer is left bound to its target.
+    pub fn set_data(&mut self, data: &[T], usage: glapi::BufferUsageARB) {
+        if let Some(gl) = self.gl.as_ref() {
+            unsafe {
+                gl.BindBuffer(self.target, self.id);
+                gl.BufferData(
+                    self.target,
+                    std::mem::size_of_val(data) as isize,
+                    data.as_ptr().cast(),
+                    usage,
+                );
+            }
+            self.len = data.len();
+        }
+    }
+
+    /// Reserve storage for `len` elements without initializing it, e.g. to be filled through
+    /// glMapBufferRange(). The buffer is left bound to its target.
+    pub fn allocate(&mut self, len: usize, usage: glapi::BufferUsageARB) {
+        if let Some(gl) = self.gl.as_ref() {
+            unsafe {
+                gl.BindBuffer(self.target, self.id);
+                gl.BufferData(
+                    self.target,
+                    (len * std::mem::size_of::<T>()) as isize,
+                    std::ptr::null(),
+                    usage,
+                );
+            }
+            self.len = len;
+        }
+    }
+
+    pub fn bind(&self) {
+        if let Some(gl) = self.gl.as_ref() {
+            unsafe { gl.BindBuffer(self.target, self.id) };
+        }
+    }
+
+    pub fn unbind(&self) {
+        if let Some(gl) = self.gl.as_ref() {
+            unsafe { gl.BindBuffer(self.target, 0) };
+        }
+    }
+}
+
+impl<T> Buffer<T> {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn target(&self) -> glapi::BufferTargetARB {
+        self.target
+    }
+
+    /// Number of elements uploaded.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Size of the uploaded data in bytes.
+    pub fn size(&self) -> usize {
+        self.len * std::mem::size_of::<T>()
+    }
+}
+
+impl<T> Drop for Buffer<T> {
+    fn drop(&mut self) {
+        if let Some(gl) = self.gl.take() {
+            unsafe { gl.DeleteBuffers(1, &self.id) };
+        }
+    }
+}
+
+impl<T> Display for Buffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "id: {}, target: 0x{:x}, len: {}, size: {}",
+            self.id,
+            self.target.0,
+            self.len,
+            self.size()
+        )
+    }
+}
+
+/// Vertex array object, deleted when dropped. The default one has no GL object (id 0).
+#[derive(Default)]
+pub struct VertexArray {
+    gl: Option<Rc<glapi::GlFns>>,
+    id: u32,
+}
+
+impl VertexArray {
+    pub fn new(gl: &Rc<glapi::GlFns>) -> Self {
+        let mut id = 0;
+        unsafe { gl.GenVertexArrays(1, &mut id) };
+
+        Self {
+            gl: Some(gl.clone()),
+            id,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn bind(&self) {
+        if let Some(gl) = self.gl.as_ref() {
+            gl.BindVertexArray(self.id);
+        }
+    }
+
+    pub fn unbind(&self) {
+        if let Some(gl) = self.gl.as_ref() {
+            gl.BindVertexArray(0);
+        }
+    }
+
+    /// Read the vertices of the vertex array from `buffer` as described by `layout`. The vertex
+    /// array is left bound, e.g. to add an element buffer.
+    pub fn set_layout<T: Copy>(&self, buffer: &Buffer<T>, layout: &VertexLayout) {
+        if let Some(gl) = self.gl.as_ref() {
+            gl.BindVertexArray(self.id);
+            buffer.bind();
+            layout.apply(gl);
+        }
+    }
+
+    /// Read vertices of type V from `buffer`.
+    pub fn set_vertices<V: Vertex>(&self, buffer: &Buffer<V>) {
+        self.set_layout(buffer, &V::layout());
+    }
+}
+
+impl Drop for VertexArray {
+    fn drop(&mut self) {
+        if let Some(gl) = self.gl.take() {
+            unsafe { gl.DeleteVertexArrays(1, &self.id) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glapi, Buffer, VertexArray};
+
+    #[test]
+    fn default_objects_have_no_gl_object() {
+        let buffer = Buffer::<u16>::default();
+        assert_eq!(buffer.id(), 0);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.target(), glapi::GL_ARRAY_BUFFER);
+
+        // Nothing to delete, dropping must not touch GL.
+        drop(buffer);
+        drop(VertexArray::default());
+    }
+}
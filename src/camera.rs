@@ -0,0 +1,588 @@
+//! Cameras and the controllers moving them from input events.
+//!
+//! A demo owns its Camera, calls Camera::resize() from Demo::resize() and feeds the events of
+//! Demo::input() to a CameraController.
+
+use super::input::{InputEvent, Key, MouseButton};
+use glam::{Mat4, Vec2, Vec3};
+use std::collections::HashSet;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Handedness {
+    Left,
+    #[default]
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// Vertical field of view in radians.
+    Perspective { fov_y: f32 },
+    /// Height of the view volume in world units, the width follows the aspect ratio.
+    Orthographic { height: f32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera {
+    position: Vec3,
+    target: Vec3,
+    up: Vec3,
+    projection: Projection,
+    near: f32,
+    far: f32,
+    handedness: Handedness,
+    width: i32,
+    height: i32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::perspective(45.0_f32.to_radians(), 0.1, 100.0)
+    }
+}
+
+impl Camera {
+    pub fn perspective(fov_y: f32, near: f32, far: f32) -> Self {
+        Self::new(Projection::Perspective { fov_y }, near, far)
+    }
+
+    pub fn orthographic(height: f32, near: f32, far: f32) -> Self {
+        Self::new(Projection::Orthographic { height }, near, far)
+    }
+
+    /// Camera at (0, 0, 1) looking at the origin, with a square viewport.
+    pub fn new(projection: Projection, near: f32, far: f32) -> Self {
+        Self {
+            position: Vec3::Z,
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            projection,
+            near,
+            far,
+            handedness: Handedness::default(),
+            width: 1,
+            height: 1,
+        }
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn target(&self) -> Vec3 {
+        self.target
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.up
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn near(&self) -> f32 {
+        self.near
+    }
+
+    pub fn far(&self) -> f32 {
+        self.far
+    }
+
+    pub fn handedness(&self) -> Handedness {
+        self.handedness
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+    }
+
+    pub fn set_target(&mut self, target: Vec3) {
+        self.target = target;
+    }
+
+    pub fn look_at(&mut self, position: Vec3, target: Vec3) {
+        self.position = position;
+        self.target = target;
+    }
+
+    pub fn set_up(&mut self, up: Vec3) {
+        self.up = up;
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    pub fn set_depth_range(&mut self, near: f32, far: f32) {
+        self.near = near;
+        self.far = far;
+    }
+
+    pub fn set_handedness(&mut self, handedness: Handedness) {
+        self.handedness = handedness;
+    }
+
+    /// Follow the size of the surface, the aspect ratio is derived from it.
+    pub fn resize(&mut self, width: i32, height: i32) {
+        if width > 0 && height > 0 {
+            self.width = width;
+            self.height = height;
+        }
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+
+    pub fn distance(&self) -> f32 {
+        (self.target - self.position).length()
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        (self.target - self.position).normalize_or_zero()
+    }
+
+    /// Direction of the positive x axis of the screen in world space.
+    pub fn right(&self) -> Vec3 {
+        match self.handedness {
+            Handedness::Left => self.up.cross(self.forward()).normalize_or_zero(),
+            Handedness::Right => self.forward().cross(self.up).normalize_or_zero(),
+        }
+    }
+
+    /// Direction of the positive y axis of the screen in world space.
+    pub fn view_up(&self) -> Vec3 {
+        perpendicular_up(self.forward(), self.up)
+    }
+
+    pub fn view(&self) -> Mat4 {
+        match self.handedness {
+            Handedness::Left => Mat4::look_at_lh(self.position, self.target, self.up),
+            Handedness::Right => Mat4::look_at_rh(self.position, self.target, self.up),
+        }
+    }
+
+    pub fn projection_matrix(&self) -> Mat4 {
+        let aspect = self.aspect();
+        match (self.projection, self.handedness) {
+            (Projection::Perspective { fov_y }, Handedness::Left) => {
+                Mat4::perspective_lh(fov_y, aspect, self.near, self.far)
+            }
+            (Projection::Perspective { fov_y }, Handedness::Right) => {
+                Mat4::perspective_rh(fov_y, aspect, self.near, self.far)
+            }
+            (Projection::Orthographic { height }, handedness) => {
+                let (h, w) = (height / 2.0, height / 2.0 * aspect);
+                match handedness {
+                    Handedness::Left => Mat4::orthographic_lh(-w, w, -h, h, self.near, self.far),
+                    Handedness::Right => Mat4::orthographic_rh(-w, w, -h, h, self.near, self.far),
+                }
+            }
+        }
+    }
+
+    pub fn view_projection(&self) -> Mat4 {
+        self.projection_matrix() * self.view()
+    }
+
+    /// World units covered by a pixel at the distance of the target.
+    pub fn units_per_pixel(&self) -> f32 {
+        let visible = match self.projection {
+            Projection::Perspective { fov_y } => 2.0 * self.distance() * (fov_y / 2.0).tan(),
+            Projection::Orthographic { height } => height,
+        };
+
+        visible / self.height as f32
+    }
+
+    /// Scale the visible area by `factor`: an orthographic view volume is resized, a
+    /// perspective camera moves along its line of sight.
+    pub fn zoom(&mut self, factor: f32) {
+        match &mut self.projection {
+            Projection::Orthographic { height } => *height *= factor,
+            Projection::Perspective { .. } => {
+                self.position = self.target + (self.position - self.target) * factor;
+            }
+        }
+    }
+
+    fn translate(&mut self, offset: Vec3) {
+        self.position += offset;
+        self.target += offset;
+    }
+
+    /// Move the camera so that the scene follows a pointer moved by `delta` pixels.
+    fn drag(&mut self, delta: Vec2) {
+        let offset = (self.view_up() * delta.y - self.right() * delta.x) * self.units_per_pixel();
+        self.translate(offset);
+    }
+}
+
+/// Changes the camera from the input events received by Demo::input().
+pub trait CameraController {
+    fn input(&mut self, camera: &mut Camera, event: &InputEvent);
+
+    /// Continuous movement, called once per frame with the duration of the frame.
+    fn update(&mut self, _camera: &mut Camera, _dt: Duration) {}
+}
+
+/// Pointer position and pressed buttons.
+#[derive(Debug, Default)]
+struct Pointer {
+    position: Option<Vec2>,
+    buttons: HashSet<MouseButton>,
+}
+
+impl Pointer {
+    /// Update from `event`, the distance moved by a motion event is returned.
+    fn handle(&mut self, event: &InputEvent) -> Option<Vec2> {
+        match *event {
+            InputEvent::PointerMotion { x, y } => {
+                let position = Vec2::new(x, y);
+                let delta = self.position.map(|p| position - p);
+                self.position = Some(position);
+                delta
+            }
+            InputEvent::PointerButton { button, pressed } => {
+                if pressed {
+                    self.buttons.insert(button);
+                } else {
+                    self.buttons.remove(&button);
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn pressed(&self, button: MouseButton) -> bool {
+        self.buttons.contains(&button)
+    }
+}
+
+/// Component of `up` perpendicular to `direction`.
+fn perpendicular_up(direction: Vec3, up: Vec3) -> Vec3 {
+    (up - direction * direction.dot(up)).normalize_or_zero()
+}
+
+/// Rotate the unit vector `direction` by `yaw` around `up`, towards `right`, and by `pitch`
+/// towards `up`. The pitch is dropped if it would get too close to the poles.
+fn turn(direction: Vec3, up: Vec3, right: Vec3, yaw: f32, pitch: f32) -> Vec3 {
+    let up = up.normalize_or_zero();
+    let vertical = up * direction.dot(up);
+    let horizontal = direction - vertical;
+    let direction = vertical + horizontal * yaw.cos() + right * horizontal.length() * yaw.sin();
+
+    let pitched = direction * pitch.cos() + perpendicular_up(direction, up) * pitch.sin();
+    if pitched.dot(up).abs() < 0.99 {
+        pitched.normalize()
+    } else {
+        direction.normalize()
+    }
+}
+
+/// Orbit around the target: drag with the left button to rotate, with the right or middle
+/// button to pan and scroll to zoom.
+#[derive(Debug)]
+pub struct OrbitController {
+    rotate_speed: f32,
+    zoom_step: f32,
+    pointer: Pointer,
+}
+
+impl Default for OrbitController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrbitController {
+    pub fn new() -> Self {
+        Self {
+            rotate_speed: 0.01,
+            zoom_step: 0.9,
+            pointer: Pointer::default(),
+        }
+    }
+
+    /// Radians per pixel of pointer motion.
+    pub fn set_rotate_speed(&mut self, speed: f32) {
+        self.rotate_speed = speed;
+    }
+
+    /// Zoom factor of a scroll step.
+    pub fn set_zoom_step(&mut self, step: f32) {
+        self.zoom_step = step;
+    }
+
+    /// True while the user drags the camera.
+    pub fn is_dragging(&self) -> bool {
+        !self.pointer.buttons.is_empty()
+    }
+}
+
+impl CameraController for OrbitController {
+    fn input(&mut self, camera: &mut Camera, event: &InputEvent) {
+        if let InputEvent::Scroll { dy, .. } = *event {
+            camera.zoom(self.zoom_step.powf(dy));
+            return;
+        }
+
+        let Some(delta) = self.pointer.handle(event) else {
+            return;
+        };
+
+        if self.pointer.pressed(MouseButton::Left) {
+            // The camera goes the opposite way of the pointer so that the scene follows it.
+            let offset = camera.position() - camera.target();
+            let direction = turn(
+                offset.normalize_or_zero(),
+                camera.up(),
+                camera.right(),
+                -delta.x * self.rotate_speed,
+                delta.y * self.rotate_speed,
+            );
+            camera.set_position(camera.target() + direction * offset.length());
+        } else if self.pointer.pressed(MouseButton::Right)
+            || self.pointer.pressed(MouseButton::Middle)
+        {
+            camera.drag(delta);
+        }
+    }
+}
+
+/// First person camera: W/A/S/D move, Q/E go down and up, Shift runs and dragging with the
+/// left button looks around.
+#[derive(Debug)]
+pub struct FlyController {
+    speed: f32,
+    look_speed: f32,
+    keys: HashSet<Key>,
+    pointer: Pointer,
+}
+
+impl Default for FlyController {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl FlyController {
+    /// Move by `speed` world units per second.
+    pub fn new(speed: f32) -> Self {
+        Self {
+            speed,
+            look_speed: 0.005,
+            keys: HashSet::new(),
+            pointer: Pointer::default(),
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// Radians per pixel of pointer motion.
+    pub fn set_look_speed(&mut self, speed: f32) {
+        self.look_speed = speed;
+    }
+}
+
+impl CameraController for FlyController {
+    fn input(&mut self, camera: &mut Camera, event: &InputEvent) {
+        if let InputEvent::Key { key, pressed } = *event {
+            if pressed {
+                self.keys.insert(key);
+            } else {
+                self.keys.remove(&key);
+            }
+            return;
+        }
+
+        let Some(delta) = self.pointer.handle(event) else {
+            return;
+        };
+
+        if self.pointer.pressed(MouseButton::Left) {
+            let forward = turn(
+                camera.forward(),
+                camera.up(),
+                camera.right(),
+                delta.x * self.look_speed,
+                -delta.y * self.look_speed,
+            );
+            let distance = camera.distance().max(f32::EPSILON);
+            camera.set_target(camera.position() + forward * distance);
+        }
+    }
+
+    fn update(&mut self, camera: &mut Camera, dt: Duration) {
+        let axis = |positive: char, negative: char| {
+            let pressed = |c| self.keys.contains(&Key::Char(c)) as i32 as f32;
+            pressed(positive) - pressed(negative)
+        };
+
+        let direction = camera.forward() * axis('w', 's')
+            + camera.right() * axis('d', 'a')
+            + camera.up().normalize_or_zero() * axis('e', 'q');
+
+        let speed = if self.keys.contains(&Key::Shift) {
+            self.speed * 4.0
+        } else {
+            self.speed
+        };
+
+        camera.translate(direction.normalize_or_zero() * speed * dt.as_secs_f32());
+    }
+}
+
+/// 2D navigation: drag with any button to pan and scroll to zoom around the pointer.
+#[derive(Debug)]
+pub struct PanZoomController {
+    zoom_step: f32,
+    pointer: Pointer,
+}
+
+impl Default for PanZoomController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PanZoomController {
+    pub fn new() -> Self {
+        Self {
+            zoom_step: 0.9,
+            pointer: Pointer::default(),
+        }
+    }
+
+    /// Zoom factor of a scroll step.
+    pub fn set_zoom_step(&mut self, step: f32) {
+        self.zoom_step = step;
+    }
+}
+
+impl CameraController for PanZoomController {
+    fn input(&mut self, camera: &mut Camera, event: &InputEvent) {
+        if let InputEvent::Scroll { dy, .. } = *event {
+            let factor = self.zoom_step.powf(dy);
+            let anchor = match (camera.projection(), self.pointer.position) {
+                (Projection::Orthographic { .. }, Some(p)) => {
+                    let (width, height) = camera.size();
+                    let p = p - Vec2::new(width as f32, height as f32) / 2.0;
+                    (camera.right() * p.x - camera.view_up() * p.y) * camera.units_per_pixel()
+                }
+                _ => Vec3::ZERO,
+            };
+
+            // Keep the point under the pointer in place.
+            camera.zoom(factor);
+            camera.translate(anchor * (1.0 - factor));
+            return;
+        }
+
+        if let Some(delta) = self.pointer.handle(event) {
+            if !self.pointer.buttons.is_empty() {
+                camera.drag(delta);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Camera, CameraController, FlyController, Handedness, OrbitController, PanZoomController,
+    };
+    use crate::input::{InputEvent, Key, MouseButton};
+    use glam::{Mat4, Vec3, Vec4Swizzles};
+    use std::time::Duration;
+
+    #[test]
+    fn projections_follow_the_surface_size() {
+        let mut camera = Camera::perspective(45.0_f32.to_radians(), 1.0, 20.0);
+        camera.set_handedness(Handedness::Left);
+        camera.look_at(Vec3::new(0.0, 0.0, -5.0), Vec3::ZERO);
+        camera.resize(800, 600);
+
+        let expected = Mat4::perspective_lh(45.0_f32.to_radians(), 800.0 / 600.0, 1.0, 20.0)
+            * Mat4::from_translation(Vec3::new(0.0, 0.0, 5.0));
+        assert!(camera.view_projection().abs_diff_eq(expected, 1e-6));
+        assert_eq!(camera.right(), Vec3::X);
+
+        let mut camera = Camera::orthographic(4.0, 0.1, 10.0);
+        camera.resize(200, 100);
+        camera.resize(0, 100);
+        assert_eq!(camera.aspect(), 2.0);
+        assert_eq!(camera.right(), Vec3::X);
+        assert_eq!(camera.units_per_pixel(), 0.04);
+
+        let corner = camera.view_projection() * Vec3::new(4.0, 2.0, 0.0).extend(1.0);
+        assert!(corner.xy().abs_diff_eq(glam::Vec2::ONE, 1e-6));
+    }
+
+    #[test]
+    fn controllers_move_the_camera() {
+        let motion = |x, y| InputEvent::PointerMotion { x, y };
+        let left = |pressed| InputEvent::PointerButton {
+            button: MouseButton::Left,
+            pressed,
+        };
+
+        let mut camera = Camera::perspective(1.0, 0.1, 100.0);
+        camera.look_at(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO);
+
+        // Dragging to the right brings the camera to the left of the target.
+        let mut orbit = OrbitController::new();
+        for event in [motion(0.0, 0.0), left(true), motion(50.0, 0.0), left(false)] {
+            orbit.input(&mut camera, &event);
+        }
+        assert!((camera.distance() - 10.0).abs() < 1e-4);
+        assert!(camera.position().x < 0.0);
+        assert_eq!(camera.target(), Vec3::ZERO);
+        orbit.input(&mut camera, &InputEvent::Scroll { dx: 0.0, dy: 1.0 });
+        assert!((camera.distance() - 9.0).abs() < 1e-4);
+
+        // Going forward for a second.
+        let mut fly = FlyController::new(2.0);
+        let w = |pressed| InputEvent::Key {
+            key: Key::Char('w'),
+            pressed,
+        };
+        let start = camera.position();
+        fly.input(&mut camera, &w(true));
+        fly.update(&mut camera, Duration::from_secs(1));
+        fly.input(&mut camera, &w(false));
+        fly.update(&mut camera, Duration::from_secs(1));
+        assert!((camera.position() - start - camera.forward() * 2.0).length() < 1e-4);
+
+        // Zooming keeps the point under the pointer in place.
+        let mut camera = Camera::orthographic(10.0, -1.0, 1.0);
+        camera.resize(100, 100);
+        let mut pan_zoom = PanZoomController::new();
+        pan_zoom.input(&mut camera, &motion(75.0, 25.0));
+        let before = camera
+            .view_projection()
+            .inverse()
+            .project_point3(Vec3::new(0.5, 0.5, 0.0));
+        pan_zoom.input(&mut camera, &InputEvent::Scroll { dx: 0.0, dy: 2.0 });
+        let after = camera
+            .view_projection()
+            .inverse()
+            .project_point3(Vec3::new(0.5, 0.5, 0.0));
+        assert!(before.abs_diff_eq(after, 1e-5));
+        assert!(after.abs_diff_eq(Vec3::new(2.5, 2.5, after.z), 1e-5));
+
+        for event in [left(true), motion(85.0, 25.0), left(false)] {
+            pan_zoom.input(&mut camera, &event);
+        }
+        assert!((camera.position().x - -0.335).abs() < 1e-5);
+    }
+}
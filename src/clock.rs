@@ -0,0 +1,247 @@
+use super::error::OglError;
+use error_stack::{Report, Result};
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+/// Fastest playback speed accepted by FrameClock::set_speed().
+pub const MAX_SPEED: f64 = 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockMode {
+    /// Follow the wall clock.
+    RealTime,
+    /// Advance by the same step on every frame regardless of the wall clock.
+    FixedStep(Duration),
+    /// Advance only by what is passed to FrameClock::step().
+    Manual,
+}
+
+/// Clock seen by draw functions.
+///
+/// The clock is ticked once per frame and provides the frame index, the time passed since the
+/// previous frame (delta) and the total time since the clock is started. The speed can be
+/// changed for slow-motion playback and the clock can be paused.
+#[derive(Debug, Clone)]
+pub struct FrameClock {
+    mode: ClockMode,
+    speed: f64,
+    paused: bool,
+    started: bool,
+    frame: u64,
+    delta: Duration,
+    total: Duration,
+    origin: Duration,
+    last: Option<Instant>,
+    pending: Duration,
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        Self::new(ClockMode::RealTime)
+    }
+}
+
+impl FrameClock {
+    pub fn new(mode: ClockMode) -> Self {
+        Self {
+            mode,
+            speed: 1.0,
+            paused: false,
+            started: false,
+            frame: 0,
+            delta: Duration::ZERO,
+            total: Duration::ZERO,
+            origin: Duration::ZERO,
+            last: None,
+            pending: Duration::ZERO,
+        }
+    }
+
+    pub fn real_time() -> Self {
+        Self::new(ClockMode::RealTime)
+    }
+
+    pub fn fixed_step(step: Duration) -> Self {
+        Self::new(ClockMode::FixedStep(step))
+    }
+
+    pub fn manual() -> Self {
+        Self::new(ClockMode::Manual)
+    }
+
+    /// Restart from frame 0 and the time of the last seek (0 if none), keeping mode, speed and
+    /// pause state.
+    pub fn reset(&mut self) {
+        self.started = false;
+        self.frame = 0;
+        self.delta = Duration::ZERO;
+        self.total = self.origin;
+        self.last = None;
+        self.pending = Duration::ZERO;
+    }
+
+    /// Advance the clock for a new frame. It is called once before each frame is drawn.
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        let raw = match self.mode {
+            ClockMode::RealTime => self.last.map(|l| now - l).unwrap_or(Duration::ZERO),
+            ClockMode::FixedStep(step) => {
+                if self.started {
+                    step
+                } else {
+                    Duration::ZERO
+                }
+            }
+            ClockMode::Manual => std::mem::take(&mut self.pending),
+        };
+        self.last = Some(now);
+
+        if self.started {
+            self.frame += 1;
+        }
+        self.started = true;
+
+        self.delta = if self.paused {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(raw.as_secs_f64() * self.speed).unwrap_or(Duration::MAX)
+        };
+        self.total = self.total.saturating_add(self.delta);
+    }
+
+    /// Queue `d` to be added on the next tick in manual mode.
+    pub fn step(&mut self, d: Duration) {
+        self.pending += d;
+    }
+
+    /// Jump to the given total time without changing the frame index. reset() comes back to it.
+    pub fn seek(&mut self, total: Duration) {
+        self.total = total;
+        self.origin = total;
+    }
+
+    pub fn set_mode(&mut self, mode: ClockMode) {
+        self.mode = mode;
+        self.last = None;
+    }
+
+    pub fn mode(&self) -> ClockMode {
+        self.mode
+    }
+
+    /// Playback speed, 1.0 is normal, 0.5 is half speed. Speeds out of [0, MAX_SPEED] are
+    /// rejected.
+    pub fn set_speed(&mut self, speed: f64) -> Result<(), OglError> {
+        if !(0.0..=MAX_SPEED).contains(&speed) {
+            return Err(Report::new(OglError::InvalidData)
+                .attach_printable(format!("Speed {speed} is out of [0, {MAX_SPEED}]")));
+        }
+
+        self.speed = speed;
+        Ok(())
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Index of the current frame, the first frame is 0.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Time passed since the previous frame.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Time passed since the clock is started.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+}
+
+impl Display for FrameClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mode: {:?}, speed: {}, paused: {}, frame: {}, total: {:?}",
+            self.mode, self.speed, self.paused, self.frame, self.total
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameClock;
+    use std::time::Duration;
+
+    #[test]
+    fn fixed_step_with_speed_and_pause() {
+        let step = Duration::from_millis(10);
+        let mut clock = FrameClock::fixed_step(step);
+
+        clock.tick();
+        assert_eq!(clock.frame(), 0);
+        assert_eq!(clock.total(), Duration::ZERO);
+
+        clock.tick();
+        clock.tick();
+        assert_eq!(clock.frame(), 2);
+        assert_eq!(clock.delta(), step);
+        assert_eq!(clock.total(), step * 2);
+
+        clock.set_speed(0.5).unwrap();
+        assert!(clock.set_speed(f64::INFINITY).is_err());
+        assert!(clock.set_speed(f64::NAN).is_err());
+        assert!(clock.set_speed(-1.0).is_err());
+        clock.tick();
+        assert_eq!(clock.delta(), Duration::from_millis(5));
+
+        clock.pause();
+        clock.tick();
+        assert_eq!(clock.frame(), 4);
+        assert_eq!(clock.delta(), Duration::ZERO);
+        assert_eq!(clock.total(), Duration::from_millis(25));
+    }
+
+    #[test]
+    fn manual_step() {
+        let mut clock = FrameClock::manual();
+        clock.seek(Duration::from_secs(1));
+
+        clock.tick();
+        clock.tick();
+        assert_eq!(clock.total(), Duration::from_secs(1));
+
+        clock.step(Duration::from_millis(16));
+        clock.tick();
+        assert_eq!(clock.delta(), Duration::from_millis(16));
+        assert_eq!(clock.total(), Duration::from_millis(1016));
+
+        // A demo switch resets the clock, the pinned time stays.
+        clock.reset();
+        assert_eq!(clock.frame(), 0);
+        assert_eq!(clock.total(), Duration::from_secs(1));
+
+        clock.seek(Duration::ZERO);
+        clock.reset();
+        assert_eq!(clock.total(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,495 @@
+use super::clock::FrameClock;
+use super::error::OglError;
+use super::gl::GlState;
+use super::input::InputEvent;
+use super::screenshot::Screenshot;
+use super::uniform::{UniformValue, Uniforms};
+use error_stack::{Report, Result};
+use jlogger_tracing::{jerror, jinfo, jwarn};
+use std::ffi::CString;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static SWITCH: Mutex<Option<DemoSwitch>> = Mutex::new(None);
+
+/// Request to replace the running demo with another one of the registry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DemoSwitch {
+    Next,
+    Previous,
+    Id(usize),
+}
+
+pub struct RunState {}
+
+impl RunState {
+    pub fn global_run() {
+        RUNNING.store(true, Ordering::Relaxed);
+    }
+
+    pub fn global_stop() {
+        RUNNING.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_running() -> bool {
+        RUNNING.load(Ordering::Relaxed)
+    }
+
+    /// Ask the run loop to switch to another demo before the next frame.
+    pub fn request_switch(switch: DemoSwitch) {
+        *SWITCH.lock().unwrap() = Some(switch);
+    }
+
+    pub fn take_switch() -> Option<DemoSwitch> {
+        SWITCH.lock().unwrap().take()
+    }
+
+    pub fn switch_pending() -> bool {
+        SWITCH.lock().unwrap().is_some()
+    }
+}
+
+/// Operations provided by a window system backend.
+pub trait DrawContextOps {
+    fn do_dispatch(&mut self) -> Result<(), OglError>;
+    fn do_swap(&self) -> Result<(), OglError>;
+
+    /// Next input event received by the last do_dispatch(), if any.
+    fn do_poll_input(&mut self) -> Option<InputEvent> {
+        None
+    }
+
+    /// New size of the surface if the last do_dispatch() changed it.
+    fn do_poll_resize(&mut self) -> Option<(i32, i32)> {
+        None
+    }
+}
+
+/// A drawing exercise driven by DrawContext.
+///
+/// init() is called once before the first frame, draw() on every frame, resize() when the
+/// size of the surface changes, input() for every input event received before a frame and
+/// teardown() when the demo is not used anymore.
+pub trait Demo {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError>;
+
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError>;
+
+    fn resize(&mut self, _df: &mut DrawContext, _width: i32, _height: i32) -> Result<(), OglError> {
+        Ok(())
+    }
+
+    fn input(&mut self, _df: &mut DrawContext, _event: &InputEvent) -> Result<(), OglError> {
+        Ok(())
+    }
+
+    fn teardown(&mut self, _df: &mut DrawContext) -> Result<(), OglError> {
+        Ok(())
+    }
+}
+
+pub type DemoFactory = Box<dyn Fn() -> Box<dyn Demo>>;
+
+pub struct DemoEntry {
+    id: usize,
+    name: String,
+    factory: DemoFactory,
+}
+
+impl DemoEntry {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn create(&self) -> Box<dyn Demo> {
+        (self.factory)()
+    }
+}
+
+impl std::fmt::Display for DemoEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:3} {}", self.id, self.name)
+    }
+}
+
+/// List of demos. Ids are assigned from 1 in the order of registration.
+#[derive(Default)]
+pub struct DemoRegistry {
+    entries: Vec<DemoEntry>,
+}
+
+impl DemoRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a demo named after its type and return the id assigned to it.
+    pub fn register<T: Demo + Default + 'static>(&mut self) -> usize {
+        let name = std::any::type_name::<T>()
+            .rsplit("::")
+            .next()
+            .unwrap_or_default()
+            .to_owned();
+
+        self.register_with(&name, || Box::new(T::default()))
+    }
+
+    /// Register a demo created by `factory` with the given name and return the id assigned to it.
+    pub fn register_with<F>(&mut self, name: &str, factory: F) -> usize
+    where
+        F: Fn() -> Box<dyn Demo> + 'static,
+    {
+        let id = self.entries.len() + 1;
+        self.entries.push(DemoEntry {
+            id,
+            name: name.to_owned(),
+            factory: Box::new(factory),
+        });
+
+        id
+    }
+
+    pub fn get(&self, id: usize) -> Option<&DemoEntry> {
+        id.checked_sub(1).and_then(|i| self.entries.get(i))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&DemoEntry> {
+        self.entries.iter().find(|a| a.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DemoEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Id of the demo selected by `switch` when `current` is running, wrapping around at both
+    /// ends. None if the requested id does not exist.
+    pub fn switch_id(&self, current: usize, switch: DemoSwitch) -> Option<usize> {
+        let len = self.entries.len();
+        if len == 0 {
+            return None;
+        }
+
+        match switch {
+            DemoSwitch::Next => Some(current % len + 1),
+            DemoSwitch::Previous => Some((current + len - 2) % len + 1),
+            DemoSwitch::Id(id) => self.get(id).map(|a| a.id),
+        }
+    }
+}
+
+pub struct DrawContext {
+    width: i32,
+    height: i32,
+    resized: bool,
+    gl: GlState,
+    screenshot: Option<(PathBuf, usize)>,
+    clock: FrameClock,
+}
+
+impl DrawContext {
+    pub fn new(gl: GlState, width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            resized: false,
+            gl,
+            screenshot: None,
+            clock: FrameClock::default(),
+        }
+    }
+
+    pub fn gl(&self) -> &GlState {
+        &self.gl
+    }
+
+    pub fn gl_mut(&mut self) -> &mut GlState {
+        &mut self.gl
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+
+    /// Change the size of the surface, the running demo is notified before the next frame.
+    pub fn resize(&mut self, width: i32, height: i32) {
+        if width > 0 && height > 0 && (width != self.width || height != self.height) {
+            self.width = width;
+            self.height = height;
+            self.resized = true;
+        }
+    }
+
+    pub fn set_clock(&mut self, clock: FrameClock) {
+        self.clock = clock;
+    }
+
+    /// Clock of the frame being drawn.
+    pub fn clock(&self) -> &FrameClock {
+        &self.clock
+    }
+
+    pub fn clock_mut(&mut self) -> &mut FrameClock {
+        &mut self.clock
+    }
+
+    /// Save the frame `at_frame` (counted from 1) to `path` as PNG and stop running.
+    pub fn set_screenshot(&mut self, path: &str, at_frame: usize) {
+        self.screenshot = Some((PathBuf::from(path), at_frame.max(1)));
+    }
+
+    /// Read back what has been drawn to the current framebuffer.
+    pub fn screenshot(&self) -> Result<Screenshot, OglError> {
+        self.gl.read_pixels(0, 0, self.width, self.height)
+    }
+
+    /// Run a single demo until stopped from the start of the clock, switch requests are ignored.
+    pub fn run(
+        &mut self,
+        ops: &mut dyn DrawContextOps,
+        demo: &mut dyn Demo,
+    ) -> Result<(), OglError> {
+        RunState::global_run();
+        self.clock.reset();
+
+        let mut result = demo.init(self);
+        while result.is_ok() && RunState::is_running() {
+            result = self.run_loop(ops, demo);
+            if result.is_err() || RunState::take_switch().is_none() {
+                break;
+            }
+        }
+        demo.teardown(self)?;
+        self.gl.release();
+
+        result
+    }
+
+    /// Run the demo `id` of `registry` until stopped and switch demos on request.
+    ///
+    /// The resources of the running demo are released before the next one is initialized. A
+    /// demo failing to initialize after a switch is skipped.
+    pub fn run_registry(
+        &mut self,
+        ops: &mut dyn DrawContextOps,
+        registry: &DemoRegistry,
+        id: usize,
+    ) -> Result<(), OglError> {
+        let mut id = id;
+        let mut previous: Option<(usize, DemoSwitch)> = None;
+        let mut failures = 0;
+
+        RunState::global_run();
+
+        while RunState::is_running() {
+            let entry = registry.get(id).ok_or(
+                Report::new(OglError::InvalidData).attach_printable(format!("No demo {id}")),
+            )?;
+
+            jinfo!("Running {}", entry.name());
+            let mut demo = entry.create();
+            if previous.is_some() {
+                self.clock.reset();
+            }
+
+            let result = demo.init(self).and_then(|_| loop {
+                self.run_loop(ops, demo.as_mut())?;
+                let Some(switch) = RunState::take_switch() else {
+                    break Ok(None);
+                };
+
+                // The running demo goes on if there is nothing to switch to.
+                match registry.switch_id(id, switch) {
+                    Some(next) => break Ok(Some((switch, next))),
+                    None => jwarn!("No demo for {:?}", switch),
+                }
+            });
+            demo.teardown(self)?;
+            self.gl.release();
+
+            let next = match (result, previous) {
+                (Ok(next), _) => {
+                    failures = 0;
+                    next
+                }
+                (Err(e), Some((from, switch))) if failures < registry.len() => {
+                    jerror!("{} failed: {:?}", entry.name(), e);
+                    failures += 1;
+
+                    // Keep going in the same direction, or go back to where we came from.
+                    let switch = match switch {
+                        DemoSwitch::Id(_) => DemoSwitch::Id(from),
+                        _ => switch,
+                    };
+                    registry.switch_id(id, switch).map(|next| (switch, next))
+                }
+                (Err(e), _) => return Err(e),
+            };
+
+            let Some((switch, next)) = next else {
+                break;
+            };
+
+            previous = Some((id, switch));
+            id = next;
+        }
+
+        Ok(())
+    }
+
+    fn run_loop(
+        &mut self,
+        ops: &mut dyn DrawContextOps,
+        demo: &mut dyn Demo,
+    ) -> Result<(), OglError> {
+        while RunState::is_running() && !RunState::switch_pending() {
+            ops.do_dispatch()?;
+            if let Some((width, height)) = ops.do_poll_resize() {
+                self.resize(width, height);
+            }
+            self.clock.tick();
+            self.gl.reload();
+
+            if self.resized {
+                self.resized = false;
+                let (width, height) = (self.width, self.height);
+                demo.resize(self, width, height)?;
+            }
+
+            while let Some(event) = ops.do_poll_input() {
+                demo.input(self, &event)?;
+            }
+
+            demo.draw(self)?;
+
+            let frame = self.clock.frame() as usize + 1;
+            if let Some((path, at_frame)) = self.screenshot.as_ref() {
+                if frame == *at_frame {
+                    self.screenshot()?.save_png(path)?;
+                    jinfo!("Frame {} saved to {}", frame, path.display());
+                    RunState::global_stop();
+                }
+            }
+
+            ops.do_swap()?;
+        }
+
+        Ok(())
+    }
+
+    /// Location of the uniform `name`. Elements of arrays such as "name[2]" are queried from GL.
+    pub fn location(&self, name: &str) -> Option<i32> {
+        let program = self.gl.linked_program()?;
+        if let Some(uniform) = program.uniform(name) {
+            return Some(uniform.location());
+        }
+
+        let program = program.id();
+        let gl = self.gl.gl();
+        let name = CString::new(name).ok()?;
+
+        let location = unsafe { gl.GetUniformLocation(program, name.as_ptr().cast()) };
+
+        if location >= 0 {
+            Some(location)
+        } else {
+            None
+        }
+    }
+
+    /// Same as location() but fails if the uniform does not exist.
+    pub fn uniform_location(&self, name: &str) -> Result<i32, OglError> {
+        self.location(name).ok_or(
+            Report::new(OglError::UnknownUniform).attach_printable(format!("No uniform {name}")),
+        )
+    }
+
+    /// Set `value` to the uniform `name` of the current program, see Program::set_uniform().
+    pub fn set_uniform<T: UniformValue>(&self, name: &str, value: T) -> Result<(), OglError> {
+        self.gl.set_uniform(name, value)
+    }
+
+    pub fn set_uniforms<T: Uniforms>(&self, uniforms: &T) -> Result<(), OglError> {
+        self.gl.set_uniforms(uniforms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Demo, DemoRegistry, DemoSwitch, DrawContext, OglError};
+    use error_stack::Result;
+
+    #[derive(Default)]
+    struct First;
+
+    #[derive(Default)]
+    struct Second;
+
+    impl Demo for First {
+        fn init(&mut self, _df: &mut DrawContext) -> Result<(), OglError> {
+            Ok(())
+        }
+
+        fn draw(&mut self, _df: &mut DrawContext) -> Result<(), OglError> {
+            Ok(())
+        }
+    }
+
+    impl Demo for Second {
+        fn init(&mut self, _df: &mut DrawContext) -> Result<(), OglError> {
+            Ok(())
+        }
+
+        fn draw(&mut self, _df: &mut DrawContext) -> Result<(), OglError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn registry_assigns_ids_and_names() {
+        let mut registry = DemoRegistry::new();
+        assert_eq!(registry.register::<First>(), 1);
+        assert_eq!(registry.register::<Second>(), 2);
+        assert_eq!(registry.register_with("Custom", || Box::new(First)), 3);
+
+        assert_eq!(registry.get(2).unwrap().name(), "Second");
+        assert_eq!(registry.find("Custom").unwrap().id(), 3);
+        assert!(registry.get(0).is_none());
+        assert!(registry.get(4).is_none());
+    }
+
+    #[test]
+    fn registry_switch_wraps_around() {
+        let mut registry = DemoRegistry::new();
+        registry.register::<First>();
+        registry.register::<Second>();
+        registry.register::<First>();
+
+        assert_eq!(registry.switch_id(3, DemoSwitch::Next), Some(1));
+        assert_eq!(registry.switch_id(1, DemoSwitch::Previous), Some(3));
+        assert_eq!(registry.switch_id(1, DemoSwitch::Next), Some(2));
+        assert_eq!(registry.switch_id(1, DemoSwitch::Id(3)), Some(3));
+        assert_eq!(registry.switch_id(1, DemoSwitch::Id(4)), None);
+    }
+}
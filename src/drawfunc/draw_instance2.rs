@@ -1,14 +1,22 @@
-use super::{DrawContext, DrawFunc};
 use error_stack::{Report, Result};
 use jlogger_tracing::jinfo;
+use libogl::buffer::{Buffer, VertexArray};
+use libogl::demo::{Demo, DrawContext};
 use libogl::error::OglError;
-use libogl::VertexOps;
+use libogl::glapi;
 use std::f32::consts::PI;
 
-pub fn draw_instance2(df: &mut DrawContext) -> Result<(), OglError> {
-    static mut VERTICES_NUMBER: usize = 0;
+#[derive(Default)]
+pub struct DrawInstance2 {
+    vertices: Buffer<f32>,
+    color: Buffer<f32>,
+    indices: Buffer<u16>,
+    vao: VertexArray,
+    vertices_number: usize,
+}
 
-    if !df.initialized || df.draw_func != DrawFunc::DrawInstance2 {
+impl Demo for DrawInstance2 {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
         // Use "gl_InstanceID" to specify the instance dependent offset.
         let v_src = r#"
                 #version 300 es
@@ -38,14 +46,11 @@ pub fn draw_instance2(df: &mut DrawContext) -> Result<(), OglError> {
                 }
         "#;
 
-        df.gl.build(Some(v_src), Some(f_src))?;
-
-        df.initialized = true;
-        df.draw_func = DrawFunc::DrawInstance2;
+        df.gl_mut().build(Some(v_src), Some(f_src))?;
 
         unsafe {
-            let gl = df.gl.gl();
-            let program = df.gl.program().unwrap();
+            let gl = df.gl().gl();
+            let program = df.gl().program().unwrap();
             gl.UseProgram(program);
 
             // Set Uniform uOffset which is used to set the offset of the instances.
@@ -97,24 +102,16 @@ pub fn draw_instance2(df: &mut DrawContext) -> Result<(), OglError> {
                 vertices.push(0f32);
             }
 
-            VERTICES_NUMBER = vertices.len() / 3;
+            self.vertices_number = vertices.len() / 3;
 
             // Create VBO for vertex and color
-            gl.GenBuffers(4, &mut df.vbo as *mut u32);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
-
-            let vertices_u8 = vertices.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                vertices_u8.len() as isize,
-                vertices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
+            self.vertices =
+                Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &vertices, glapi::GL_STATIC_DRAW);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
 
             let mut color = vec![];
 
-            for i in 0..VERTICES_NUMBER {
+            for i in 0..self.vertices_number {
                 let unit = (i as f32) * PI / 180f32;
                 let rc = r * f32::cos(unit);
                 let gc = r * f32::sin(unit);
@@ -133,80 +130,73 @@ pub fn draw_instance2(df: &mut DrawContext) -> Result<(), OglError> {
 
             assert_eq!(vertices.len(), color.len());
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[1]);
-
-            let color_u8 = color.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                color_u8.len() as isize,
-                color_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
+            self.color =
+                Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &color, glapi::GL_STATIC_DRAW);
 
             // Create VBO for element indices.
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, df.vbo[2]);
-
             let mut indices = vec![];
-            for i in 0..VERTICES_NUMBER {
+            for i in 0..self.vertices_number {
                 indices.push(i as u16);
             }
 
-            let indices_u8 = indices.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ELEMENT_ARRAY_BUFFER,
-                indices_u8.len() as isize,
-                indices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
+            self.indices = Buffer::with_data(
+                gl,
+                glapi::GL_ELEMENT_ARRAY_BUFFER,
+                &indices,
+                glapi::GL_STATIC_DRAW,
             );
 
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, 0);
 
             // Create VAO to handle VBO
-            let mut vao = 0_u32;
-            gl.GenVertexArrays(1, &mut vao as *mut u32);
+            let vao = VertexArray::new(gl);
 
-            gl.BindVertexArray(vao);
+            gl.BindVertexArray(vao.id());
 
             gl.EnableVertexAttribArray(0);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
-            gl.VertexAttribPointer(0, 3, gl33::GL_FLOAT, 0, 0, core::ptr::null_mut());
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.vertices.id());
+            gl.VertexAttribPointer(0, 3, glapi::GL_FLOAT, 0, 0, core::ptr::null_mut());
 
             gl.EnableVertexAttribArray(1);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[1]);
-            gl.VertexAttribPointer(1, 3, gl33::GL_FLOAT, 0, 0, core::ptr::null_mut());
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.color.id());
+            gl.VertexAttribPointer(1, 3, glapi::GL_FLOAT, 0, 0, core::ptr::null_mut());
 
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, df.vbo[2]);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, self.indices.id());
 
             gl.BindVertexArray(0);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, 0);
 
-            df.vao = Some(vao);
+            self.vao = vao;
         }
+
+        Ok(())
     }
 
-    unsafe {
-        let gl = df.gl.gl();
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        unsafe {
+            let gl = df.gl().gl();
 
-        gl.Viewport(0, 0, df.width, df.height);
+            gl.Viewport(0, 0, df.width(), df.height());
 
-        gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
-        gl.Clear(gl33::GL_COLOR_BUFFER_BIT);
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT);
 
-        // Enable VBO and corresponding through VAO
-        let vao = df.vao.unwrap();
-        gl.BindVertexArray(vao);
+            // Enable VBO and corresponding through VAO
+            let vao = self.vao.id();
+            gl.BindVertexArray(vao);
 
-        gl.DrawElementsInstanced(
-            gl33::GL_TRIANGLE_FAN,
-            VERTICES_NUMBER as i32,
-            gl33::GL_UNSIGNED_SHORT,
-            core::ptr::null_mut(),
-            5,
-        );
+            gl.DrawElementsInstanced(
+                glapi::GL_TRIANGLE_FAN,
+                self.vertices_number as i32,
+                glapi::GL_UNSIGNED_SHORT,
+                core::ptr::null_mut(),
+                5,
+            );
 
-        gl.BindVertexArray(0);
-        gl.Flush();
+            gl.BindVertexArray(0);
+            gl.Flush();
+        }
+        Ok(())
     }
-    Ok(())
 }
@@ -0,0 +1,135 @@
+use error_stack::Result;
+use glam::{Mat4, Quat, Vec3};
+use libogl::camera::{Camera, CameraController, FlyController};
+use libogl::demo::{Demo, DrawContext};
+use libogl::error::OglError;
+use libogl::glapi;
+use libogl::input::InputEvent;
+use libogl::mesh::{GpuMesh, Mesh};
+use libogl::uniform::Uniforms;
+
+#[derive(Uniforms)]
+struct ShapeUniforms {
+    #[uniform = "u_model"]
+    model: Mat4,
+    #[uniform = "u_viewProjection"]
+    view_projection: Mat4,
+    #[uniform = "u_color"]
+    color: Vec3,
+}
+
+/// The shapes of libogl::mesh in a 3x3 grid, lit by a directional light. The camera flies
+/// with W/A/S/D/Q/E and looks around while dragging.
+#[derive(Default)]
+pub struct DrawMeshes {
+    shapes: Vec<(GpuMesh, Vec3)>,
+    camera: Camera,
+    controller: FlyController,
+}
+
+impl Demo for DrawMeshes {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        let v_src = r#"
+                #version 300 es
+                layout(location = 0) in vec3 vPosition;
+                layout(location = 1) in vec3 vNormal;
+
+                uniform mat4 u_model;
+                uniform mat4 u_viewProjection;
+
+                out vec3 vWorldNormal;
+
+                void main()
+                {
+                    gl_Position = u_viewProjection * u_model * vec4(vPosition, 1.0);
+                    vWorldNormal = mat3(u_model) * vNormal;
+                }
+        "#;
+
+        let f_src = r#"
+                #version 300 es
+                precision mediump float;
+
+                uniform vec3 u_color;
+
+                in vec3 vWorldNormal;
+                out vec4 fragColor;
+
+                void main()
+                {
+                    vec3 light = normalize(vec3(0.5, 0.8, 1.0));
+                    float diffuse = abs(dot(normalize(vWorldNormal), light));
+                    fragColor = vec4(u_color * (0.25 + 0.75 * diffuse), 1.0);
+                }
+        "#;
+
+        df.gl_mut().build(Some(v_src), Some(f_src))?;
+
+        let gl = df.gl().gl();
+        self.shapes = [
+            (Mesh::plane(1.4), Vec3::new(0.9, 0.3, 0.3)),
+            (Mesh::cube(1.1), Vec3::new(0.3, 0.9, 0.3)),
+            (Mesh::uv_sphere(0.8, 32, 16), Vec3::new(0.3, 0.5, 0.9)),
+            (Mesh::icosphere(0.8, 2), Vec3::new(0.9, 0.9, 0.3)),
+            (Mesh::cylinder(0.6, 1.3, 32), Vec3::new(0.9, 0.3, 0.9)),
+            (Mesh::cone(0.7, 1.3, 32), Vec3::new(0.3, 0.9, 0.9)),
+            (Mesh::torus(0.6, 0.25, 32, 16), Vec3::new(0.9, 0.6, 0.3)),
+            (Mesh::disc(0.8, 32), Vec3::new(0.6, 0.3, 0.9)),
+            (Mesh::grid(1.4, 1.4, 4, 4), Vec3::new(0.8, 0.8, 0.8)),
+        ]
+        .into_iter()
+        .map(|(mesh, color)| (mesh.upload(gl), color))
+        .collect();
+
+        self.camera = Camera::perspective(45.0_f32.to_radians(), 0.1, 50.0);
+        self.camera.look_at(Vec3::new(0.0, 0.0, 9.0), Vec3::ZERO);
+        self.camera.resize(df.width(), df.height());
+        self.controller = FlyController::new(3.0);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        let gl = df.gl().gl();
+        unsafe {
+            gl.Viewport(0, 0, df.width(), df.height());
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT | glapi::GL_DEPTH_BUFFER_BIT);
+            gl.Enable(glapi::GL_DEPTH_TEST);
+        }
+
+        self.controller.update(&mut self.camera, df.clock().delta());
+        let view_projection = self.camera.view_projection();
+
+        let angle = df.clock().total().as_secs_f32();
+        let rotation = Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0).normalize(), angle);
+
+        for (i, (mesh, color)) in self.shapes.iter().enumerate() {
+            let x = (i % 3) as f32 - 1.0;
+            let y = 1.0 - (i / 3) as f32;
+            df.set_uniforms(&ShapeUniforms {
+                model: Mat4::from_rotation_translation(rotation, Vec3::new(x, y, 0.0) * 2.2),
+                view_projection,
+                color: *color,
+            })?;
+            mesh.draw();
+        }
+
+        unsafe {
+            gl.Disable(glapi::GL_DEPTH_TEST);
+            gl.Flush();
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, _df: &mut DrawContext, width: i32, height: i32) -> Result<(), OglError> {
+        self.camera.resize(width, height);
+        Ok(())
+    }
+
+    fn input(&mut self, _df: &mut DrawContext, event: &InputEvent) -> Result<(), OglError> {
+        self.controller.input(&mut self.camera, event);
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+use error_stack::{Report, Result};
+use glam::{Mat4, Vec3};
+use libogl::camera::{Camera, CameraController, OrbitController};
+use libogl::demo::{Demo, DrawContext};
+use libogl::error::OglError;
+use libogl::glapi;
+use libogl::gltf::{GltfMaterial, GltfModel, GpuGltfModel};
+use libogl::input::InputEvent;
+use libogl::mesh::GpuMesh;
+use libogl::obj::{GpuObjModel, ObjMaterial, ObjModel};
+use libogl::texture2d::Texture2D;
+use libogl::uniform::Uniforms;
+use libogl::{ply, stl};
+use std::path::PathBuf;
+
+#[derive(Uniforms)]
+struct CameraUniforms {
+    #[uniform = "u_viewProjection"]
+    view_projection: Mat4,
+    #[uniform = "u_eye"]
+    eye: Vec3,
+}
+
+/// Material of either format mapped to a simple Blinn-Phong model.
+#[derive(Uniforms)]
+struct MaterialUniforms {
+    #[uniform = "u_model"]
+    model: Mat4,
+    #[uniform = "u_emissive"]
+    emissive: Vec3,
+    #[uniform = "u_diffuse"]
+    diffuse: Vec3,
+    #[uniform = "u_specular"]
+    specular: Vec3,
+    #[uniform = "u_shininess"]
+    shininess: f32,
+    #[uniform = "u_useTexture"]
+    use_texture: bool,
+}
+
+impl MaterialUniforms {
+    fn obj(material: Option<&ObjMaterial>, texture: Option<&Texture2D>) -> Self {
+        match material {
+            Some(m) => Self {
+                model: Mat4::IDENTITY,
+                emissive: m.ambient() * 0.1,
+                diffuse: m.diffuse(),
+                specular: m.specular(),
+                shininess: m.shininess(),
+                use_texture: texture.is_some(),
+            },
+            None => Self::obj(Some(&ObjMaterial::new("")), None),
+        }
+    }
+
+    fn gltf(model: Mat4, material: Option<&GltfMaterial>, texture: Option<&Texture2D>) -> Self {
+        let default = GltfMaterial::default();
+        let m = material.unwrap_or(&default);
+        let base = m.base_color().truncate();
+        let roughness = m.roughness().clamp(0.05, 1.0);
+
+        Self {
+            model,
+            emissive: m.emissive(),
+            diffuse: base * (1.0 - m.metallic()),
+            specular: Vec3::splat(0.04).lerp(base, m.metallic()) * (1.0 - roughness),
+            shininess: (2.0 / roughness.powi(4) - 2.0).clamp(1.0, 256.0),
+            use_texture: texture.is_some(),
+        }
+    }
+}
+
+enum Model {
+    Obj(GpuObjModel),
+    Gltf(GpuGltfModel),
+    Mesh(GpuMesh),
+}
+
+/// OBJ, glTF, STL or PLY model given by --model, seen from a camera orbiting around it. The
+/// camera turns by itself until it is moved with the pointer.
+pub struct DrawModel {
+    path: PathBuf,
+    model: Option<Model>,
+    camera: Camera,
+    controller: OrbitController,
+    spinning: bool,
+}
+
+impl DrawModel {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            model: None,
+            camera: Camera::default(),
+            controller: OrbitController::new(),
+            spinning: true,
+        }
+    }
+}
+
+impl Demo for DrawModel {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        let v_src = r#"
+                #version 300 es
+                layout(location = 0) in vec3 vPosition;
+                layout(location = 1) in vec3 vNormal;
+                layout(location = 2) in vec2 vTextureCoord;
+                layout(location = 4) in vec4 vColor;
+
+                uniform mat4 u_model;
+                uniform mat4 u_viewProjection;
+
+                out vec3 vWorldPosition;
+                out vec3 vWorldNormal;
+                out vec2 vUv;
+                out vec3 vVertexColor;
+
+                void main()
+                {
+                    vec4 position = u_model * vec4(vPosition, 1.0);
+                    gl_Position = u_viewProjection * position;
+                    vWorldPosition = position.xyz;
+                    vWorldNormal = transpose(inverse(mat3(u_model))) * vNormal;
+                    vUv = vTextureCoord;
+                    vVertexColor = vColor.rgb;
+                }
+        "#;
+
+        let f_src = r#"
+                #version 300 es
+                precision mediump float;
+
+                uniform vec3 u_eye;
+                uniform vec3 u_emissive;
+                uniform vec3 u_diffuse;
+                uniform vec3 u_specular;
+                uniform float u_shininess;
+                uniform bool u_useTexture;
+                uniform sampler2D u_texture;
+
+                in vec3 vWorldPosition;
+                in vec3 vWorldNormal;
+                in vec2 vUv;
+                in vec3 vVertexColor;
+                out vec4 fragColor;
+
+                void main()
+                {
+                    vec3 n = normalize(vWorldNormal);
+                    vec3 v = normalize(u_eye - vWorldPosition);
+                    vec3 l = normalize(v + vec3(0.3, 0.6, 0.0));
+                    if (dot(n, v) < 0.0) {
+                        n = -n;
+                    }
+
+                    vec3 albedo = u_diffuse * vVertexColor;
+                    if (u_useTexture) {
+                        albedo *= texture(u_texture, vUv).rgb;
+                    }
+
+                    float diffuse = max(dot(n, l), 0.0);
+                    float specular = pow(max(dot(n, normalize(l + v)), 0.0), max(u_shininess, 1.0));
+                    vec3 color = albedo * (0.2 + 0.8 * diffuse) + u_emissive
+                        + u_specular * specular;
+                    fragColor = vec4(color, 1.0);
+                }
+        "#;
+
+        df.gl_mut().build(Some(v_src), Some(f_src))?;
+
+        let gl = df.gl().gl();
+        let extension = self
+            .path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase());
+
+        let (model, positions): (Model, Vec<Vec3>) = match extension.as_deref() {
+            Some("gltf" | "glb") => {
+                let model = GltfModel::load(&self.path)?;
+                let positions = model
+                    .instances()
+                    .iter()
+                    .flat_map(|(mesh, transform)| {
+                        model.meshes()[*mesh]
+                            .primitives()
+                            .iter()
+                            .flat_map(|p| p.mesh().vertices())
+                            .map(|v| transform.transform_point3(v.position))
+                    })
+                    .collect();
+                (Model::Gltf(model.upload(gl)), positions)
+            }
+            Some("stl" | "ply") => {
+                let mesh = match extension.as_deref() {
+                    Some("stl") => stl::load(&self.path)?,
+                    _ => ply::load(&self.path)?,
+                };
+                let positions = mesh.vertices().iter().map(|v| v.position).collect();
+                (Model::Mesh(mesh.upload(gl)), positions)
+            }
+            _ => {
+                let model = ObjModel::load(&self.path)?;
+                let positions = model.mesh().vertices().iter().map(|v| v.position).collect();
+                (Model::Obj(model.upload(gl)), positions)
+            }
+        };
+
+        if positions.is_empty() {
+            return Err(Report::new(OglError::InvalidData)
+                .attach_printable(format!("{} has nothing to draw", self.path.display())));
+        }
+
+        let min = positions.iter().fold(Vec3::MAX, |m, p| m.min(*p));
+        let max = positions.iter().fold(Vec3::MIN, |m, p| m.max(*p));
+        let radius = ((max - min).length() / 2.0).max(f32::EPSILON);
+        let fov = 45.0_f32.to_radians();
+        let distance = radius / (fov / 2.0).sin() * 1.1;
+
+        // The far plane leaves room to zoom out.
+        self.camera = Camera::perspective(fov, distance * 0.01, (distance + radius) * 4.0);
+        self.camera
+            .look_at((min + max) / 2.0 + Vec3::Z * distance, (min + max) / 2.0);
+        self.camera.resize(df.width(), df.height());
+        self.model = Some(model);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        let gl = df.gl().gl();
+        unsafe {
+            gl.Viewport(0, 0, df.width(), df.height());
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT | glapi::GL_DEPTH_BUFFER_BIT);
+            gl.Enable(glapi::GL_DEPTH_TEST);
+        }
+
+        if self.spinning {
+            let angle = df.clock().total().as_secs_f32() * 0.5;
+            let elevation = 25.0_f32.to_radians();
+            let target = self.camera.target();
+            let direction = Vec3::new(
+                elevation.cos() * angle.sin(),
+                elevation.sin(),
+                elevation.cos() * angle.cos(),
+            );
+            self.camera
+                .set_position(target + direction * self.camera.distance());
+        }
+
+        df.set_uniforms(&CameraUniforms {
+            view_projection: self.camera.view_projection(),
+            eye: self.camera.position(),
+        })?;
+
+        let location = df.location("u_texture").unwrap_or(-1);
+        let bind = |uniforms: MaterialUniforms, texture: Option<&Texture2D>| {
+            df.set_uniforms(&uniforms)?;
+            match texture {
+                Some(texture) => unsafe { texture.bind(gl, 0, location) },
+                None => Ok(()),
+            }
+        };
+
+        match &self.model {
+            Some(Model::Obj(model)) => model.draw(|material, texture| {
+                bind(MaterialUniforms::obj(material, texture), texture)
+            })?,
+            Some(Model::Gltf(model)) => model.draw(|transform, material| {
+                let texture = model.texture(material.and_then(|m| m.base_color_texture()));
+                bind(
+                    MaterialUniforms::gltf(*transform, material, texture),
+                    texture,
+                )
+            })?,
+            Some(Model::Mesh(mesh)) => {
+                bind(MaterialUniforms::obj(None, None), None)?;
+                mesh.draw();
+            }
+            None => {}
+        }
+
+        unsafe {
+            gl.Disable(glapi::GL_DEPTH_TEST);
+            gl.Flush();
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, _df: &mut DrawContext, width: i32, height: i32) -> Result<(), OglError> {
+        self.camera.resize(width, height);
+        Ok(())
+    }
+
+    fn input(&mut self, _df: &mut DrawContext, event: &InputEvent) -> Result<(), OglError> {
+        self.controller.input(&mut self.camera, event);
+        if self.controller.is_dragging() || matches!(event, InputEvent::Scroll { .. }) {
+            self.spinning = false;
+        }
+        Ok(())
+    }
+
+    fn teardown(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        let gl = df.gl().gl();
+        match &mut self.model {
+            Some(Model::Obj(model)) => model.delete(gl),
+            Some(Model::Gltf(model)) => model.delete(gl),
+            Some(Model::Mesh(_)) | None => {}
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,22 @@
-use super::{DrawContext, DrawFunc};
 use error_stack::Result;
 use jlogger_tracing::jdebug;
+use libogl::buffer::{Buffer, VertexArray};
+use libogl::demo::{Demo, DrawContext};
 use libogl::error::OglError;
-use libogl::texture2d::Texture2DFilter;
-use libogl::VertexOps;
+use libogl::glapi;
+use libogl::texture2d::{Texture2D, Texture2DFilter};
+
+#[derive(Default)]
+pub struct DrawTexture {
+    vertices: Buffer<f32>,
+    texture_vertex: Buffer<f32>,
+    indices: Buffer<u16>,
+    vao: VertexArray,
+    texture: Texture2D,
+}
 
-pub fn draw_texture(df: &mut DrawContext) -> Result<(), OglError> {
-    if !df.initialized || df.draw_func != DrawFunc::DrawTexture {
+impl Demo for DrawTexture {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
         let v_src = r#"
                 #version 300 es
                 layout(location = 0) in vec4 vPosition;
@@ -36,18 +46,16 @@ pub fn draw_texture(df: &mut DrawContext) -> Result<(), OglError> {
                 }
         "#;
 
-        df.gl.build(Some(v_src), Some(f_src))?;
-
-        df.initialized = true;
-        df.draw_func = DrawFunc::DrawTexture;
+        df.gl_mut().build(Some(v_src), Some(f_src))?;
 
         unsafe {
-            let gl = df.gl.gl();
-            let program = df.gl.program().unwrap();
+            let gl = df.gl().gl();
+            let program = df.gl().program().unwrap();
 
             let data = include_bytes!("../../doc/sample.png");
-            df.texture[0].create_from_buffer(data, gl, Texture2DFilter::Linear)?;
-            jdebug!("texture: {}", df.texture[0]);
+            self.texture
+                .create_from_buffer(data, gl, Texture2DFilter::Linear)?;
+            jdebug!("texture: {}", self.texture);
 
             gl.UseProgram(program);
 
@@ -60,18 +68,9 @@ pub fn draw_texture(df: &mut DrawContext) -> Result<(), OglError> {
             ];
 
             // Create VBO for vertex and color
-            gl.GenBuffers(3, &mut df.vbo as *mut u32);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
-
-            let vertices_u8 = vertices.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                vertices_u8.len() as isize,
-                vertices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
+            self.vertices =
+                Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &vertices, glapi::GL_STATIC_DRAW);
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[1]);
             // Texture coordinates falls into the range [0, 1].
             #[rustfmt::skip]
             let texture_vertex = [
@@ -81,75 +80,84 @@ pub fn draw_texture(df: &mut DrawContext) -> Result<(), OglError> {
                 1.0f32, 0.0f32,
             ];
 
-            let texture_vertex_u8 = texture_vertex.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                texture_vertex_u8.len() as isize,
-                texture_vertex_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
+            self.texture_vertex = Buffer::with_data(
+                gl,
+                glapi::GL_ARRAY_BUFFER,
+                &texture_vertex,
+                glapi::GL_STATIC_DRAW,
             );
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
 
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, df.vbo[2]);
             let indices = [0_u16, 1_u16, 2_u16];
-            let indices_u8 = indices.to_u8_slice();
-
-            gl.BufferData(
-                gl33::GL_ELEMENT_ARRAY_BUFFER,
-                indices_u8.len() as isize,
-                indices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
+            self.indices = Buffer::with_data(
+                gl,
+                glapi::GL_ELEMENT_ARRAY_BUFFER,
+                &indices,
+                glapi::GL_STATIC_DRAW,
             );
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, 0);
 
             // Create VAO to handle VBO
-            let mut vao = 0_u32;
-            gl.GenVertexArrays(1, &mut vao as *mut u32);
+            let vao = VertexArray::new(gl);
 
-            gl.BindVertexArray(vao);
+            gl.BindVertexArray(vao.id());
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.vertices.id());
             gl.EnableVertexAttribArray(0);
-            gl.VertexAttribPointer(0, 3, gl33::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
+            gl.VertexAttribPointer(0, 3, glapi::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[1]);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.texture_vertex.id());
             gl.EnableVertexAttribArray(1);
-            gl.VertexAttribPointer(1, 2, gl33::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
+            gl.VertexAttribPointer(1, 2, glapi::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
 
             // Bind texture
             let location = df.location("u_Texture").unwrap();
-            df.texture[0].bind(gl, 0, location)?;
+            self.texture.bind(gl, 0, location)?;
 
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, df.vbo[2]);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, self.indices.id());
 
             gl.BindVertexArray(0);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, 0);
 
-            df.vao = Some(vao);
+            self.vao = vao;
         }
+
+        Ok(())
     }
 
-    unsafe {
-        let gl = df.gl.gl();
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        unsafe {
+            let gl = df.gl().gl();
 
-        gl.Viewport(0, 0, df.width, df.height);
-        gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
-        gl.Clear(gl33::GL_COLOR_BUFFER_BIT);
+            gl.Viewport(0, 0, df.width(), df.height());
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT);
 
-        // Enable VBO and corresponding through VAO
-        gl.BindVertexArray(df.vao.unwrap());
+            // Enable VBO and corresponding through VAO
+            gl.BindVertexArray(self.vao.id());
 
-        gl.DrawElements(
-            gl33::GL_TRIANGLES,
-            3,
-            gl33::GL_UNSIGNED_SHORT,
-            0 as *const std::ffi::c_void,
-        );
+            gl.DrawElements(
+                glapi::GL_TRIANGLES,
+                3,
+                glapi::GL_UNSIGNED_SHORT,
+                0 as *const std::ffi::c_void,
+            );
 
-        gl.BindVertexArray(0);
-        gl.Flush();
+            gl.BindVertexArray(0);
+            gl.Flush();
+        }
+
+        Ok(())
     }
 
-    Ok(())
+    fn teardown(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        let gl = df.gl().gl();
+
+        unsafe {
+            self.texture.delete(gl);
+        }
+
+        Ok(())
+    }
 }
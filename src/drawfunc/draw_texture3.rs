@@ -1,12 +1,25 @@
-use super::{elapsed_milliseconds, DrawContext, DrawFunc};
-use error_stack::{Report, Result};
+use error_stack::Result;
 use jlogger_tracing::jdebug;
+use libogl::buffer::{Buffer, VertexArray};
+use libogl::camera::Camera;
+use libogl::demo::{Demo, DrawContext};
 use libogl::error::OglError;
-use libogl::texture2d::Texture2DFilter;
-use libogl::VertexOps;
+use libogl::glapi;
+use libogl::texture2d::{Texture2D, Texture2DFilter};
+
+#[derive(Default)]
+pub struct DrawTexture3 {
+    vertices: Buffer<f32>,
+    texture_coordinates: Buffer<f32>,
+    indices: Buffer<u16>,
+    vao: VertexArray,
+    vertex_number: u32,
+    texture: Texture2D,
+    camera: Camera,
+}
 
-pub fn draw_texture3(df: &mut DrawContext) -> Result<(), OglError> {
-    if !df.initialized || df.draw_func != DrawFunc::DrawTexture3 {
+impl Demo for DrawTexture3 {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
         let v_src = r#"
                 #version 300 es
                 layout(location = 0) in vec4 vPosition;
@@ -37,28 +50,20 @@ pub fn draw_texture3(df: &mut DrawContext) -> Result<(), OglError> {
                 }
         "#;
 
-        df.gl.build(Some(v_src), Some(f_src))?;
-
-        df.initialized = true;
-        df.draw_func = DrawFunc::DrawTexture3;
+        df.gl_mut().build(Some(v_src), Some(f_src))?;
 
         unsafe {
-            let gl = df.gl.gl();
-            let program = df.gl.program().unwrap();
+            let gl = df.gl().gl();
+            let program = df.gl().program().unwrap();
 
             let data = include_bytes!("../../doc/hamster.png");
-            df.texture[0].create_from_buffer(data, gl, Texture2DFilter::Linear)?;
-            jdebug!("texture: {}", df.texture[0]);
+            self.texture
+                .create_from_buffer(data, gl, Texture2DFilter::Linear)?;
+            jdebug!("texture: {}", self.texture);
 
             gl.UseProgram(program);
 
-            df.locations[0] = df
-                .location("u_mvpMatrix")
-                .ok_or(Report::new(OglError::Unexpected))?;
-
             // Create VBO for vertex and color
-            gl.GenBuffers(3, &mut df.vbo as *mut u32);
-
             // We can draw a cube with only 8 vertices. BUT it will lead to the problem of setting
             // up color/texture for each surfaces.
             #[rustfmt::skip]
@@ -75,18 +80,9 @@ pub fn draw_texture3(df: &mut DrawContext) -> Result<(), OglError> {
                  0.5f32,  0.5f32, -0.5f32,  //v7
             ];
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
-
-            let vertices_u8 = vertices.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                vertices_u8.len() as isize,
-                vertices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
-
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[1]);
+            self.vertices =
+                Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &vertices, glapi::GL_STATIC_DRAW);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
 
             //   Since we reuse the vertex data, the texture coordinates of vertex are decided by
             //   first 8 vertices.
@@ -128,16 +124,14 @@ pub fn draw_texture3(df: &mut DrawContext) -> Result<(), OglError> {
                 1.0f32, 0.0f32,     //v23 = v7
             ];
 
-            let texture_coordinates_u8 = texture_coordinates.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                texture_coordinates_u8.len() as isize,
-                texture_coordinates_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
+            self.texture_coordinates = Buffer::with_data(
+                gl,
+                glapi::GL_ARRAY_BUFFER,
+                &texture_coordinates,
+                glapi::GL_STATIC_DRAW,
             );
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
 
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, df.vbo[2]);
             #[rustfmt::skip]
             let indices = [
                 0_u16,   2_u16,  1_u16,
@@ -159,105 +153,111 @@ pub fn draw_texture3(df: &mut DrawContext) -> Result<(), OglError> {
                 3_u16,   6_u16,  2_u16
             ];
 
-            let indices_u8 = indices.to_u8_slice();
-            df.vertex_number = indices.len() as u32;
+            self.vertex_number = indices.len() as u32;
 
-            gl.BufferData(
-                gl33::GL_ELEMENT_ARRAY_BUFFER,
-                indices_u8.len() as isize,
-                indices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
+            self.indices = Buffer::with_data(
+                gl,
+                glapi::GL_ELEMENT_ARRAY_BUFFER,
+                &indices,
+                glapi::GL_STATIC_DRAW,
             );
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, 0);
 
             // Create VAO to handle VBO
-            let mut vao = 0_u32;
-            gl.GenVertexArrays(1, &mut vao as *mut u32);
+            let vao = VertexArray::new(gl);
 
-            gl.BindVertexArray(vao);
+            gl.BindVertexArray(vao.id());
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.vertices.id());
             gl.EnableVertexAttribArray(0);
-            gl.VertexAttribPointer(0, 3, gl33::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
+            gl.VertexAttribPointer(0, 3, glapi::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
 
             gl.EnableVertexAttribArray(1);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[1]);
-            gl.VertexAttribPointer(1, 2, gl33::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.texture_coordinates.id());
+            gl.VertexAttribPointer(1, 2, glapi::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
 
             // Bind texture
             let location = df.location("u_Texture").unwrap();
-            df.texture[0].bind(gl, 0, location)?;
+            self.texture.bind(gl, 0, location)?;
 
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, df.vbo[2]);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, self.indices.id());
 
             gl.BindVertexArray(0);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, 0);
 
-            df.vao = Some(vao);
+            self.vao = vao;
         }
+
+        self.camera = super::cube_camera(df, 7.5, 1.0);
+
+        Ok(())
     }
 
-    unsafe {
-        let gl = df.gl.gl();
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        unsafe {
+            let gl = df.gl().gl();
 
-        gl.Viewport(0, 0, df.width, df.height);
-        gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
-        gl.Clear(gl33::GL_COLOR_BUFFER_BIT);
+            gl.Viewport(0, 0, df.width(), df.height());
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT);
 
-        // Enable VBO and corresponding through VAO
-        gl.BindVertexArray(df.vao.unwrap());
+            // Enable VBO and corresponding through VAO
+            gl.BindVertexArray(self.vao.id());
 
-        // Scale matrix
-        let scale = glam::Mat4::from_scale(glam::vec3(1.5f32, 1.5f32, 1.5f32));
+            // Scale matrix
+            let scale = glam::Mat4::from_scale(glam::vec3(1.5f32, 1.5f32, 1.5f32));
 
-        // Rotate matrix
-        let angle = (elapsed_milliseconds() / 16 % u32::MAX as u128) as f32;
-        let rotate_x = glam::Mat4::from_quat(glam::Quat::from_rotation_x(angle.to_radians()));
+            // Rotate matrix
+            let angle = (df.clock().total().as_millis() / 16 % u32::MAX as u128) as f32;
+            let rotate_x = glam::Mat4::from_quat(glam::Quat::from_rotation_x(angle.to_radians()));
 
-        let rotate_y = glam::Mat4::from_quat(glam::Quat::from_rotation_y(angle.to_radians()));
+            let rotate_y = glam::Mat4::from_quat(glam::Quat::from_rotation_y(angle.to_radians()));
 
-        let rotate_z = glam::Mat4::from_quat(glam::Quat::from_rotation_z(angle.to_radians()));
+            let rotate_z = glam::Mat4::from_quat(glam::Quat::from_rotation_z(angle.to_radians()));
 
-        // Translate matrix
-        let translate = glam::Mat4::from_translation(glam::Vec3::new(0.0f32, 0.0f32, 5.0f32));
-        jdebug!(translate = format!("{:?}", translate));
+            // Cube is operated from right to left
+            //  1. rotate z
+            //  2. rotate y
+            //  3. rotate x
+            //  4. scale
+            //  5. camera view and perspective
+            let mvp = self.camera.view_projection() * scale * rotate_x * rotate_y * rotate_z;
 
-        // Perspective matrix
-        let aspect = df.width as f32 / df.height as f32;
-        let near = 1.0f32;
-        let far = 20.0f32;
-        let fov = 45.0f32.to_radians();
-        jdebug!(near = near, far = far, fov = fov);
-        let perspective = glam::Mat4::perspective_lh(fov, aspect, near, far);
+            jdebug!(mvp = format!("{:?}", mvp));
+            df.set_uniform("u_mvpMatrix", mvp)?;
 
-        // Cube is operated from right to left
-        //  1. rotate z
-        //  2. rotate y
-        //  3. rotate x
-        //  4. translate
-        //  5. scale
-        //  6. perspective
-        let mvp = perspective * scale * translate * rotate_x * rotate_y * rotate_z;
+            gl.Enable(glapi::GL_CULL_FACE);
+            gl.FrontFace(glapi::GL_CCW);
+            gl.CullFace(glapi::GL_FRONT);
 
-        jdebug!(mvp = format!("{:?}", mvp));
-        gl.UniformMatrix4fv(df.locations[0], 1, 0, mvp.as_ref().as_ptr().cast());
+            gl.DrawElements(
+                glapi::GL_TRIANGLES,
+                self.vertex_number as i32,
+                glapi::GL_UNSIGNED_SHORT,
+                0 as *const std::ffi::c_void,
+            );
 
-        gl.Enable(gl33::GL_CULL_FACE);
-        gl.FrontFace(gl33::GL_CCW);
-        gl.CullFace(gl33::GL_FRONT);
+            gl.BindVertexArray(0);
+            gl.Flush();
+        }
 
-        gl.DrawElements(
-            gl33::GL_TRIANGLES,
-            df.vertex_number as i32,
-            gl33::GL_UNSIGNED_SHORT,
-            0 as *const std::ffi::c_void,
-        );
+        Ok(())
+    }
 
-        gl.BindVertexArray(0);
-        gl.Flush();
+    fn resize(&mut self, _df: &mut DrawContext, width: i32, height: i32) -> Result<(), OglError> {
+        self.camera.resize(width, height);
+        Ok(())
     }
 
-    Ok(())
+    fn teardown(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        let gl = df.gl().gl();
+
+        unsafe {
+            self.texture.delete(gl);
+        }
+
+        Ok(())
+    }
 }
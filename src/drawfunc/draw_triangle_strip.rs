@@ -1,11 +1,20 @@
-use super::{DrawContext, DrawFunc};
 use error_stack::Result;
+use libogl::buffer::{Buffer, VertexArray};
+use libogl::demo::{Demo, DrawContext};
 use libogl::error::OglError;
-use libogl::VertexOps;
+use libogl::glapi;
+
+#[derive(Default)]
+pub struct DrawTriangleStrip {
+    vertices: Buffer<f32>,
+    color: Buffer<f32>,
+    indices: Buffer<u8>,
+    vao: VertexArray,
+    vertices_number: i32,
+}
 
-pub fn draw_triangle_strip(df: &mut DrawContext) -> Result<(), OglError> {
-    static mut VERTICES_NUMBER: i32 = 0;
-    if !df.initialized || df.draw_func != DrawFunc::DrawTriangleStrip {
+impl Demo for DrawTriangleStrip {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
         let v_src = r#"
                 #version 300 es
                 layout(location = 0) in vec4 vPosition;
@@ -33,18 +42,12 @@ pub fn draw_triangle_strip(df: &mut DrawContext) -> Result<(), OglError> {
                 }
         "#;
 
-        df.gl.build(Some(v_src), Some(f_src))?;
-
-        df.initialized = true;
-        df.draw_func = DrawFunc::DrawTriangleStrip;
+        df.gl_mut().build(Some(v_src), Some(f_src))?;
 
         unsafe {
-            let gl = df.gl.gl();
-            gl.UseProgram(df.gl.program().unwrap());
+            let gl = df.gl().gl();
+            gl.UseProgram(df.gl().program().unwrap());
 
-            gl.GenBuffers(3, &mut df.vbo as *mut u32);
-
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
             // w decided the clip range,
             //      -w <= x <= w
             //      -w <= y <= w
@@ -69,14 +72,8 @@ pub fn draw_triangle_strip(df: &mut DrawContext) -> Result<(), OglError> {
             ];
 
             // Create VBO for vertex and color
-
-            let vertices_u8 = vertices.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                vertices_u8.len() as isize,
-                vertices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
+            self.vertices =
+                Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &vertices, glapi::GL_STATIC_DRAW);
 
             #[rustfmt::skip]
             let color = [
@@ -96,17 +93,9 @@ pub fn draw_triangle_strip(df: &mut DrawContext) -> Result<(), OglError> {
                1.0f32, 1.0f32, 1.0f32,      // v11
             ];
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[1]);
-            let color_u8 = color.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                color_u8.len() as isize,
-                color_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
-
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, df.vbo[2]);
+            self.color =
+                Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &color, glapi::GL_STATIC_DRAW);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
 
             // We draw two separated triangle strips (v0, v1, v2, v3 v4) and (v8, v9, v10, v11)with
             // one DrawElements() call by using degenerate triangles.
@@ -121,59 +110,62 @@ pub fn draw_triangle_strip(df: &mut DrawContext) -> Result<(), OglError> {
                 0_u8, 1_u8, 2_u8, 3_u8, 4_u8, 4_u8, 8_u8, 8_u8, 9_u8, 10_u8, 11_u8,
             ];
             //let indices = [8_u8, 9_u8, 10_u8, 11_u8, 11_u8, 0_u8, 0_u8, 1_u8, 2_u8, 3_u8, 4_u8];
-            VERTICES_NUMBER = indices.len() as i32;
-            gl.BufferData(
-                gl33::GL_ELEMENT_ARRAY_BUFFER,
-                indices.len() as isize,
-                indices.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
+            self.vertices_number = indices.len() as i32;
+            self.indices = Buffer::with_data(
+                gl,
+                glapi::GL_ELEMENT_ARRAY_BUFFER,
+                &indices,
+                glapi::GL_STATIC_DRAW,
             );
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, 0);
 
             // Create VAO to handle VBO
-            let mut vao = 0_u32;
-            gl.GenVertexArrays(1, &mut vao as *mut u32);
-            gl.BindVertexArray(vao);
+            let vao = VertexArray::new(gl);
+            gl.BindVertexArray(vao.id());
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.vertices.id());
             gl.EnableVertexAttribArray(0);
-            gl.VertexAttribPointer(0, 4, gl33::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
+            gl.VertexAttribPointer(0, 4, glapi::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[1]);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.color.id());
             gl.EnableVertexAttribArray(1);
-            gl.VertexAttribPointer(1, 3, gl33::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
+            gl.VertexAttribPointer(1, 3, glapi::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
 
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, df.vbo[2]);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, self.indices.id());
 
             gl.BindVertexArray(0);
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
-            gl.BindBuffer(gl33::GL_ELEMENT_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ELEMENT_ARRAY_BUFFER, 0);
 
-            df.vao = Some(vao);
+            self.vao = vao;
         }
+
+        Ok(())
     }
 
-    unsafe {
-        let gl = df.gl.gl();
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        unsafe {
+            let gl = df.gl().gl();
 
-        gl.Viewport(0, 0, df.width, df.height);
-        gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
-        gl.Clear(gl33::GL_COLOR_BUFFER_BIT);
+            gl.Viewport(0, 0, df.width(), df.height());
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT);
 
-        // Enable VBO and corresponding through VAO
-        gl.BindVertexArray(df.vao.unwrap());
+            // Enable VBO and corresponding through VAO
+            gl.BindVertexArray(self.vao.id());
 
-        gl.DrawElements(
-            gl33::GL_TRIANGLE_STRIP,
-            VERTICES_NUMBER,
-            gl33::GL_UNSIGNED_BYTE,
-            0 as *const std::ffi::c_void,
-        );
+            gl.DrawElements(
+                glapi::GL_TRIANGLE_STRIP,
+                self.vertices_number,
+                glapi::GL_UNSIGNED_BYTE,
+                0 as *const std::ffi::c_void,
+            );
 
-        gl.BindVertexArray(0);
-        gl.Flush();
-    }
+            gl.BindVertexArray(0);
+            gl.Flush();
+        }
 
-    Ok(())
+        Ok(())
+    }
 }
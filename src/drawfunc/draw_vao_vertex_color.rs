@@ -1,10 +1,35 @@
-use super::{DrawContext, DrawFunc};
 use error_stack::Result;
+use glam::Vec3;
+use libogl::buffer::{Buffer, VertexArray};
+use libogl::demo::{Demo, DrawContext};
 use libogl::error::OglError;
-use libogl::VertexOps;
+use libogl::glapi;
+use libogl::vertex::{Vertex, VertexLayout};
+use std::mem::offset_of;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ColorVertex {
+    position: Vec3,
+    color: Vec3,
+}
+
+impl Vertex for ColorVertex {
+    fn layout() -> VertexLayout {
+        VertexLayout::of::<Self>()
+            .attribute::<Vec3>(0, offset_of!(Self, position))
+            .attribute::<Vec3>(1, offset_of!(Self, color))
+    }
+}
 
-pub fn draw_vao_vertex_color(df: &mut DrawContext) -> Result<(), OglError> {
-    if !df.initialized || df.draw_func != DrawFunc::DrawVaoVertexColor {
+#[derive(Default)]
+pub struct DrawVaoVertexColor {
+    vertices: Buffer<ColorVertex>,
+    vao: VertexArray,
+}
+
+impl Demo for DrawVaoVertexColor {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
         let v_src = r#"
                 #version 300 es
                 layout(location = 0) in vec4 vPosition;
@@ -32,87 +57,58 @@ pub fn draw_vao_vertex_color(df: &mut DrawContext) -> Result<(), OglError> {
                 }
         "#;
 
-        df.gl.build(Some(v_src), Some(f_src))?;
-
-        df.initialized = true;
-        df.draw_func = DrawFunc::DrawVaoVertexColor;
+        df.gl_mut().build(Some(v_src), Some(f_src))?;
 
         unsafe {
-            let gl = df.gl.gl();
-            gl.UseProgram(df.gl.program().unwrap());
+            let gl = df.gl().gl();
+            gl.UseProgram(df.gl().program().unwrap());
 
-            #[rustfmt::skip]
             let vertices = [
-                0.0f32,  0.5f32, 0.0f32,      1.0f32, 0.0f32, 0.0f32,
-               -0.5f32, -0.5f32, 0.0f32,      0.0f32, 1.0f32, 0.0f32,
-                0.5f32, -0.5f32, 0.0f32,      0.0f32, 0.0f32, 1.0f32,
+                ColorVertex {
+                    position: Vec3::new(0.0, 0.5, 0.0),
+                    color: Vec3::new(1.0, 0.0, 0.0),
+                },
+                ColorVertex {
+                    position: Vec3::new(-0.5, -0.5, 0.0),
+                    color: Vec3::new(0.0, 1.0, 0.0),
+                },
+                ColorVertex {
+                    position: Vec3::new(0.5, -0.5, 0.0),
+                    color: Vec3::new(0.0, 0.0, 1.0),
+                },
             ];
 
-            let mut vbo = 0_u32;
-            gl.GenBuffers(1, &mut vbo as *mut u32);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, vbo);
-
-            let vertices_u8 = vertices.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                vertices_u8.len() as isize,
-                vertices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
-
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
+            self.vertices =
+                Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &vertices, glapi::GL_STATIC_DRAW);
 
             // Create VAO to handle VBO
-            let mut vao = 0_u32;
-            gl.GenVertexArrays(1, &mut vao as *mut u32);
-            gl.BindVertexArray(vao);
-
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, vbo);
-
-            let stride = (core::mem::size_of::<f32>() * 6) as i32;
-            let mut offset = 0;
-
-            gl.EnableVertexAttribArray(0);
-            gl.VertexAttribPointer(
-                0,
-                3,
-                gl33::GL_FLOAT,
-                0,
-                stride,
-                offset as *const std::ffi::c_void,
-            );
-
-            offset = core::mem::size_of::<f32>() * 3;
-            gl.EnableVertexAttribArray(1);
-            gl.VertexAttribPointer(
-                1,
-                3,
-                gl33::GL_FLOAT,
-                0,
-                stride,
-                offset as *const std::ffi::c_void,
-            );
+            let vao = VertexArray::new(gl);
+            vao.set_vertices(&self.vertices);
 
             gl.BindVertexArray(0);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
 
-            df.vao = Some(vao);
+            self.vao = vao;
         }
+
+        Ok(())
     }
 
-    unsafe {
-        let gl = df.gl.gl();
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        unsafe {
+            let gl = df.gl().gl();
 
-        gl.Viewport(0, 0, df.width, df.height);
-        gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
-        gl.Clear(gl33::GL_COLOR_BUFFER_BIT);
+            gl.Viewport(0, 0, df.width(), df.height());
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT);
 
-        // Enable VBO and corresponding through VAO
-        gl.BindVertexArray(df.vao.unwrap());
-        gl.DrawArrays(gl33::GL_TRIANGLES, 0, 3);
-        gl.BindVertexArray(0);
+            // Enable VBO and corresponding through VAO
+            gl.BindVertexArray(self.vao.id());
+            gl.DrawArrays(glapi::GL_TRIANGLES, 0, 3);
+            gl.BindVertexArray(0);
 
-        gl.Flush();
+            gl.Flush();
+        }
+        Ok(())
     }
-    Ok(())
 }
@@ -1,10 +1,18 @@
-use super::{DrawContext, DrawFunc};
 use error_stack::Result;
+use libogl::buffer::{Buffer, VertexArray};
+use libogl::demo::{Demo, DrawContext};
 use libogl::error::OglError;
-use libogl::VertexOps;
+use libogl::glapi;
 
-pub fn draw_vao_vertex_color2(df: &mut DrawContext) -> Result<(), OglError> {
-    if !df.initialized || df.draw_func != DrawFunc::DrawVaoVertexColor2 {
+#[derive(Default)]
+pub struct DrawVaoVertexColor2 {
+    vertices: Buffer<f32>,
+    color: Buffer<f32>,
+    vao: VertexArray,
+}
+
+impl Demo for DrawVaoVertexColor2 {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
         let v_src = r#"
                 #version 300 es
                 layout(location = 0) in vec4 vPosition;
@@ -32,14 +40,11 @@ pub fn draw_vao_vertex_color2(df: &mut DrawContext) -> Result<(), OglError> {
                 }
         "#;
 
-        df.gl.build(Some(v_src), Some(f_src))?;
-
-        df.initialized = true;
-        df.draw_func = DrawFunc::DrawVaoVertexColor2;
+        df.gl_mut().build(Some(v_src), Some(f_src))?;
 
         unsafe {
-            let gl = df.gl.gl();
-            gl.UseProgram(df.gl.program().unwrap());
+            let gl = df.gl().gl();
+            gl.UseProgram(df.gl().program().unwrap());
 
             #[rustfmt::skip]
             let vertices = [
@@ -48,17 +53,8 @@ pub fn draw_vao_vertex_color2(df: &mut DrawContext) -> Result<(), OglError> {
                 0.5f32,   -0.5f32, 0.0f32,
             ];
 
-            let mut vbo = [0_u32; 2];
-            gl.GenBuffers(3, &mut vbo as *mut u32);
-
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, vbo[0]);
-            let vertices_u8 = vertices.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                vertices_u8.len() as isize,
-                vertices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
+            self.vertices =
+                Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &vertices, glapi::GL_STATIC_DRAW);
 
             #[rustfmt::skip]
             let color = [
@@ -67,50 +63,47 @@ pub fn draw_vao_vertex_color2(df: &mut DrawContext) -> Result<(), OglError> {
                0.0f32, 0.0f32, 1.0f32,
             ];
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, vbo[1]);
-            let color_u8 = color.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                color_u8.len() as isize,
-                color_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
+            self.color =
+                Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &color, glapi::GL_STATIC_DRAW);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
 
             // Create VAO to handle VBO
-            let mut vao = 0_u32;
-            gl.GenVertexArrays(1, &mut vao as *mut u32);
+            let vao = VertexArray::new(gl);
 
-            gl.BindVertexArray(vao);
+            gl.BindVertexArray(vao.id());
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, vbo[0]);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.vertices.id());
             gl.EnableVertexAttribArray(0);
-            gl.VertexAttribPointer(0, 3, gl33::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
+            gl.VertexAttribPointer(0, 3, glapi::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, vbo[1]);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.color.id());
             gl.EnableVertexAttribArray(1);
-            gl.VertexAttribPointer(1, 3, gl33::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
+            gl.VertexAttribPointer(1, 3, glapi::GL_FLOAT, 0, 0, 0 as *const std::ffi::c_void);
 
             gl.BindVertexArray(0);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
 
-            df.vao = Some(vao);
+            self.vao = vao;
         }
+
+        Ok(())
     }
 
-    unsafe {
-        let gl = df.gl.gl();
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        unsafe {
+            let gl = df.gl().gl();
 
-        gl.Viewport(0, 0, df.width, df.height);
-        gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
-        gl.Clear(gl33::GL_COLOR_BUFFER_BIT);
+            gl.Viewport(0, 0, df.width(), df.height());
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT);
 
-        // Enable VBO and corresponding through VAO
-        gl.BindVertexArray(df.vao.unwrap());
-        gl.DrawArrays(gl33::GL_TRIANGLES, 0, 3);
-        gl.BindVertexArray(0);
+            // Enable VBO and corresponding through VAO
+            gl.BindVertexArray(self.vao.id());
+            gl.DrawArrays(glapi::GL_TRIANGLES, 0, 3);
+            gl.BindVertexArray(0);
 
-        gl.Flush();
+            gl.Flush();
+        }
+        Ok(())
     }
-    Ok(())
 }
@@ -1,66 +1,76 @@
-use super::{DrawContext, DrawFunc};
 use error_stack::{Report, Result};
 use jlogger_tracing::jdebug;
+use libogl::buffer::Buffer;
+use libogl::demo::{Demo, DrawContext};
 use libogl::error::OglError;
-use libogl::VertexOps;
+use libogl::glapi;
+use std::path::Path;
 
-pub fn draw_vbo(df: &mut DrawContext) -> Result<(), OglError> {
-    if !df.initialized || df.draw_func != DrawFunc::DrawVbo {
-        let v_src = include_str!("../../es300/sample.vert");
-        let f_src = include_str!("../../es300/sample.frag");
-        df.gl.build(Some(v_src), Some(f_src))?;
+#[derive(Default)]
+pub struct DrawVbo {
+    vertices: Buffer<f32>,
+}
+
+impl Demo for DrawVbo {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        // Edit the shader files while running to see them reloaded, the embedded copies are
+        // used when ogl is not started from the top of the repository.
+        let (v_path, f_path) = ("es300/sample.vert", "es300/sample.frag");
+        if Path::new(v_path).exists() && Path::new(f_path).exists() {
+            df.gl_mut().build_files(v_path, f_path)?;
+        } else {
+            let v_src = include_str!("../../es300/sample.vert");
+            let f_src = include_str!("../../es300/sample.frag");
+            df.gl_mut().build(Some(v_src), Some(f_src))?;
+        }
 
-        df.initialized = true;
-        df.draw_func = DrawFunc::DrawVbo;
+        Ok(())
     }
 
-    let gl = df.gl.gl();
-    let program = df.gl.program().ok_or(Report::new(OglError::InvalidData))?;
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        let gl = df.gl().gl();
+        let program = df
+            .gl()
+            .program()
+            .ok_or(Report::new(OglError::InvalidData))?;
 
-    unsafe {
-        gl.Viewport(0, 0, df.width, df.height);
-        gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
-        gl.Clear(gl33::GL_COLOR_BUFFER_BIT);
-        gl.UseProgram(program);
+        unsafe {
+            gl.Viewport(0, 0, df.width(), df.height());
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT);
+            gl.UseProgram(program);
 
-        // Use uniform to set color for all vertex in frag shader.
+            // Use uniform to set color for all vertex in frag shader.
 
-        let location = df
-            .location("u_Color")
-            .ok_or(Report::new(OglError::Unexpected))?;
+            let location = df
+                .location("u_Color")
+                .ok_or(Report::new(OglError::Unexpected))?;
 
-        gl.Uniform4f(location, 0.8f32, 0.3f32, 0.02f32, 1.0f32);
+            gl.Uniform4f(location, 0.8f32, 0.3f32, 0.02f32, 1.0f32);
 
-        if df.vbo[0] == 0 {
-            #[rustfmt::skip]
-            let vertices = [
-                0.0f32,    0.5f32, 0.0f32,
-               -0.5f32,   -0.5f32, 0.0f32,
-                0.5f32,   -0.5f32, 0.0f32,
-            ];
+            if self.vertices.id() == 0 {
+                #[rustfmt::skip]
+                let vertices = [
+                    0.0f32,    0.5f32, 0.0f32,
+                   -0.5f32,   -0.5f32, 0.0f32,
+                    0.5f32,   -0.5f32, 0.0f32,
+                ];
 
-            gl.GenBuffers(1, &mut df.vbo as *mut u32);
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
+                self.vertices =
+                    Buffer::with_data(gl, glapi::GL_ARRAY_BUFFER, &vertices, glapi::GL_STATIC_DRAW);
 
-            let vertices_u8 = vertices.to_u8_slice();
-            gl.BufferData(
-                gl33::GL_ARRAY_BUFFER,
-                vertices_u8.len() as isize,
-                vertices_u8.as_ptr().cast(),
-                gl33::GL_STATIC_DRAW,
-            );
+                gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
+            }
 
-            gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
+            jdebug!("drawing with VBO");
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, self.vertices.id());
+            gl.VertexAttribPointer(0, 3, glapi::GL_FLOAT, 0, 0, core::ptr::null_mut());
+            gl.EnableVertexAttribArray(0);
+            gl.DrawArrays(glapi::GL_TRIANGLES, 0, 3);
+            gl.BindBuffer(glapi::GL_ARRAY_BUFFER, 0);
+            gl.DisableVertexAttribArray(0);
+            gl.Flush();
         }
-
-        jdebug!("drawing with VBO");
-        gl.BindBuffer(gl33::GL_ARRAY_BUFFER, df.vbo[0]);
-        gl.VertexAttribPointer(0, 3, gl33::GL_FLOAT, 0, 0, core::ptr::null_mut());
-        gl.EnableVertexAttribArray(0);
-        gl.DrawArrays(gl33::GL_TRIANGLES, 0, 3);
-        gl.BindBuffer(gl33::GL_ARRAY_BUFFER, 0);
-        gl.DisableVertexAttribArray(0);
-        gl.Flush();
+        Ok(())
     }
-    Ok(())
 }
@@ -1,55 +1,63 @@
-use super::{DrawContext, DrawFunc};
 use error_stack::{Report, Result};
 use jlogger_tracing::jdebug;
+use libogl::demo::{Demo, DrawContext};
 use libogl::error::OglError;
+use libogl::glapi;
 use libogl::VertexOps;
 
-pub fn draw_without_vbo(df: &mut DrawContext) -> Result<(), OglError> {
-    if !df.initialized || df.draw_func != DrawFunc::DrawWithoutVbo {
+#[derive(Default)]
+pub struct DrawWithoutVbo {}
+
+impl Demo for DrawWithoutVbo {
+    fn init(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
         let v_src = include_str!("../../es300/sample.vert");
         let f_src = include_str!("../../es300/sample.frag");
-        df.gl.build(Some(v_src), Some(f_src))?;
+        df.gl_mut().build(Some(v_src), Some(f_src))?;
 
-        df.initialized = true;
-        df.draw_func = DrawFunc::DrawWithoutVbo;
+        Ok(())
     }
 
-    let gl = df.gl.gl();
-    let program = df.gl.program().ok_or(Report::new(OglError::InvalidData))?;
-
-    unsafe {
-        gl.Viewport(0, 0, df.width, df.height);
-        gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
-        gl.Clear(gl33::GL_COLOR_BUFFER_BIT);
-        gl.UseProgram(program);
-
-        let location = df
-            .location("u_Color")
-            .ok_or(Report::new(OglError::Unexpected))?;
-
-        gl.Uniform4f(location, 0.8f32, 0.3f32, 0.02f32, 1.0f32);
-
-        #[rustfmt::skip]
-        let vertices: [f32; 9] = [
-                0.0f32,    0.5f32, 0.0f32,
-               -0.5f32,   -0.5f32, 0.0f32,
-                0.5f32,   -0.5f32, 0.0f32,
-        ];
-
-        jdebug!("drawing without VBO.");
-
-        gl.VertexAttribPointer(
-            0,
-            3,
-            gl33::GL_FLOAT,
-            0,
-            0,
-            vertices.to_u8_slice().as_ptr().cast(),
-        );
-
-        gl.EnableVertexAttribArray(0);
-        gl.DrawArrays(gl33::GL_TRIANGLES, 0, 3);
-        gl.DisableVertexAttribArray(0);
+    fn draw(&mut self, df: &mut DrawContext) -> Result<(), OglError> {
+        let gl = df.gl().gl();
+        let program = df
+            .gl()
+            .program()
+            .ok_or(Report::new(OglError::InvalidData))?;
+
+        unsafe {
+            gl.Viewport(0, 0, df.width(), df.height());
+            gl.ClearColor(0.07_f32, 0.13_f32, 0.17_f32, 1.0_f32);
+            gl.Clear(glapi::GL_COLOR_BUFFER_BIT);
+            gl.UseProgram(program);
+
+            let location = df
+                .location("u_Color")
+                .ok_or(Report::new(OglError::Unexpected))?;
+
+            gl.Uniform4f(location, 0.8f32, 0.3f32, 0.02f32, 1.0f32);
+
+            #[rustfmt::skip]
+            let vertices: [f32; 9] = [
+                    0.0f32,    0.5f32, 0.0f32,
+                   -0.5f32,   -0.5f32, 0.0f32,
+                    0.5f32,   -0.5f32, 0.0f32,
+            ];
+
+            jdebug!("drawing without VBO.");
+
+            gl.VertexAttribPointer(
+                0,
+                3,
+                glapi::GL_FLOAT,
+                0,
+                0,
+                vertices.to_u8_slice().as_ptr().cast(),
+            );
+
+            gl.EnableVertexAttribArray(0);
+            gl.DrawArrays(glapi::GL_TRIANGLES, 0, 3);
+            gl.DisableVertexAttribArray(0);
+        }
+        Ok(())
     }
-    Ok(())
 }
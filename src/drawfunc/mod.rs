@@ -3,6 +3,8 @@ pub mod draw_complex;
 pub mod draw_instance;
 pub mod draw_instance2;
 pub mod draw_lines;
+pub mod draw_meshes;
+pub mod draw_model;
 pub mod draw_model_view_projection;
 pub mod draw_primitive_restart;
 pub mod draw_provoking_vertex;
@@ -21,314 +23,83 @@ pub mod draw_vbo_vertex_color;
 pub mod draw_vbo_vertex_color2;
 pub mod draw_without_vbo;
 
-use super::gl::GlState;
-use error_stack::Result;
-use jlogger_tracing::{jerror, jinfo};
-use libogl::error::OglError;
-use libogl::texture2d::{Texture2D, Texture2DCubeMap};
-use once_cell::sync::OnceCell;
-use std::ffi::CString;
-use std::mem::{self, MaybeUninit};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Instant;
-
-use draw_circle::draw_circle;
-use draw_complex::draw_complex;
-use draw_instance::draw_instance;
-use draw_instance2::draw_instance2;
-use draw_lines::draw_lines;
-use draw_model_view_projection::draw_model_view_projection;
-use draw_primitive_restart::draw_primitive_restart;
-use draw_provoking_vertex::draw_provoking_vertex;
-use draw_texture::draw_texture;
-use draw_texture2::draw_texture2;
-use draw_texture3::draw_texture3;
-use draw_texture_cubemap::draw_texture_cubemap;
-use draw_texture_mipmap::draw_texture_mipmapping;
-use draw_triangle_strip::draw_triangle_strip;
-use draw_vao_elements::draw_vao_elements;
-use draw_vao_vertex_color::draw_vao_vertex_color;
-use draw_vao_vertex_color2::draw_vao_vertex_color2;
-use draw_vbo::draw_vbo;
-use draw_vbo2::draw_vbo2;
-use draw_vbo_vertex_color::draw_vbo_vertex_color;
-use draw_vbo_vertex_color2::draw_vbo_vertex_color2;
-use draw_without_vbo::draw_without_vbo;
-
-static START: OnceCell<Instant> = once_cell::sync::OnceCell::new();
-pub fn elapsed_seconds() -> u64 {
-    START.get().unwrap().elapsed().as_secs()
-}
-
-pub fn elapsed_milliseconds() -> u128 {
-    START.get().unwrap().elapsed().as_millis()
-}
-
-static mut RUNNING: AtomicBool = AtomicBool::new(false);
-
-pub struct RunState {}
-
-impl RunState {
-    pub fn global_run() {
-        unsafe {
-            START.set(Instant::now()).unwrap();
-            RUNNING.store(true, Ordering::Relaxed);
-        }
-    }
-    pub fn global_stop() {
-        unsafe {
-            RUNNING.store(false, Ordering::Relaxed);
-        }
-    }
-
-    pub fn is_running() -> bool {
-        unsafe { RUNNING.load(Ordering::Relaxed) }
-    }
+use glam::Vec3;
+use libogl::camera::{Camera, Handedness};
+use libogl::demo::{DemoRegistry, DrawContext};
+
+use draw_circle::DrawCircle;
+use draw_complex::DrawComplex;
+use draw_instance::DrawInstance;
+use draw_instance2::DrawInstance2;
+use draw_lines::DrawLines;
+use draw_meshes::DrawMeshes;
+use draw_model_view_projection::DrawModelViewProjection;
+use draw_primitive_restart::DrawPrimitiveRestart;
+use draw_provoking_vertex::DrawProvokingVertex;
+use draw_texture::DrawTexture;
+use draw_texture2::DrawTexture2;
+use draw_texture3::DrawTexture3;
+use draw_texture_cubemap::DrawTextureCubeMap;
+use draw_texture_mipmap::DrawTextureMipMapping;
+use draw_triangle_strip::DrawTriangleStrip;
+use draw_vao_elements::DrawVaoVertexColorElement2;
+use draw_vao_vertex_color::DrawVaoVertexColor;
+use draw_vao_vertex_color2::DrawVaoVertexColor2;
+use draw_vbo::DrawVbo;
+use draw_vbo2::DrawVbo2;
+use draw_vbo_vertex_color::DrawVboVertexColor;
+use draw_vbo_vertex_color2::DrawVboVertexColor2;
+use draw_without_vbo::DrawWithoutVbo;
+
+/// Demos shipped with ogl, the order decides the number passed to --func.
+pub fn registry() -> DemoRegistry {
+    let mut registry = DemoRegistry::new();
+
+    registry.register::<DrawVbo>();
+    registry.register::<DrawVbo2>();
+    registry.register::<DrawVboVertexColor>();
+    registry.register::<DrawVboVertexColor2>();
+    registry.register::<DrawVaoVertexColor>();
+    registry.register::<DrawVaoVertexColor2>();
+    registry.register::<DrawVaoVertexColorElement2>();
+    registry.register::<DrawCircle>();
+    registry.register::<DrawComplex>();
+    registry.register::<DrawWithoutVbo>();
+    registry.register::<DrawLines>();
+    registry.register::<DrawPrimitiveRestart>();
+    registry.register::<DrawProvokingVertex>();
+    registry.register::<DrawInstance>();
+    registry.register::<DrawInstance2>();
+    registry.register::<DrawTriangleStrip>();
+    registry.register::<DrawModelViewProjection>();
+    registry.register::<DrawTexture>();
+    registry.register::<DrawTexture2>();
+    registry.register::<DrawTexture3>();
+    registry.register::<DrawTextureMipMapping>();
+    registry.register::<DrawTextureCubeMap>();
+    registry.register::<DrawMeshes>();
+
+    registry
 }
 
-pub trait DrawContextOps {
-    fn do_dispatch(&mut self) -> Result<(), OglError>;
-    fn do_swap(&self) -> Result<(), OglError>;
-}
-
-#[allow(unused)]
-#[derive(Debug, PartialEq, PartialOrd)]
-pub enum DrawFunc {
-    DrawVbo,
-    DrawVbo2,
-    DrawVboVertexColor,
-    DrawVboVertexColor2,
-    DrawVaoVertexColor,
-    DrawVaoVertexColor2,
-    DrawVaoVertexColorElement2,
-    DrawCircle,
-    DrawComplex,
-    DrawWithoutVbo,
-    DrawLines,
-    DrawPrimitiveRestart,
-    DrawProvokingVertex,
-    DrawInstance,
-    DrawInstance2,
-    DrawTriangleStrip,
-    DrawModelViewProjection,
-    DrawTexture,
-    DrawTexture2,
-    DrawTexture3,
-    DrawTextureMipMapping,
-    DrawTextureCubeMap,
-    InvalidDrawFunc,
-}
-
-impl std::fmt::Display for DrawFunc {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let index: usize = self.into();
-
-        let msg = match self {
-            DrawFunc::DrawVbo => format!("{:3} DrawVbo", index),
-            DrawFunc::DrawVbo2 => format!("{:3} DrawVbo2", index),
-            DrawFunc::DrawVboVertexColor => format!("{:3} DrawVboVertexColor", index),
-            DrawFunc::DrawVboVertexColor2 => {
-                format!("{:3} DrawVboVertexColor2", index)
-            }
-            DrawFunc::DrawVaoVertexColor => format!("{:3} DrawVaoVertexColor", index),
-            DrawFunc::DrawVaoVertexColor2 => {
-                format!("{:3} DrawVaoVertexColor2", index)
-            }
-            DrawFunc::DrawVaoVertexColorElement2 => {
-                format!("{:3} DrawVaoVertexColorElement2", index)
-            }
-            DrawFunc::DrawCircle => format!("{:3} DrawCircle", index),
-            DrawFunc::DrawComplex => format!("{:3} DrawComplex", index),
-            DrawFunc::DrawWithoutVbo => format!("{:3} DrawWithoutVbo", index),
-            DrawFunc::DrawLines => format!("{:3} DrawLines", index),
-            DrawFunc::DrawPrimitiveRestart => {
-                format!("{:3} DrawPrimitiveRestart", index)
-            }
-            DrawFunc::DrawProvokingVertex => {
-                format!("{:3} DrawProvokingVertex", index)
-            }
-            DrawFunc::DrawInstance => format!("{:3} DrawInstance", index),
-            DrawFunc::DrawInstance2 => format!("{:3} DrawInstance2", index),
-            DrawFunc::DrawTriangleStrip => format!("{:3} DrawTriangleStrip", index),
-            DrawFunc::DrawModelViewProjection => format!("{:3} DrawModelViewProjection", index),
-            DrawFunc::DrawTexture => format!("{:3} DrawTexture", index),
-            DrawFunc::DrawTexture2 => format!("{:3} DrawTexture2", index),
-            DrawFunc::DrawTexture3 => format!("{:3} DrawTexture3", index),
-            DrawFunc::DrawTextureMipMapping => format!("{:3} DrawTextureMipMapping", index),
-            DrawFunc::DrawTextureCubeMap => format!("{:3} DrawTextureCubeMap", index),
-            DrawFunc::InvalidDrawFunc => format!("InvalidDrawFunc"),
-        };
-
-        write!(f, "{}", msg)
-    }
-}
-
-impl From<usize> for DrawFunc {
-    fn from(value: usize) -> Self {
-        match value {
-            1 => DrawFunc::DrawVbo,
-            2 => DrawFunc::DrawVbo2,
-            3 => DrawFunc::DrawVboVertexColor,
-            4 => DrawFunc::DrawVboVertexColor2,
-            5 => DrawFunc::DrawVaoVertexColor,
-            6 => DrawFunc::DrawVaoVertexColor2,
-            7 => DrawFunc::DrawVaoVertexColorElement2,
-            8 => DrawFunc::DrawCircle,
-            9 => DrawFunc::DrawComplex,
-            10 => DrawFunc::DrawWithoutVbo,
-            11 => DrawFunc::DrawLines,
-            12 => DrawFunc::DrawPrimitiveRestart,
-            13 => DrawFunc::DrawProvokingVertex,
-            14 => DrawFunc::DrawInstance,
-            15 => DrawFunc::DrawInstance2,
-            16 => DrawFunc::DrawTriangleStrip,
-            17 => DrawFunc::DrawModelViewProjection,
-            18 => DrawFunc::DrawTexture,
-            19 => DrawFunc::DrawTexture2,
-            20 => DrawFunc::DrawTexture3,
-            21 => DrawFunc::DrawTextureMipMapping,
-            22 => DrawFunc::DrawTextureCubeMap,
-            _ => DrawFunc::InvalidDrawFunc,
-        }
-    }
-}
-
-impl From<&DrawFunc> for usize {
-    fn from(value: &DrawFunc) -> Self {
-        (1..=100)
-            .into_iter()
-            .find(|a| &DrawFunc::from(*a) == value)
-            .unwrap()
-    }
-}
-
-#[allow(unused)]
-pub struct DrawContext {
-    w: i32,
-    h: i32,
-    turn_small: bool,
-    width: i32,
-    height: i32,
-    gl: GlState,
-    vao: Option<u32>,
-    vbo: [u32; 16],
-    locations: [i32; 256],
-    vertex_number: u32,
-    initialized: bool,
-    draw_func: DrawFunc,
-    texture: [Texture2D; 8],
-    texture_cubemap: [Texture2DCubeMap; 8],
-}
-
-impl DrawContext {
-    pub fn new(gl: GlState, width: i32, height: i32) -> Self {
-        let mut texture: [MaybeUninit<Texture2D>; 8] =
-            unsafe { mem::MaybeUninit::uninit().assume_init() };
-
-        for element in &mut texture {
-            element.write(Texture2D::default());
-        }
-
-        let texture = unsafe { mem::transmute(texture) };
-
-        let mut texture_cubemap: [MaybeUninit<Texture2DCubeMap>; 8] =
-            unsafe { mem::MaybeUninit::uninit().assume_init() };
-
-        for element in &mut texture_cubemap {
-            element.write(Texture2DCubeMap::default());
-        }
-
-        let texture_cubemap = unsafe { mem::transmute(texture_cubemap) };
-
-        Self {
-            w: 0,
-            h: 0,
-            turn_small: false,
-            width,
-            height,
-            gl,
-            vao: None,
-            vbo: [0_u32; 16],
-            locations: [0; 256],
-            vertex_number: 0,
-            initialized: false,
-            draw_func: DrawFunc::DrawVbo,
-            texture,
-            texture_cubemap,
-        }
-    }
-
-    pub fn run(
-        &mut self,
-        ops: &mut dyn DrawContextOps,
-        draw_func: DrawFunc,
-    ) -> Result<(), OglError> {
-        RunState::global_run();
-
-        jinfo!(func = draw_func.to_string());
-        while RunState::is_running() {
-            ops.do_dispatch()?;
-
-            match draw_func {
-                DrawFunc::DrawVbo => draw_vbo(self)?,
-                DrawFunc::DrawVbo2 => draw_vbo2(self)?,
-                DrawFunc::DrawLines => draw_lines(self)?,
-                DrawFunc::DrawCircle => draw_circle(self)?,
-                DrawFunc::DrawComplex => draw_complex(self)?,
-                DrawFunc::DrawWithoutVbo => draw_without_vbo(self)?,
-                DrawFunc::DrawVboVertexColor => draw_vbo_vertex_color(self)?,
-                DrawFunc::DrawVboVertexColor2 => draw_vbo_vertex_color2(self)?,
-                DrawFunc::DrawVaoVertexColor => draw_vao_vertex_color(self)?,
-                DrawFunc::DrawVaoVertexColor2 => draw_vao_vertex_color2(self)?,
-                DrawFunc::DrawVaoVertexColorElement2 => draw_vao_elements(self)?,
-                DrawFunc::DrawPrimitiveRestart => draw_primitive_restart(self)?,
-                DrawFunc::DrawProvokingVertex => draw_provoking_vertex(self)?,
-                DrawFunc::DrawInstance => draw_instance(self)?,
-                DrawFunc::DrawInstance2 => draw_instance2(self)?,
-                DrawFunc::DrawTriangleStrip => draw_triangle_strip(self)?,
-                DrawFunc::DrawModelViewProjection => draw_model_view_projection(self)?,
-                DrawFunc::DrawTexture => draw_texture(self)?,
-                DrawFunc::DrawTexture2 => draw_texture2(self)?,
-                DrawFunc::DrawTexture3 => draw_texture3(self)?,
-                DrawFunc::DrawTextureMipMapping => draw_texture_mipmapping(self)?,
-                DrawFunc::DrawTextureCubeMap => draw_texture_cubemap(self)?,
-                DrawFunc::InvalidDrawFunc => {
-                    jerror!("Invalid function.");
-                    break;
-                }
-            }
-
-            ops.do_swap()?;
-        }
-
-        Ok(())
-    }
-
-    pub fn location(&self, name: &str) -> Option<i32> {
-        let program = self.gl.program().unwrap();
-        let gl = self.gl.gl();
-        let name = CString::new(name).unwrap();
-
-        let location = unsafe { gl.GetUniformLocation(program, name.as_ptr().cast()) };
-
-        if location >= 0 {
-            Some(location)
-        } else {
-            None
-        }
-    }
+/// Left-handed camera `distance` units in front of the rotating cube of the texture demos.
+fn cube_camera(df: &DrawContext, distance: f32, near: f32) -> Camera {
+    let mut camera = Camera::perspective(45.0_f32.to_radians(), near, 20.0);
+    camera.set_handedness(Handedness::Left);
+    camera.look_at(Vec3::new(0.0, 0.0, -distance), Vec3::ZERO);
+    camera.resize(df.width(), df.height());
+    camera
 }
 
 #[cfg(test)]
 mod tests {
-    use super::DrawFunc;
+    use super::registry;
 
     #[test]
-    fn drawfunc_to_usize() {
-        let index: usize = (&DrawFunc::DrawVbo).into();
-        assert_eq!(index, 1);
-
-        let index: usize = (&DrawFunc::DrawModelViewProjection).into();
-        assert_eq!(index, 17);
+    fn registry_keeps_func_numbers() {
+        let registry = registry();
+        assert_eq!(registry.find("DrawVbo").unwrap().id(), 1);
+        assert_eq!(registry.find("DrawModelViewProjection").unwrap().id(), 17);
+        assert_eq!(registry.len(), 23);
     }
 }
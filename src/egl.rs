@@ -1,12 +1,12 @@
 #[allow(unused)]
 use {
-    super::gl::GlContextOps,
     core::borrow::Borrow,
     error_stack::{Report, Result, ResultExt},
     jlogger_tracing::{
         jdebug, jerror, jinfo, jtrace, jwarn, JloggerBuilder, LevelFilter, LogTimeFormat,
     },
     libogl::error::OglError,
+    libogl::gl::GlContextOps,
     std::sync::{
         atomic::{AtomicBool, Ordering},
         Mutex,
@@ -69,6 +69,8 @@ impl EglState {
             8,
             khronos_egl::ALPHA_SIZE,
             8,
+            khronos_egl::DEPTH_SIZE,
+            16,
             khronos_egl::RENDERABLE_TYPE,
             khronos_egl::OPENGL_ES3_BIT,
             khronos_egl::NONE,
@@ -146,6 +148,7 @@ impl GlContextOps for EglState {
             .as_ref()
             .unwrap()
             .get_proc_address(s)
-            .expect(&format!("Failed to load {s}")) as *mut std::ffi::c_void
+            .map(|f| f as *mut std::ffi::c_void)
+            .unwrap_or(core::ptr::null_mut())
     }
 }
@@ -6,7 +6,22 @@ pub enum OglError {
     SDLError,
     EglError,
     GlError,
+    /// A shader failed to compile. `source_excerpt` lists the lines reported in `log`.
+    ShaderCompile {
+        stage: &'static str,
+        log: String,
+        source_excerpt: String,
+    },
+    ProgramLink {
+        log: String,
+    },
     InvalidData,
+    /// The data uses a feature which is not implemented, e.g. a glTF extension.
+    Unsupported {
+        feature: String,
+    },
+    UnknownUniform,
+    UniformTypeMismatch,
     Unexpected,
 }
 
@@ -17,7 +32,24 @@ impl Display for OglError {
             OglError::SDLError => "SDL error",
             OglError::EglError => "EGL error",
             OglError::GlError => "Opengl error",
+            OglError::ShaderCompile {
+                stage,
+                log,
+                source_excerpt,
+            } => {
+                return write!(
+                    f,
+                    "Failed to compile {stage} shader\n{}\n{source_excerpt}",
+                    log.trim_end()
+                )
+            }
+            OglError::ProgramLink { log } => {
+                return write!(f, "Failed to link program\n{}", log.trim_end())
+            }
             OglError::InvalidData => "Invalid error",
+            OglError::Unsupported { feature } => return write!(f, "Unsupported {feature}"),
+            OglError::UnknownUniform => "Unknown uniform",
+            OglError::UniformTypeMismatch => "Uniform type mismatch",
             OglError::Unexpected => "Unexpected error",
         };
 
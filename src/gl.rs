@@ -1,40 +1,30 @@
-#[allow(unused)]
-use {
-    super::egl::EglState,
-    core::borrow::Borrow,
-    error_stack::{Report, Result, ResultExt},
-    jlogger_tracing::{
-        jdebug, jerror, jinfo, jtrace, jwarn, JloggerBuilder, LevelFilter, LogTimeFormat,
-    },
-    libogl::error::OglError,
-    std::sync::{
-        atomic::{AtomicBool, Ordering},
-        Mutex,
-    },
-    std::{fs::File, os::fd::AsFd},
-    std::{thread::sleep, time::Duration},
-    wayland_client::{
-        backend::{Backend, ObjectId},
-        delegate_noop,
-        protocol::wl_keyboard::{self, KeyState},
-        protocol::{
-            wl_buffer, wl_compositor, wl_registry, wl_seat, wl_shm, wl_shm_pool, wl_surface,
-        },
-        Connection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum,
-    },
-    wayland_egl::WlEglSurface,
-    wayland_protocols::xdg::shell::client::{xdg_surface, xdg_toplevel, xdg_wm_base},
-};
+use super::error::OglError;
+use super::glapi;
+use super::preprocess::Preprocessor;
+use super::program_cache::ProgramCache;
+use super::screenshot::Screenshot;
+use super::shader::{Program, Shader};
+use super::uniform::{UniformValue, Uniforms};
+use super::watch::FileWatcher;
+use error_stack::{Report, Result};
+use jlogger_tracing::{jerror, jinfo, jwarn};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 pub trait GlContextOps {
+    /// Address of the GL function `s`, null if it is not available.
     fn get_proc_address(&self, s: &str) -> *mut std::ffi::c_void;
 }
 
 pub struct GlState {
-    gl: gl33::GlFns,
-    program: Option<u32>,
+    gl: Rc<glapi::GlFns>,
+    program: Option<Program>,
     v_src: String,
     f_src: String,
+    paths: Option<(PathBuf, PathBuf)>,
+    watcher: Option<FileWatcher>,
+    preprocessor: Preprocessor,
+    cache: Option<ProgramCache>,
 }
 
 impl GlState {
@@ -43,19 +33,20 @@ impl GlState {
         v_src: Option<&str>,
         f_src: Option<&str>,
     ) -> Result<Self, OglError> {
-        let gl = unsafe {
-            gl33::GlFns::load_from(&|p| {
-                let s = std::ffi::CStr::from_ptr(p.cast()).to_str().unwrap();
-                egl.get_proc_address(s)
-            })
-            .unwrap()
-        };
+        let gl = glapi::load(&|s| egl.get_proc_address(s).cast_const())?;
+
+        let gl = Rc::new(gl);
+        let cache = ProgramCache::default_dir().and_then(|dir| ProgramCache::new(&gl, egl, dir));
 
         let mut state = Self {
             gl,
             v_src: String::new(),
             f_src: String::new(),
             program: None,
+            paths: None,
+            watcher: None,
+            preprocessor: Preprocessor::new(),
+            cache,
         };
 
         if v_src.is_some() && f_src.is_some() {
@@ -74,88 +65,239 @@ impl GlState {
             self.f_src = s.to_owned();
         }
 
-        unsafe {
-            let gl = &mut self.gl;
-
-            let v_shader = gl.CreateShader(gl33::GL_VERTEX_SHADER);
-            gl.ShaderSource(
-                v_shader,
-                1,
-                &self.v_src.as_bytes().as_ptr().cast(),
-                &self.v_src.len().try_into().unwrap(),
-            );
+        if v_src.is_some() || f_src.is_some() {
+            self.paths = None;
+            self.watcher = None;
+        }
 
-            gl.CompileShader(v_shader);
+        // The previous program is deleted when replaced.
+        let (program, _) = self.link(&self.v_src, &self.f_src, None)?;
+        self.program = Some(program);
 
-            let mut success = 0;
-            gl.GetShaderiv(v_shader, gl33::GL_COMPILE_STATUS, &mut success);
+        Ok(())
+    }
+
+    /// Build the program from shader files and watch them and the files they include, see
+    /// reload().
+    pub fn build_files<P: AsRef<Path>>(&mut self, v_path: P, f_path: P) -> Result<(), OglError> {
+        let (v_path, f_path) = (v_path.as_ref(), f_path.as_ref());
+        let v_src = read_shader(v_path)?;
+        let f_src = read_shader(f_path)?;
+
+        let (program, files) = self.link(&v_src, &f_src, Some((v_path, f_path)))?;
+        self.program = Some(program);
+        self.v_src = v_src;
+        self.f_src = f_src;
+        self.paths = Some((v_path.to_path_buf(), f_path.to_path_buf()));
+
+        // Hot reload is a convenience, the program can be used without it.
+        self.watcher = FileWatcher::new()
+            .and_then(|mut w| {
+                for file in &files {
+                    w.watch(file)?;
+                }
+                Ok(w)
+            })
+            .map_err(|e| jwarn!("Shader files are not watched: {:?}", e))
+            .ok();
 
-            if success == 0 {
-                let mut v: Vec<u8> = Vec::with_capacity(1024);
-                let mut log_len = 0_i32;
+        Ok(())
+    }
 
-                gl.GetShaderInfoLog(v_shader, 1024, &mut log_len, v.as_mut_ptr().cast());
+    /// Rebuild the program if the shader files given to build_files() were modified.
+    ///
+    /// The new program is made current. If it fails to build, the error is logged and the
+    /// previous program is kept. Returns whether the program was replaced.
+    pub fn reload(&mut self) -> bool {
+        let changed = match self.watcher.as_mut() {
+            Some(w) => !w.changed().is_empty(),
+            None => false,
+        };
 
-                v.set_len(log_len.try_into().unwrap());
-                let error_msg = String::from_utf8_lossy(&v).to_string();
-                jerror!("Error: {}", error_msg);
+        let (v_path, f_path) = match self.paths.as_ref() {
+            Some(paths) if changed => paths,
+            _ => return false,
+        };
 
-                return Err(Report::new(OglError::GlError).attach_printable(error_msg));
+        let built = read_shader(v_path).and_then(|v_src| {
+            let f_src = read_shader(f_path)?;
+            let (program, files) = self.link(&v_src, &f_src, Some((v_path, f_path)))?;
+            Ok((v_src, f_src, program, files))
+        });
+
+        match built {
+            Ok((v_src, f_src, program, files)) => {
+                jinfo!("Reloaded {} and {}", v_path.display(), f_path.display());
+                program.use_program();
+                self.v_src = v_src;
+                self.f_src = f_src;
+                self.program = Some(program);
+
+                // Newly included files.
+                if let Some(w) = self.watcher.as_mut() {
+                    for file in &files {
+                        if let Err(e) = w.watch(file) {
+                            jwarn!("{:?}", e);
+                        }
+                    }
+                }
+                true
+            }
+            Err(e) => {
+                // Compile and link logs are printed where they are read.
+                match e.current_context() {
+                    OglError::ShaderCompile { .. } | OglError::ProgramLink { .. } => {
+                        jerror!("Keep the previous program")
+                    }
+                    _ => jerror!("Keep the previous program: {:?}", e),
+                }
+                false
             }
+        }
+    }
 
-            let f_shader = gl.CreateShader(gl33::GL_FRAGMENT_SHADER);
+    /// Preprocess, compile and link the sources. The files read are returned with the program.
+    fn link(
+        &self,
+        v_src: &str,
+        f_src: &str,
+        paths: Option<(&Path, &Path)>,
+    ) -> Result<(Program, Vec<PathBuf>), OglError> {
+        let v_source = self.preprocessor.process(v_src, paths.map(|a| a.0))?;
+        let f_source = self.preprocessor.process(f_src, paths.map(|a| a.1))?;
+
+        let key = self
+            .cache
+            .as_ref()
+            .map(|a| a.key(&[v_source.text(), f_source.text()]));
+
+        let cached = self.cache.as_ref().zip(key.as_ref());
+        let program = match cached.and_then(|(cache, key)| cache.load(key)) {
+            Some(program) => program,
+            None => {
+                let v_shader =
+                    Shader::compile_source(&self.gl, glapi::GL_VERTEX_SHADER, &v_source)?;
+                let f_shader =
+                    Shader::compile_source(&self.gl, glapi::GL_FRAGMENT_SHADER, &f_source)?;
+                let program = Program::link(&self.gl, &[&v_shader, &f_shader])?;
+
+                if let Some((cache, key)) = cached {
+                    cache.store(key, &program);
+                }
+                program
+            }
+        };
 
-            gl.ShaderSource(
-                f_shader,
-                1,
-                &self.f_src.as_bytes().as_ptr().cast(),
-                &self.f_src.len().try_into().unwrap(),
-            );
+        let mut files = v_source.files().to_vec();
+        files.extend_from_slice(f_source.files());
+        Ok((program, files))
+    }
 
-            gl.CompileShader(f_shader);
+    /// Cache of linked programs, enabled in the default directory if the driver supports it.
+    pub fn program_cache(&self) -> Option<&ProgramCache> {
+        self.cache.as_ref()
+    }
 
-            gl.GetShaderiv(f_shader, gl33::GL_COMPILE_STATUS, &mut success);
-            if success == 0 {
-                let mut v: Vec<u8> = Vec::with_capacity(1024);
-                let mut log_len = 0_i32;
+    /// Replace the cache of linked programs, None to always compile the shaders.
+    pub fn set_program_cache(&mut self, cache: Option<ProgramCache>) {
+        self.cache = cache;
+    }
 
-                gl.GetShaderInfoLog(f_shader, 1024, &mut log_len, v.as_mut_ptr().cast());
+    /// Preprocessor applied to the sources by build() and build_files().
+    pub fn preprocessor(&self) -> &Preprocessor {
+        &self.preprocessor
+    }
 
-                v.set_len(log_len.try_into().unwrap());
+    pub fn preprocessor_mut(&mut self) -> &mut Preprocessor {
+        &mut self.preprocessor
+    }
 
-                return Err(Report::new(OglError::GlError)
-                    .attach_printable(String::from_utf8_lossy(&v).to_string()));
-            }
+    pub fn gl(&self) -> &Rc<glapi::GlFns> {
+        &self.gl
+    }
 
-            let program = gl.CreateProgram();
-            gl.AttachShader(program, v_shader);
-            gl.AttachShader(program, f_shader);
-            gl.LinkProgram(program);
+    pub fn program(&self) -> Option<u32> {
+        self.program.as_ref().map(|a| a.id())
+    }
 
-            gl.GetProgramiv(program, gl33::GL_LINK_STATUS, &mut success);
-            if success == 0 {
-                let mut v: Vec<u8> = Vec::with_capacity(1024);
-                let mut log_len = 0_i32;
+    /// Program built by build() with its active uniforms and attributes.
+    pub fn linked_program(&self) -> Option<&Program> {
+        self.program.as_ref()
+    }
 
-                gl.GetProgramInfoLog(program, 1024, &mut log_len, v.as_mut_ptr().cast());
+    /// Set `value` to the uniform `name` of the program built by build().
+    pub fn set_uniform<T: UniformValue>(&self, name: &str, value: T) -> Result<(), OglError> {
+        self.program
+            .as_ref()
+            .ok_or(Report::new(OglError::GlError).attach_printable("No program is built"))?
+            .set_uniform(name, value)
+    }
 
-                v.set_len(log_len.try_into().unwrap());
+    /// Set every uniform of `uniforms` to the program built by build().
+    pub fn set_uniforms<T: Uniforms>(&self, uniforms: &T) -> Result<(), OglError> {
+        uniforms.upload(
+            self.program
+                .as_ref()
+                .ok_or(Report::new(OglError::GlError).attach_printable("No program is built"))?,
+        )
+    }
 
-                return Err(Report::new(OglError::GlError)
-                    .attach_printable(String::from_utf8_lossy(&v).to_string()));
-            }
+    /// Delete the program built by build() and stop watching its files.
+    pub fn release(&mut self) {
+        self.paths = None;
+        self.watcher = None;
+        if self.program.take().is_some() {
+            self.gl.UseProgram(0);
+        }
+    }
 
-            self.program = Some(program);
+    /// Read back the given area of the current framebuffer.
+    pub fn read_pixels(
+        &self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> Result<Screenshot, OglError> {
+        if width <= 0 || height <= 0 {
+            return Err(Report::new(OglError::InvalidData)
+                .attach_printable(format!("Invalid size {width}x{height}")));
         }
 
-        Ok(())
-    }
+        let mut pixels = vec![0_u8; (width * height * 4) as usize];
 
-    pub fn gl(&self) -> &gl33::GlFns {
-        &self.gl
-    }
+        unsafe {
+            let gl = &self.gl;
+
+            // Discard errors left by draw functions.
+            while gl.GetError() != glapi::GL_NO_ERROR {}
+
+            gl.Finish();
+            gl.PixelStorei(glapi::GL_PACK_ALIGNMENT, 1);
+            gl.ReadPixels(
+                x,
+                y,
+                width,
+                height,
+                glapi::GL_RGBA,
+                glapi::GL_UNSIGNED_BYTE,
+                pixels.as_mut_ptr().cast(),
+            );
 
-    pub fn program(&self) -> Option<u32> {
-        self.program.as_ref().cloned()
+            let error = gl.GetError();
+            if error != glapi::GL_NO_ERROR {
+                return Err(Report::new(OglError::GlError)
+                    .attach_printable(format!("glReadPixels failed: 0x{:x}", error.0)));
+            }
+        }
+
+        Screenshot::from_gl_pixels(width as u32, height as u32, &pixels)
     }
 }
+
+fn read_shader(path: &Path) -> Result<String, OglError> {
+    std::fs::read_to_string(path).map_err(|e| {
+        Report::new(OglError::InvalidData)
+            .attach_printable(format!("Failed to read {}: {e}", path.display()))
+    })
+}
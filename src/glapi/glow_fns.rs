@@ -0,0 +1,904 @@
+//! glow implementation of the GL functions used by libogl, named after gl33 so that both can be
+//! used through the same code.
+//!
+//! Object names are kept as raw u32 like in gl33. Functions not provided by glow, or not in the
+//! way gl33 does, are loaded directly.
+//!
+//! The unsafe functions have the safety requirements of the GL functions of the same name.
+
+#![allow(
+    non_snake_case,
+    non_upper_case_globals,
+    clippy::missing_safety_doc,
+    clippy::too_many_arguments
+)]
+
+use super::super::error::OglError;
+use error_stack::{Report, Result};
+use glow::HasContext;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_void, CStr, CString};
+use std::num::NonZeroU32;
+
+pub type GLuint = u32;
+pub type GLint = i32;
+pub type GLsizei = i32;
+pub type GLfloat = f32;
+pub type GLboolean = u8;
+pub type GLchar = std::os::raw::c_char;
+pub type GLubyte = u8;
+pub type GLsizeiptr = isize;
+pub type GLintptr = isize;
+
+/// A GL enumeration value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct GLenum(pub u32);
+
+/// A GL bitfield value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct GLbitfield(pub u32);
+
+impl std::ops::BitOr for GLbitfield {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+pub type AttributeType = GLenum;
+pub type BufferTargetARB = GLenum;
+pub type BufferUsageARB = GLenum;
+pub type CullFaceMode = GLenum;
+pub type DepthFunction = GLenum;
+pub type DrawElementsType = GLenum;
+pub type EnableCap = GLenum;
+pub type ErrorCode = GLenum;
+pub type FrontFaceDirection = GLenum;
+pub type GetPName = GLenum;
+pub type PixelFormat = GLenum;
+pub type PixelStoreParameter = GLenum;
+pub type PixelType = GLenum;
+pub type PrimitiveType = GLenum;
+pub type ProgramPropertyARB = GLenum;
+pub type ShaderParameterName = GLenum;
+pub type ShaderType = GLenum;
+pub type StringName = GLenum;
+pub type TextureParameterName = GLenum;
+pub type TextureTarget = GLenum;
+pub type TextureUnit = GLenum;
+pub type UniformType = GLenum;
+pub type VertexAttribPointerType = GLenum;
+
+pub const GL_ACTIVE_ATTRIBUTES: GLenum = GLenum(0x8B89);
+pub const GL_ACTIVE_ATTRIBUTE_MAX_LENGTH: GLenum = GLenum(0x8B8A);
+pub const GL_ACTIVE_UNIFORMS: GLenum = GLenum(0x8B86);
+pub const GL_ACTIVE_UNIFORM_MAX_LENGTH: GLenum = GLenum(0x8B87);
+pub const GL_ARRAY_BUFFER: GLenum = GLenum(0x8892);
+pub const GL_BOOL: GLenum = GLenum(0x8B56);
+pub const GL_BOOL_VEC2: GLenum = GLenum(0x8B57);
+pub const GL_BOOL_VEC3: GLenum = GLenum(0x8B58);
+pub const GL_BOOL_VEC4: GLenum = GLenum(0x8B59);
+pub const GL_BYTE: GLenum = GLenum(0x1400);
+pub const GL_CCW: GLenum = GLenum(0x0901);
+pub const GL_CLAMP_TO_EDGE: GLenum = GLenum(0x812F);
+pub const GL_COLOR_BUFFER_BIT: GLbitfield = GLbitfield(0x00004000);
+pub const GL_COMPILE_STATUS: GLenum = GLenum(0x8B81);
+pub const GL_CULL_FACE: GLenum = GLenum(0x0B44);
+pub const GL_DEPTH_BUFFER_BIT: GLbitfield = GLbitfield(0x00000100);
+pub const GL_DEPTH_TEST: GLenum = GLenum(0x0B71);
+pub const GL_ELEMENT_ARRAY_BUFFER: GLenum = GLenum(0x8893);
+pub const GL_FALSE: GLenum = GLenum(0);
+pub const GL_FLOAT: GLenum = GLenum(0x1406);
+pub const GL_FLOAT_MAT2: GLenum = GLenum(0x8B5A);
+pub const GL_FLOAT_MAT2x3: GLenum = GLenum(0x8B65);
+pub const GL_FLOAT_MAT2x4: GLenum = GLenum(0x8B66);
+pub const GL_FLOAT_MAT3: GLenum = GLenum(0x8B5B);
+pub const GL_FLOAT_MAT3x2: GLenum = GLenum(0x8B67);
+pub const GL_FLOAT_MAT3x4: GLenum = GLenum(0x8B68);
+pub const GL_FLOAT_MAT4: GLenum = GLenum(0x8B5C);
+pub const GL_FLOAT_MAT4x2: GLenum = GLenum(0x8B69);
+pub const GL_FLOAT_MAT4x3: GLenum = GLenum(0x8B6A);
+pub const GL_FLOAT_VEC2: GLenum = GLenum(0x8B50);
+pub const GL_FLOAT_VEC3: GLenum = GLenum(0x8B51);
+pub const GL_FLOAT_VEC4: GLenum = GLenum(0x8B52);
+pub const GL_FRAGMENT_SHADER: GLenum = GLenum(0x8B30);
+pub const GL_FRONT: GLenum = GLenum(0x0404);
+pub const GL_GEOMETRY_SHADER: GLenum = GLenum(0x8DD9);
+pub const GL_INFO_LOG_LENGTH: GLenum = GLenum(0x8B84);
+pub const GL_INT: GLenum = GLenum(0x1404);
+pub const GL_INT_SAMPLER_2D: GLenum = GLenum(0x8DCA);
+pub const GL_INT_SAMPLER_2D_ARRAY: GLenum = GLenum(0x8DCF);
+pub const GL_INT_SAMPLER_3D: GLenum = GLenum(0x8DCB);
+pub const GL_INT_SAMPLER_CUBE: GLenum = GLenum(0x8DCC);
+pub const GL_INT_VEC2: GLenum = GLenum(0x8B53);
+pub const GL_INT_VEC3: GLenum = GLenum(0x8B54);
+pub const GL_INT_VEC4: GLenum = GLenum(0x8B55);
+pub const GL_LEQUAL: GLenum = GLenum(0x0203);
+pub const GL_LESS: GLenum = GLenum(0x0201);
+pub const GL_LINEAR: GLenum = GLenum(0x2601);
+pub const GL_LINEAR_MIPMAP_LINEAR: GLenum = GLenum(0x2703);
+pub const GL_LINEAR_MIPMAP_NEAREST: GLenum = GLenum(0x2701);
+pub const GL_LINE_LOOP: GLenum = GLenum(0x0002);
+pub const GL_LINE_STRIP: GLenum = GLenum(0x0003);
+pub const GL_LINK_STATUS: GLenum = GLenum(0x8B82);
+pub const GL_MAP_WRITE_BIT: GLbitfield = GLbitfield(0x0002);
+pub const GL_NEAREST: GLenum = GLenum(0x2600);
+pub const GL_NEAREST_MIPMAP_LINEAR: GLenum = GLenum(0x2702);
+pub const GL_NEAREST_MIPMAP_NEAREST: GLenum = GLenum(0x2700);
+pub const GL_NO_ERROR: GLenum = GLenum(0);
+pub const GL_PACK_ALIGNMENT: GLenum = GLenum(0x0D05);
+pub const GL_PRIMITIVE_RESTART: GLenum = GLenum(0x8F9D);
+pub const GL_PRIMITIVE_RESTART_INDEX: GLenum = GLenum(0x8F9E);
+pub const GL_PROGRAM_BINARY_LENGTH: GLenum = GLenum(0x8741);
+pub const GL_RENDERER: GLenum = GLenum(0x1F01);
+pub const GL_REPEAT: GLenum = GLenum(0x2901);
+pub const GL_RGB: GLenum = GLenum(0x1907);
+pub const GL_RGBA: GLenum = GLenum(0x1908);
+pub const GL_SAMPLER_2D: GLenum = GLenum(0x8B5E);
+pub const GL_SAMPLER_2D_ARRAY: GLenum = GLenum(0x8DC1);
+pub const GL_SAMPLER_2D_ARRAY_SHADOW: GLenum = GLenum(0x8DC4);
+pub const GL_SAMPLER_2D_SHADOW: GLenum = GLenum(0x8B62);
+pub const GL_SAMPLER_3D: GLenum = GLenum(0x8B5F);
+pub const GL_SAMPLER_CUBE: GLenum = GLenum(0x8B60);
+pub const GL_SAMPLER_CUBE_SHADOW: GLenum = GLenum(0x8DC5);
+pub const GL_SHORT: GLenum = GLenum(0x1402);
+pub const GL_STATIC_DRAW: GLenum = GLenum(0x88E4);
+pub const GL_TEXTURE0: GLenum = GLenum(0x84C0);
+pub const GL_TEXTURE1: GLenum = GLenum(0x84C1);
+pub const GL_TEXTURE2: GLenum = GLenum(0x84C2);
+pub const GL_TEXTURE3: GLenum = GLenum(0x84C3);
+pub const GL_TEXTURE4: GLenum = GLenum(0x84C4);
+pub const GL_TEXTURE5: GLenum = GLenum(0x84C5);
+pub const GL_TEXTURE6: GLenum = GLenum(0x84C6);
+pub const GL_TEXTURE7: GLenum = GLenum(0x84C7);
+pub const GL_TEXTURE_2D: GLenum = GLenum(0x0DE1);
+pub const GL_TEXTURE_CUBE_MAP: GLenum = GLenum(0x8513);
+pub const GL_TEXTURE_CUBE_MAP_NEGATIVE_X: GLenum = GLenum(0x8516);
+pub const GL_TEXTURE_CUBE_MAP_NEGATIVE_Y: GLenum = GLenum(0x8518);
+pub const GL_TEXTURE_CUBE_MAP_NEGATIVE_Z: GLenum = GLenum(0x851A);
+pub const GL_TEXTURE_CUBE_MAP_POSITIVE_X: GLenum = GLenum(0x8515);
+pub const GL_TEXTURE_CUBE_MAP_POSITIVE_Y: GLenum = GLenum(0x8517);
+pub const GL_TEXTURE_CUBE_MAP_POSITIVE_Z: GLenum = GLenum(0x8519);
+pub const GL_TEXTURE_MAG_FILTER: GLenum = GLenum(0x2800);
+pub const GL_TEXTURE_MIN_FILTER: GLenum = GLenum(0x2801);
+pub const GL_TEXTURE_WRAP_R: GLenum = GLenum(0x8072);
+pub const GL_TEXTURE_WRAP_S: GLenum = GLenum(0x2802);
+pub const GL_TEXTURE_WRAP_T: GLenum = GLenum(0x2803);
+pub const GL_TRIANGLES: GLenum = GLenum(0x0004);
+pub const GL_TRIANGLE_FAN: GLenum = GLenum(0x0006);
+pub const GL_TRIANGLE_STRIP: GLenum = GLenum(0x0005);
+pub const GL_TRUE: GLenum = GLenum(1);
+pub const GL_UNSIGNED_BYTE: GLenum = GLenum(0x1401);
+pub const GL_UNSIGNED_INT: GLenum = GLenum(0x1405);
+pub const GL_UNSIGNED_INT_SAMPLER_2D: GLenum = GLenum(0x8DD2);
+pub const GL_UNSIGNED_INT_SAMPLER_2D_ARRAY: GLenum = GLenum(0x8DD7);
+pub const GL_UNSIGNED_INT_SAMPLER_3D: GLenum = GLenum(0x8DD3);
+pub const GL_UNSIGNED_INT_SAMPLER_CUBE: GLenum = GLenum(0x8DD4);
+pub const GL_UNSIGNED_INT_VEC2: GLenum = GLenum(0x8DC6);
+pub const GL_UNSIGNED_INT_VEC3: GLenum = GLenum(0x8DC7);
+pub const GL_UNSIGNED_INT_VEC4: GLenum = GLenum(0x8DC8);
+pub const GL_UNSIGNED_SHORT: GLenum = GLenum(0x1403);
+pub const GL_VERSION: GLenum = GLenum(0x1F02);
+pub const GL_VERTEX_SHADER: GLenum = GLenum(0x8B31);
+
+type GetProgramivFn = unsafe extern "system" fn(u32, u32, *mut i32);
+type PointSizeFn = unsafe extern "system" fn(f32);
+type PrimitiveRestartIndexFn = unsafe extern "system" fn(u32);
+type VertexAttribPointerFn = unsafe extern "system" fn(u32, i32, u32, u8, i32, *const c_void);
+
+pub struct GlFns {
+    gl: glow::Context,
+    get_programiv: Option<GetProgramivFn>,
+    point_size: Option<PointSizeFn>,
+    primitive_restart_index: Option<PrimitiveRestartIndexFn>,
+    vertex_attrib_pointer: VertexAttribPointerFn,
+    strings: RefCell<HashMap<u32, CString>>,
+}
+
+/// Load the GL functions with `get_proc_address`.
+pub fn load(get_proc_address: &dyn Fn(&str) -> *const c_void) -> Result<GlFns, OglError> {
+    let gl = unsafe {
+        glow::Context::from_loader_function_cstr(|s| {
+            get_proc_address(s.to_str().unwrap_or_default())
+        })
+    };
+
+    // glow has no program binaries, their length is queried directly.
+    let get_programiv = get_proc_address("glGetProgramiv");
+    let point_size = get_proc_address("glPointSize");
+    let primitive_restart_index = get_proc_address("glPrimitiveRestartIndex");
+    let vertex_attrib_pointer = get_proc_address("glVertexAttribPointer");
+
+    if gl.version().major == 0 || vertex_attrib_pointer.is_null() {
+        return Err(Report::new(OglError::GlError).attach_printable("No GL context"));
+    }
+
+    Ok(GlFns {
+        gl,
+        get_programiv: (!get_programiv.is_null()).then(|| unsafe {
+            std::mem::transmute::<*const c_void, GetProgramivFn>(get_programiv)
+        }),
+        point_size: (!point_size.is_null())
+            .then(|| unsafe { std::mem::transmute::<*const c_void, PointSizeFn>(point_size) }),
+        primitive_restart_index: (!primitive_restart_index.is_null()).then(|| unsafe {
+            std::mem::transmute::<*const c_void, PrimitiveRestartIndexFn>(primitive_restart_index)
+        }),
+        vertex_attrib_pointer: unsafe {
+            std::mem::transmute::<*const c_void, VertexAttribPointerFn>(vertex_attrib_pointer)
+        },
+        strings: RefCell::new(HashMap::new()),
+    })
+}
+
+macro_rules! native {
+    ($t:ident, $id:expr) => {
+        NonZeroU32::new($id).map(glow::$t)
+    };
+}
+
+fn location(location: GLint) -> Option<glow::NativeUniformLocation> {
+    (location >= 0).then_some(glow::NativeUniformLocation(location as u32))
+}
+
+fn flag(b: GLboolean) -> bool {
+    b != 0
+}
+
+/// Bytes of a `width` x `height` image, rows are expected to be tightly packed.
+fn image_len(width: GLsizei, height: GLsizei, format: PixelFormat, type_: PixelType) -> usize {
+    let channels = match format {
+        GL_RGBA => 4,
+        GL_RGB => 3,
+        _ => 1,
+    };
+    let size = match type_ {
+        GL_UNSIGNED_BYTE => 1,
+        _ => 4,
+    };
+
+    (width.max(0) * height.max(0)) as usize * channels * size
+}
+
+/// Copy `s` to the buffer of a glGet*InfoLog() like function.
+unsafe fn copy_str(s: &str, buf_size: GLsizei, length: *mut GLsizei, buf: *mut GLchar) {
+    let n = s.len().min((buf_size.max(1) - 1) as usize);
+    if !buf.is_null() && buf_size > 0 {
+        std::ptr::copy_nonoverlapping(s.as_ptr().cast(), buf, n);
+        *buf.add(n) = 0;
+    }
+    if !length.is_null() {
+        *length = n as GLsizei;
+    }
+}
+
+impl GlFns {
+    pub unsafe fn ActiveTexture(&self, texture: TextureUnit) {
+        self.gl.active_texture(texture.0)
+    }
+
+    pub fn AttachShader(&self, program: GLuint, shader: GLuint) {
+        if let (Some(p), Some(s)) = (
+            native!(NativeProgram, program),
+            native!(NativeShader, shader),
+        ) {
+            unsafe { self.gl.attach_shader(p, s) }
+        }
+    }
+
+    pub unsafe fn BindBuffer(&self, target: BufferTargetARB, buffer: GLuint) {
+        self.gl.bind_buffer(target.0, native!(NativeBuffer, buffer))
+    }
+
+    pub unsafe fn BindTexture(&self, target: TextureTarget, texture: GLuint) {
+        self.gl
+            .bind_texture(target.0, native!(NativeTexture, texture))
+    }
+
+    pub fn BindVertexArray(&self, array: GLuint) {
+        unsafe { self.gl.bind_vertex_array(native!(NativeVertexArray, array)) }
+    }
+
+    pub unsafe fn BufferData(
+        &self,
+        target: BufferTargetARB,
+        size: GLsizeiptr,
+        data: *const c_void,
+        usage: BufferUsageARB,
+    ) {
+        if data.is_null() {
+            self.gl.buffer_data_size(target.0, size as i32, usage.0)
+        } else {
+            let data = std::slice::from_raw_parts(data.cast::<u8>(), size as usize);
+            self.gl.buffer_data_u8_slice(target.0, data, usage.0)
+        }
+    }
+
+    pub unsafe fn Clear(&self, mask: GLbitfield) {
+        self.gl.clear(mask.0)
+    }
+
+    pub unsafe fn ClearColor(&self, red: GLfloat, green: GLfloat, blue: GLfloat, alpha: GLfloat) {
+        self.gl.clear_color(red, green, blue, alpha)
+    }
+
+    pub fn CompileShader(&self, shader: GLuint) {
+        if let Some(s) = native!(NativeShader, shader) {
+            unsafe { self.gl.compile_shader(s) }
+        }
+    }
+
+    pub fn CreateProgram(&self) -> GLuint {
+        unsafe { self.gl.create_program() }.map_or(0, |a| a.0.get())
+    }
+
+    pub fn CreateShader(&self, type_: ShaderType) -> GLuint {
+        unsafe { self.gl.create_shader(type_.0) }.map_or(0, |a| a.0.get())
+    }
+
+    pub unsafe fn CullFace(&self, mode: CullFaceMode) {
+        self.gl.cull_face(mode.0)
+    }
+
+    pub unsafe fn DeleteBuffers(&self, n: GLsizei, buffers: *const GLuint) {
+        for i in 0..n as usize {
+            if let Some(b) = native!(NativeBuffer, *buffers.add(i)) {
+                self.gl.delete_buffer(b)
+            }
+        }
+    }
+
+    pub fn DeleteProgram(&self, program: GLuint) {
+        if let Some(p) = native!(NativeProgram, program) {
+            unsafe { self.gl.delete_program(p) }
+        }
+    }
+
+    pub fn DeleteShader(&self, shader: GLuint) {
+        if let Some(s) = native!(NativeShader, shader) {
+            unsafe { self.gl.delete_shader(s) }
+        }
+    }
+
+    pub unsafe fn DeleteTextures(&self, n: GLsizei, textures: *const GLuint) {
+        for i in 0..n as usize {
+            if let Some(t) = native!(NativeTexture, *textures.add(i)) {
+                self.gl.delete_texture(t)
+            }
+        }
+    }
+
+    pub unsafe fn DeleteVertexArrays(&self, n: GLsizei, arrays: *const GLuint) {
+        for i in 0..n as usize {
+            if let Some(a) = native!(NativeVertexArray, *arrays.add(i)) {
+                self.gl.delete_vertex_array(a)
+            }
+        }
+    }
+
+    pub unsafe fn DepthFunc(&self, func: DepthFunction) {
+        self.gl.depth_func(func.0)
+    }
+
+    pub unsafe fn DetachShader(&self, program: GLuint, shader: GLuint) {
+        if let (Some(p), Some(s)) = (
+            native!(NativeProgram, program),
+            native!(NativeShader, shader),
+        ) {
+            self.gl.detach_shader(p, s)
+        }
+    }
+
+    pub unsafe fn Disable(&self, cap: EnableCap) {
+        self.gl.disable(cap.0)
+    }
+
+    pub unsafe fn DisableVertexAttribArray(&self, index: GLuint) {
+        self.gl.disable_vertex_attrib_array(index)
+    }
+
+    pub unsafe fn DrawArrays(&self, mode: PrimitiveType, first: GLint, count: GLsizei) {
+        self.gl.draw_arrays(mode.0, first, count)
+    }
+
+    pub unsafe fn DrawElements(
+        &self,
+        mode: PrimitiveType,
+        count: GLsizei,
+        type_: DrawElementsType,
+        indices: *const c_void,
+    ) {
+        self.gl
+            .draw_elements(mode.0, count, type_.0, indices as usize as i32)
+    }
+
+    pub unsafe fn DrawElementsInstanced(
+        &self,
+        mode: PrimitiveType,
+        count: GLsizei,
+        type_: DrawElementsType,
+        indices: *const c_void,
+        instancecount: GLsizei,
+    ) {
+        self.gl.draw_elements_instanced(
+            mode.0,
+            count,
+            type_.0,
+            indices as usize as i32,
+            instancecount,
+        )
+    }
+
+    pub unsafe fn Enable(&self, cap: EnableCap) {
+        self.gl.enable(cap.0)
+    }
+
+    pub unsafe fn EnableVertexAttribArray(&self, index: GLuint) {
+        self.gl.enable_vertex_attrib_array(index)
+    }
+
+    pub unsafe fn Finish(&self) {
+        self.gl.finish()
+    }
+
+    pub unsafe fn Flush(&self) {
+        self.gl.flush()
+    }
+
+    pub unsafe fn FrontFace(&self, mode: FrontFaceDirection) {
+        self.gl.front_face(mode.0)
+    }
+
+    pub unsafe fn GenBuffers(&self, n: GLsizei, buffers: *mut GLuint) {
+        for i in 0..n as usize {
+            *buffers.add(i) = self.gl.create_buffer().map_or(0, |a| a.0.get());
+        }
+    }
+
+    pub unsafe fn GenTextures(&self, n: GLsizei, textures: *mut GLuint) {
+        for i in 0..n as usize {
+            *textures.add(i) = self.gl.create_texture().map_or(0, |a| a.0.get());
+        }
+    }
+
+    pub unsafe fn GenVertexArrays(&self, n: GLsizei, arrays: *mut GLuint) {
+        for i in 0..n as usize {
+            *arrays.add(i) = self.gl.create_vertex_array().map_or(0, |a| a.0.get());
+        }
+    }
+
+    pub unsafe fn GenerateMipmap(&self, target: TextureTarget) {
+        self.gl.generate_mipmap(target.0)
+    }
+
+    pub unsafe fn GetActiveAttrib(
+        &self,
+        program: GLuint,
+        index: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        size: *mut GLint,
+        type_: *mut AttributeType,
+        name: *mut GLchar,
+    ) {
+        let active =
+            native!(NativeProgram, program).and_then(|p| self.gl.get_active_attribute(p, index));
+        if let Some(a) = active {
+            *size = a.size;
+            *type_ = GLenum(a.atype);
+            copy_str(&a.name, bufSize, length, name);
+        }
+    }
+
+    pub unsafe fn GetActiveUniform(
+        &self,
+        program: GLuint,
+        index: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        size: *mut GLint,
+        type_: *mut UniformType,
+        name: *mut GLchar,
+    ) {
+        let active =
+            native!(NativeProgram, program).and_then(|p| self.gl.get_active_uniform(p, index));
+        if let Some(a) = active {
+            *size = a.size;
+            *type_ = GLenum(a.utype);
+            copy_str(&a.name, bufSize, length, name);
+        }
+    }
+
+    pub unsafe fn GetAttribLocation(&self, program: GLuint, name: *const GLchar) -> GLint {
+        let name = CStr::from_ptr(name).to_str().unwrap_or_default();
+        native!(NativeProgram, program)
+            .and_then(|p| self.gl.get_attrib_location(p, name))
+            .map_or(-1, |a| a as GLint)
+    }
+
+    pub unsafe fn GetError(&self) -> ErrorCode {
+        GLenum(self.gl.get_error())
+    }
+
+    pub unsafe fn GetIntegerv(&self, pname: GetPName, data: *mut GLint) {
+        *data = self.gl.get_parameter_i32(pname.0)
+    }
+
+    pub unsafe fn GetProgramInfoLog(
+        &self,
+        program: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        infoLog: *mut GLchar,
+    ) {
+        let log = native!(NativeProgram, program)
+            .map(|p| self.gl.get_program_info_log(p))
+            .unwrap_or_default();
+        copy_str(&log, bufSize, length, infoLog);
+    }
+
+    /// Only the parameters used by libogl are supported, others return 0.
+    pub unsafe fn GetProgramiv(
+        &self,
+        program: GLuint,
+        pname: ProgramPropertyARB,
+        params: *mut GLint,
+    ) {
+        let Some(p) = native!(NativeProgram, program) else {
+            return;
+        };
+
+        let gl = &self.gl;
+        *params = match pname {
+            GL_LINK_STATUS => gl.get_program_link_status(p) as GLint,
+            GL_INFO_LOG_LENGTH => match gl.get_program_info_log(p).len() {
+                0 => 0,
+                n => n as GLint + 1,
+            },
+            GL_ACTIVE_UNIFORMS => gl.get_active_uniforms(p) as GLint,
+            GL_ACTIVE_ATTRIBUTES => gl.get_active_attributes(p) as GLint,
+            GL_ACTIVE_UNIFORM_MAX_LENGTH => (0..gl.get_active_uniforms(p))
+                .filter_map(|i| gl.get_active_uniform(p, i))
+                .map(|a| a.name.len() as GLint + 1)
+                .max()
+                .unwrap_or(0),
+            GL_ACTIVE_ATTRIBUTE_MAX_LENGTH => (0..gl.get_active_attributes(p))
+                .filter_map(|i| gl.get_active_attribute(p, i))
+                .map(|a| a.name.len() as GLint + 1)
+                .max()
+                .unwrap_or(0),
+            GL_PROGRAM_BINARY_LENGTH => match self.get_programiv {
+                Some(f) => {
+                    let mut length = 0;
+                    f(program, pname.0, &mut length);
+                    length
+                }
+                None => 0,
+            },
+            _ => 0,
+        };
+    }
+
+    pub unsafe fn GetShaderInfoLog(
+        &self,
+        shader: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        infoLog: *mut GLchar,
+    ) {
+        let log = native!(NativeShader, shader)
+            .map(|s| self.gl.get_shader_info_log(s))
+            .unwrap_or_default();
+        copy_str(&log, bufSize, length, infoLog);
+    }
+
+    /// Only the parameters used by libogl are supported, others return 0.
+    pub unsafe fn GetShaderiv(
+        &self,
+        shader: GLuint,
+        pname: ShaderParameterName,
+        params: *mut GLint,
+    ) {
+        let Some(s) = native!(NativeShader, shader) else {
+            return;
+        };
+
+        *params = match pname {
+            GL_COMPILE_STATUS => self.gl.get_shader_compile_status(s) as GLint,
+            GL_INFO_LOG_LENGTH => match self.gl.get_shader_info_log(s).len() {
+                0 => 0,
+                n => n as GLint + 1,
+            },
+            _ => 0,
+        };
+    }
+
+    /// The string stays valid as long as the GlFns.
+    pub unsafe fn GetString(&self, name: StringName) -> *const GLubyte {
+        let mut strings = self.strings.borrow_mut();
+        let s = strings.entry(name.0).or_insert_with(|| {
+            CString::new(self.gl.get_parameter_string(name.0)).unwrap_or_default()
+        });
+        s.as_ptr().cast()
+    }
+
+    pub unsafe fn GetUniformLocation(&self, program: GLuint, name: *const GLchar) -> GLint {
+        let name = CStr::from_ptr(name).to_str().unwrap_or_default();
+        native!(NativeProgram, program)
+            .and_then(|p| self.gl.get_uniform_location(p, name))
+            .map_or(-1, |a| a.0 as GLint)
+    }
+
+    pub unsafe fn GetUniformfv(&self, program: GLuint, location: GLint, params: *mut GLfloat) {
+        if let (Some(p), Some(l)) = (native!(NativeProgram, program), self::location(location)) {
+            // glow passes the pointer as it is, the length is not checked.
+            let params = std::slice::from_raw_parts_mut(params, 1);
+            self.gl.get_uniform_f32(p, &l, params)
+        }
+    }
+
+    pub unsafe fn LineWidth(&self, width: GLfloat) {
+        self.gl.line_width(width)
+    }
+
+    pub fn LinkProgram(&self, program: GLuint) {
+        if let Some(p) = native!(NativeProgram, program) {
+            unsafe { self.gl.link_program(p) }
+        }
+    }
+
+    pub unsafe fn MapBufferRange(
+        &self,
+        target: BufferTargetARB,
+        offset: GLintptr,
+        length: GLsizeiptr,
+        access: GLbitfield,
+    ) -> *mut c_void {
+        self.gl
+            .map_buffer_range(target.0, offset as i32, length as i32, access.0)
+            .cast()
+    }
+
+    pub unsafe fn PixelStorei(&self, pname: PixelStoreParameter, param: GLint) {
+        self.gl.pixel_store_i32(pname.0, param)
+    }
+
+    /// Not available on GLES.
+    pub fn PointSize(&self, size: GLfloat) {
+        if let Some(f) = self.point_size {
+            unsafe { f(size) }
+        }
+    }
+
+    /// Not available on GLES which always uses the maximum value.
+    pub unsafe fn PrimitiveRestartIndex(&self, index: GLuint) {
+        if let Some(f) = self.primitive_restart_index {
+            f(index)
+        }
+    }
+
+    pub unsafe fn ReadPixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: PixelFormat,
+        type_: PixelType,
+        pixels: *mut c_void,
+    ) {
+        let len = image_len(width, height, format, type_);
+        let pixels = std::slice::from_raw_parts_mut(pixels.cast::<u8>(), len);
+        self.gl.read_pixels(
+            x,
+            y,
+            width,
+            height,
+            format.0,
+            type_.0,
+            glow::PixelPackData::Slice(pixels),
+        )
+    }
+
+    pub unsafe fn ShaderSource(
+        &self,
+        shader: GLuint,
+        count: GLsizei,
+        string: *const *const GLchar,
+        length: *const GLint,
+    ) {
+        let mut source = String::new();
+        for i in 0..count as usize {
+            let s = *string.add(i);
+            let bytes = if length.is_null() || *length.add(i) < 0 {
+                CStr::from_ptr(s).to_bytes()
+            } else {
+                std::slice::from_raw_parts(s.cast::<u8>(), *length.add(i) as usize)
+            };
+            source.push_str(&String::from_utf8_lossy(bytes));
+        }
+
+        if let Some(s) = native!(NativeShader, shader) {
+            self.gl.shader_source(s, &source)
+        }
+    }
+
+    pub unsafe fn TexImage2D(
+        &self,
+        target: TextureTarget,
+        level: GLint,
+        internalformat: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: PixelFormat,
+        type_: PixelType,
+        pixels: *const c_void,
+    ) {
+        let pixels = (!pixels.is_null()).then(|| {
+            let len = image_len(width, height, format, type_);
+            std::slice::from_raw_parts(pixels.cast::<u8>(), len)
+        });
+
+        self.gl.tex_image_2d(
+            target.0,
+            level,
+            internalformat,
+            width,
+            height,
+            border,
+            format.0,
+            type_.0,
+            pixels,
+        )
+    }
+
+    pub unsafe fn TexParameteri(
+        &self,
+        target: TextureTarget,
+        pname: TextureParameterName,
+        param: GLint,
+    ) {
+        self.gl.tex_parameter_i32(target.0, pname.0, param)
+    }
+
+    pub unsafe fn Uniform1f(&self, location: GLint, v0: GLfloat) {
+        self.gl.uniform_1_f32(self::location(location).as_ref(), v0)
+    }
+
+    pub unsafe fn Uniform1i(&self, location: GLint, v0: GLint) {
+        self.gl.uniform_1_i32(self::location(location).as_ref(), v0)
+    }
+
+    pub unsafe fn Uniform1ui(&self, location: GLint, v0: GLuint) {
+        self.gl.uniform_1_u32(self::location(location).as_ref(), v0)
+    }
+
+    pub unsafe fn Uniform4f(
+        &self,
+        location: GLint,
+        v0: GLfloat,
+        v1: GLfloat,
+        v2: GLfloat,
+        v3: GLfloat,
+    ) {
+        self.gl
+            .uniform_4_f32(self::location(location).as_ref(), v0, v1, v2, v3)
+    }
+
+    pub unsafe fn Uniform2fv(&self, location: GLint, count: GLsizei, value: *const GLfloat) {
+        let v = std::slice::from_raw_parts(value, 2 * count as usize);
+        self.gl
+            .uniform_2_f32_slice(self::location(location).as_ref(), v)
+    }
+
+    pub unsafe fn Uniform3fv(&self, location: GLint, count: GLsizei, value: *const GLfloat) {
+        let v = std::slice::from_raw_parts(value, 3 * count as usize);
+        self.gl
+            .uniform_3_f32_slice(self::location(location).as_ref(), v)
+    }
+
+    pub unsafe fn Uniform4fv(&self, location: GLint, count: GLsizei, value: *const GLfloat) {
+        let v = std::slice::from_raw_parts(value, 4 * count as usize);
+        self.gl
+            .uniform_4_f32_slice(self::location(location).as_ref(), v)
+    }
+
+    pub unsafe fn Uniform2iv(&self, location: GLint, count: GLsizei, value: *const GLint) {
+        let v = std::slice::from_raw_parts(value, 2 * count as usize);
+        self.gl
+            .uniform_2_i32_slice(self::location(location).as_ref(), v)
+    }
+
+    pub unsafe fn Uniform3iv(&self, location: GLint, count: GLsizei, value: *const GLint) {
+        let v = std::slice::from_raw_parts(value, 3 * count as usize);
+        self.gl
+            .uniform_3_i32_slice(self::location(location).as_ref(), v)
+    }
+
+    pub unsafe fn Uniform4iv(&self, location: GLint, count: GLsizei, value: *const GLint) {
+        let v = std::slice::from_raw_parts(value, 4 * count as usize);
+        self.gl
+            .uniform_4_i32_slice(self::location(location).as_ref(), v)
+    }
+
+    pub unsafe fn UniformMatrix2fv(
+        &self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        let v = std::slice::from_raw_parts(value, 4 * count as usize);
+        self.gl
+            .uniform_matrix_2_f32_slice(self::location(location).as_ref(), flag(transpose), v)
+    }
+
+    pub unsafe fn UniformMatrix3fv(
+        &self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        let v = std::slice::from_raw_parts(value, 9 * count as usize);
+        self.gl
+            .uniform_matrix_3_f32_slice(self::location(location).as_ref(), flag(transpose), v)
+    }
+
+    pub unsafe fn UniformMatrix4fv(
+        &self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        let v = std::slice::from_raw_parts(value, 16 * count as usize);
+        self.gl
+            .uniform_matrix_4_f32_slice(self::location(location).as_ref(), flag(transpose), v)
+    }
+
+    pub unsafe fn UnmapBuffer(&self, target: BufferTargetARB) -> GLboolean {
+        self.gl.unmap_buffer(target.0);
+        1
+    }
+
+    pub fn UseProgram(&self, program: GLuint) {
+        unsafe { self.gl.use_program(native!(NativeProgram, program)) }
+    }
+
+    pub unsafe fn VertexAttrib4f(
+        &self,
+        index: GLuint,
+        x: GLfloat,
+        y: GLfloat,
+        z: GLfloat,
+        w: GLfloat,
+    ) {
+        self.gl.vertex_attrib_4_f32(index, x, y, z, w)
+    }
+
+    pub unsafe fn VertexAttribDivisor(&self, index: GLuint, divisor: GLuint) {
+        self.gl.vertex_attrib_divisor(index, divisor)
+    }
+
+    /// glow only takes offsets in the bound buffer, this is loaded directly so that client
+    /// side arrays can be used as well.
+    pub unsafe fn VertexAttribPointer(
+        &self,
+        index: GLuint,
+        size: GLint,
+        type_: VertexAttribPointerType,
+        normalized: GLboolean,
+        stride: GLsizei,
+        pointer: *const c_void,
+    ) {
+        (self.vertex_attrib_pointer)(index, size, type_.0, normalized, stride, pointer)
+    }
+
+    pub unsafe fn Viewport(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        self.gl.viewport(x, y, width, height)
+    }
+}
@@ -0,0 +1,37 @@
+//! GL functions and constants used by libogl and the draw functions.
+//!
+//! The implementation is selected by the features, `enable_gl33` (default) uses gl33 and
+//! `enable_glow` uses glow. Both provide the gl33 names, e.g. `glapi::GlFns::DrawArrays()` and
+//! `glapi::GL_TRIANGLES`, and the function `load()`.
+
+#[cfg(not(any(feature = "enable_gl33", feature = "enable_glow")))]
+compile_error!("Either enable_gl33 or enable_glow feature is required");
+
+#[cfg(feature = "enable_gl33")]
+pub use gl33::*;
+
+#[cfg(all(feature = "enable_glow", not(feature = "enable_gl33")))]
+mod glow_fns;
+#[cfg(all(feature = "enable_glow", not(feature = "enable_gl33")))]
+pub use glow_fns::*;
+
+/// Load the GL functions with `get_proc_address`.
+#[cfg(feature = "enable_gl33")]
+pub fn load(
+    get_proc_address: &dyn Fn(&str) -> *const std::ffi::c_void,
+) -> error_stack::Result<GlFns, super::error::OglError> {
+    use error_stack::Report;
+
+    unsafe {
+        GlFns::load_from(&|p| {
+            let s = std::ffi::CStr::from_ptr(p.cast())
+                .to_str()
+                .unwrap_or_default();
+            get_proc_address(s)
+        })
+    }
+    .map_err(|e| {
+        Report::new(super::error::OglError::GlError)
+            .attach_printable(format!("Failed to load GL functions: {e}"))
+    })
+}
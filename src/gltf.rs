@@ -0,0 +1,1169 @@
+use super::error::OglError;
+use super::glapi;
+use super::json::Json;
+use super::mesh::{GpuMesh, Mesh, MeshVertex};
+use super::texture2d::{Texture2D, Texture2DFilter};
+use error_stack::{Report, Result};
+use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
+use jlogger_tracing::jwarn;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Elements of an accessor without buffer view, whose count cannot be checked against any data.
+const MAX_ZERO_ACCESSOR_COUNT: usize = 1 << 24;
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const GLB_JSON: u32 = 0x4E4F534A;
+const GLB_BIN: u32 = 0x004E4942;
+
+const TRIANGLES: usize = 4;
+const TRIANGLE_STRIP: usize = 5;
+const TRIANGLE_FAN: usize = 6;
+const REPEAT: u32 = 10497;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlphaMode {
+    Opaque,
+    /// Fragments with an alpha below the cutoff are discarded.
+    Mask(f32),
+    Blend,
+}
+
+/// PBR metallic-roughness material. The textures are indices in GltfModel::textures().
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMaterial {
+    name: String,
+    base_color: Vec4,
+    base_color_texture: Option<usize>,
+    metallic: f32,
+    roughness: f32,
+    metallic_roughness_texture: Option<usize>,
+    normal_texture: Option<usize>,
+    normal_scale: f32,
+    occlusion_texture: Option<usize>,
+    occlusion_strength: f32,
+    emissive: Vec3,
+    emissive_texture: Option<usize>,
+    alpha_mode: AlphaMode,
+    double_sided: bool,
+}
+
+impl Default for GltfMaterial {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            base_color: Vec4::ONE,
+            base_color_texture: None,
+            metallic: 1.0,
+            roughness: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive: Vec3::ZERO,
+            emissive_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+            double_sided: false,
+        }
+    }
+}
+
+impl GltfMaterial {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn base_color(&self) -> Vec4 {
+        self.base_color
+    }
+
+    pub fn base_color_texture(&self) -> Option<usize> {
+        self.base_color_texture
+    }
+
+    pub fn metallic(&self) -> f32 {
+        self.metallic
+    }
+
+    pub fn roughness(&self) -> f32 {
+        self.roughness
+    }
+
+    /// Roughness in the green channel and metalness in the blue one.
+    pub fn metallic_roughness_texture(&self) -> Option<usize> {
+        self.metallic_roughness_texture
+    }
+
+    pub fn normal_texture(&self) -> Option<usize> {
+        self.normal_texture
+    }
+
+    pub fn normal_scale(&self) -> f32 {
+        self.normal_scale
+    }
+
+    pub fn occlusion_texture(&self) -> Option<usize> {
+        self.occlusion_texture
+    }
+
+    pub fn occlusion_strength(&self) -> f32 {
+        self.occlusion_strength
+    }
+
+    pub fn emissive(&self) -> Vec3 {
+        self.emissive
+    }
+
+    pub fn emissive_texture(&self) -> Option<usize> {
+        self.emissive_texture
+    }
+
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    pub fn double_sided(&self) -> bool {
+        self.double_sided
+    }
+}
+
+/// Image of GltfModel::images() sampled with the wrap modes of its sampler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GltfTexture {
+    image: Option<usize>,
+    wrap_s: u32,
+    wrap_t: u32,
+}
+
+impl GltfTexture {
+    pub fn image(&self) -> Option<usize> {
+        self.image
+    }
+
+    /// GL_REPEAT, GL_MIRRORED_REPEAT or GL_CLAMP_TO_EDGE.
+    pub fn wrap(&self) -> (u32, u32) {
+        (self.wrap_s, self.wrap_t)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GltfPrimitive {
+    mesh: Mesh,
+    material: Option<usize>,
+}
+
+impl GltfPrimitive {
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    pub fn material(&self) -> Option<usize> {
+        self.material
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GltfMesh {
+    name: String,
+    primitives: Vec<GltfPrimitive>,
+}
+
+impl GltfMesh {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn primitives(&self) -> &[GltfPrimitive] {
+        &self.primitives
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GltfNode {
+    name: String,
+    mesh: Option<usize>,
+    children: Vec<usize>,
+    transform: Mat4,
+}
+
+impl GltfNode {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn mesh(&self) -> Option<usize> {
+        self.mesh
+    }
+
+    pub fn children(&self) -> &[usize] {
+        &self.children
+    }
+
+    /// Transform relative to the parent node.
+    pub fn transform(&self) -> Mat4 {
+        self.transform
+    }
+}
+
+/// glTF 2.0 model read from a .gltf file, with external or embedded buffers, or a .glb file.
+///
+/// The V texture coordinates are flipped to match the images loaded by Texture2D, which have
+/// their origin at the bottom left. Missing normals and tangents are computed.
+#[derive(Debug, Clone, Default)]
+pub struct GltfModel {
+    nodes: Vec<GltfNode>,
+    meshes: Vec<GltfMesh>,
+    materials: Vec<GltfMaterial>,
+    textures: Vec<GltfTexture>,
+    images: Vec<Vec<u8>>,
+    roots: Vec<usize>,
+    instances: Vec<(usize, Mat4)>,
+}
+
+impl GltfModel {
+    /// Load a .gltf or .glb file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, OglError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|e| {
+            Report::new(OglError::InvalidData)
+                .attach_printable(format!("Failed to read {}: {e}", path.display()))
+        })?;
+
+        let dir = path.parent().unwrap_or(Path::new("."));
+        Self::parse(&data, dir).map_err(|e| e.attach_printable(format!("in {}", path.display())))
+    }
+
+    /// Parse the content of a .gltf or .glb file, external files are looked up in `dir`.
+    pub fn parse(data: &[u8], dir: &Path) -> Result<Self, OglError> {
+        let (json, bin) = if data.starts_with(GLB_MAGIC) {
+            read_glb(data)?
+        } else {
+            (data, None)
+        };
+
+        let json = std::str::from_utf8(json)
+            .map_err(|e| invalid(format!("JSON is not UTF-8: {e}")))
+            .and_then(Json::parse)?;
+
+        GltfParser {
+            json: &json,
+            dir,
+            buffers: Vec::new(),
+        }
+        .parse(bin)
+    }
+
+    pub fn nodes(&self) -> &[GltfNode] {
+        &self.nodes
+    }
+
+    pub fn meshes(&self) -> &[GltfMesh] {
+        &self.meshes
+    }
+
+    pub fn materials(&self) -> &[GltfMaterial] {
+        &self.materials
+    }
+
+    pub fn textures(&self) -> &[GltfTexture] {
+        &self.textures
+    }
+
+    /// Encoded PNG or JPEG images.
+    pub fn images(&self) -> &[Vec<u8>] {
+        &self.images
+    }
+
+    /// Root nodes of the default scene.
+    pub fn roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    /// Mesh index and world transform of every node of the default scene which has a mesh.
+    pub fn instances(&self) -> &[(usize, Mat4)] {
+        &self.instances
+    }
+
+    /// Upload the meshes and the textures. A texture failing to decode is skipped with a
+    /// warning.
+    pub fn upload(&self, gl: &Rc<glapi::GlFns>) -> GpuGltfModel {
+        let textures = self
+            .textures
+            .iter()
+            .map(|t| {
+                let data = &self.images[t.image?];
+                let mut texture = Texture2D::default();
+                let loaded = unsafe {
+                    texture.create_from_buffer(data, gl, Texture2DFilter::LinearMiMapLinear)
+                };
+
+                match loaded {
+                    Ok(_) => {
+                        unsafe {
+                            gl.BindTexture(glapi::GL_TEXTURE_2D, texture.id());
+                            gl.TexParameteri(
+                                glapi::GL_TEXTURE_2D,
+                                glapi::GL_TEXTURE_WRAP_S,
+                                t.wrap_s as i32,
+                            );
+                            gl.TexParameteri(
+                                glapi::GL_TEXTURE_2D,
+                                glapi::GL_TEXTURE_WRAP_T,
+                                t.wrap_t as i32,
+                            );
+                            gl.BindTexture(glapi::GL_TEXTURE_2D, 0);
+                        }
+                        Some(texture)
+                    }
+                    Err(e) => {
+                        jwarn!("Failed to decode image {:?}: {:?}", t.image, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        GpuGltfModel {
+            meshes: self
+                .meshes
+                .iter()
+                .map(|m| {
+                    m.primitives
+                        .iter()
+                        .map(|p| (p.mesh.upload(gl), p.material))
+                        .collect()
+                })
+                .collect(),
+            materials: self.materials.clone(),
+            textures,
+            instances: self.instances.clone(),
+        }
+    }
+}
+
+/// GltfModel uploaded by GltfModel::upload().
+#[derive(Default)]
+pub struct GpuGltfModel {
+    meshes: Vec<Vec<(GpuMesh, Option<usize>)>>,
+    materials: Vec<GltfMaterial>,
+    textures: Vec<Option<Texture2D>>,
+    instances: Vec<(usize, Mat4)>,
+}
+
+impl GpuGltfModel {
+    /// Texture of a material, e.g. `texture(material.base_color_texture())`.
+    pub fn texture(&self, index: Option<usize>) -> Option<&Texture2D> {
+        index.and_then(|i| self.textures.get(i)?.as_ref())
+    }
+
+    /// Draw every primitive of the scene, calling `f` with the world transform and the
+    /// material to set up the program before each of them.
+    pub fn draw<F>(&self, mut f: F) -> Result<(), OglError>
+    where
+        F: FnMut(&Mat4, Option<&GltfMaterial>) -> Result<(), OglError>,
+    {
+        for (mesh, transform) in &self.instances {
+            for (primitive, material) in &self.meshes[*mesh] {
+                f(transform, material.map(|i| &self.materials[i]))?;
+                primitive.draw();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Delete the textures, the meshes are deleted when dropped.
+    pub fn delete(&mut self, gl: &glapi::GlFns) {
+        for texture in self.textures.iter_mut().flatten() {
+            unsafe { texture.delete(gl) };
+        }
+    }
+}
+
+struct GltfParser<'a> {
+    json: &'a Json,
+    dir: &'a Path,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl GltfParser<'_> {
+    fn parse(mut self, bin: Option<&[u8]>) -> Result<GltfModel, OglError> {
+        let version = self
+            .json
+            .get("asset")
+            .and_then(|a| a.get("version"))
+            .and_then(Json::as_str)
+            .ok_or_else(|| invalid("no asset.version".to_owned()))?;
+        if !version.starts_with("2.") {
+            return Err(unsupported(format!("glTF version {version}")));
+        }
+
+        let required: Vec<&str> = array(self.json, "extensionsRequired")
+            .iter()
+            .filter_map(Json::as_str)
+            .collect();
+        if !required.is_empty() {
+            return Err(unsupported(format!(
+                "glTF extension {}",
+                required.join(", ")
+            )));
+        }
+        for extension in array(self.json, "extensionsUsed") {
+            jwarn!(
+                "Ignore glTF extension {}",
+                extension.as_str().unwrap_or("?")
+            );
+        }
+
+        for (i, buffer) in array(self.json, "buffers").iter().enumerate() {
+            let data = match (buffer.get("uri").and_then(Json::as_str), bin) {
+                (Some(uri), _) => self.read_uri(uri)?,
+                (None, Some(bin)) if i == 0 => bin.to_vec(),
+                (None, _) => return Err(invalid(format!("buffer {i} has no uri"))),
+            };
+
+            let length = usize_of(buffer, "byteLength").unwrap_or(0);
+            if data.len() < length {
+                return Err(invalid(format!(
+                    "buffer {i} has {} bytes instead of {length}",
+                    data.len()
+                )));
+            }
+            self.buffers.push(data);
+        }
+
+        let images = array(self.json, "images")
+            .iter()
+            .enumerate()
+            .map(|(i, image)| {
+                match (
+                    image.get("uri").and_then(Json::as_str),
+                    usize_of(image, "bufferView"),
+                ) {
+                    (Some(uri), _) => self.read_uri(uri),
+                    (None, Some(view)) => self.buffer_view(view).map(|(data, _)| data.to_vec()),
+                    (None, None) => Err(invalid(format!("image {i} has no data"))),
+                }
+            })
+            .collect::<Result<Vec<_>, OglError>>()?;
+
+        let samplers = array(self.json, "samplers");
+        let textures = array(self.json, "textures")
+            .iter()
+            .map(|texture| {
+                let sampler = usize_of(texture, "sampler").and_then(|s| samplers.get(s));
+                let wrap = |key| {
+                    sampler
+                        .and_then(|s| s.get(key))
+                        .and_then(Json::as_usize)
+                        .map_or(REPEAT, |w| w as u32)
+                };
+
+                Ok(GltfTexture {
+                    image: index(texture, "source", images.len())?,
+                    wrap_s: wrap("wrapS"),
+                    wrap_t: wrap("wrapT"),
+                })
+            })
+            .collect::<Result<Vec<_>, OglError>>()?;
+
+        let materials = array(self.json, "materials")
+            .iter()
+            .map(|m| material(m, textures.len()))
+            .collect::<Result<Vec<_>, OglError>>()?;
+
+        let meshes = array(self.json, "meshes")
+            .iter()
+            .enumerate()
+            .map(|(i, mesh)| {
+                let primitives = array(mesh, "primitives")
+                    .iter()
+                    .map(|p| self.primitive(p, materials.len()))
+                    .collect::<Result<Vec<_>, OglError>>()
+                    .map_err(|e| e.attach_printable(format!("in mesh {i}")))?;
+
+                Ok(GltfMesh {
+                    name: string_of(mesh, "name"),
+                    primitives,
+                })
+            })
+            .collect::<Result<Vec<_>, OglError>>()?;
+
+        let count = array(self.json, "nodes").len();
+        let nodes = array(self.json, "nodes")
+            .iter()
+            .map(|node| node_of(node, meshes.len(), count))
+            .collect::<Result<Vec<_>, OglError>>()?;
+
+        // Every node has one parent at most, the hierarchy is a forest.
+        let mut parents = vec![None; count];
+        for (i, node) in nodes.iter().enumerate() {
+            for &child in &node.children {
+                if parents[child].replace(i).is_some() {
+                    return Err(invalid(format!("node {child} has several parents")));
+                }
+            }
+        }
+
+        let scenes = array(self.json, "scenes");
+        let roots = match scenes.get(usize_of(self.json, "scene").unwrap_or(0)) {
+            Some(scene) => array(scene, "nodes")
+                .iter()
+                .map(|n| n.as_usize().filter(|n| *n < count))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid("invalid node in scene".to_owned()))?,
+            None => (0..count).filter(|i| parents[*i].is_none()).collect(),
+        };
+
+        for (i, &root) in roots.iter().enumerate() {
+            if parents[root].is_some() || roots[..i].contains(&root) {
+                return Err(invalid(format!("root node {root} has a parent")));
+            }
+        }
+
+        let instances = collect_instances(&nodes, &roots);
+
+        Ok(GltfModel {
+            nodes,
+            meshes,
+            materials,
+            textures,
+            images,
+            roots,
+            instances,
+        })
+    }
+
+    fn primitive(&self, primitive: &Json, materials: usize) -> Result<GltfPrimitive, OglError> {
+        let mode = usize_of(primitive, "mode").unwrap_or(TRIANGLES);
+        if !matches!(mode, TRIANGLES | TRIANGLE_STRIP | TRIANGLE_FAN) {
+            return Err(unsupported(format!(
+                "primitive mode {mode}, only triangles are"
+            )));
+        }
+        if primitive.get("targets").is_some() {
+            jwarn!("Ignore morph targets");
+        }
+
+        let attributes = primitive
+            .get("attributes")
+            .ok_or_else(|| invalid("primitive without attributes".to_owned()))?;
+        let attribute = |name: &str, components: usize| match usize_of(attributes, name) {
+            Some(i) => self
+                .accessor(i, components)
+                .map(|values| Some(values.into_iter().map(|v| v as f32).collect::<Vec<_>>())),
+            None => Ok(None),
+        };
+
+        let positions = attribute("POSITION", 3)?
+            .ok_or_else(|| invalid("primitive without POSITION".to_owned()))?;
+        let normals = attribute("NORMAL", 3)?;
+        let uvs = attribute("TEXCOORD_0", 2)?;
+        let tangents = attribute("TANGENT", 4)?;
+
+        let count = positions.len() / 3;
+        for (name, values, n) in [
+            ("NORMAL", &normals, 3),
+            ("TEXCOORD_0", &uvs, 2),
+            ("TANGENT", &tangents, 4),
+        ] {
+            if values.as_ref().is_some_and(|v| v.len() != count * n) {
+                return Err(invalid(format!("{name} does not have {count} elements")));
+            }
+        }
+
+        let vertices = (0..count)
+            .map(|i| {
+                let normal = normals
+                    .as_ref()
+                    .map_or(Vec3::ZERO, |n| Vec3::from_slice(&n[i * 3..]));
+                let uv = uvs
+                    .as_ref()
+                    .map_or(Vec2::ZERO, |uv| Vec2::new(uv[i * 2], 1.0 - uv[i * 2 + 1]));
+                // Flipping V flips the bitangent.
+                let tangent = tangents.as_ref().map_or(Vec4::ZERO, |t| {
+                    let t = Vec4::from_slice(&t[i * 4..]);
+                    t.truncate().extend(-t.w)
+                });
+
+                MeshVertex::new(Vec3::from_slice(&positions[i * 3..]), normal, uv, tangent)
+            })
+            .collect();
+
+        let indices = match usize_of(primitive, "indices") {
+            Some(i) => self
+                .accessor(i, 1)?
+                .into_iter()
+                .map(|i| i as u32)
+                .collect::<Vec<_>>(),
+            None => (0..count as u32).collect(),
+        };
+        if let Some(i) = indices.iter().find(|i| **i as usize >= count) {
+            return Err(invalid(format!("index {i} out of {count} vertices")));
+        }
+
+        let indices = match mode {
+            TRIANGLE_STRIP => (2..indices.len())
+                .flat_map(|i| match i % 2 {
+                    0 => [indices[i - 2], indices[i - 1], indices[i]],
+                    _ => [indices[i - 1], indices[i - 2], indices[i]],
+                })
+                .collect(),
+            TRIANGLE_FAN => (2..indices.len())
+                .flat_map(|i| [indices[0], indices[i - 1], indices[i]])
+                .collect(),
+            _ => indices,
+        };
+
+        let mut mesh = Mesh::new(vertices, indices);
+        if normals.is_none() {
+            mesh.compute_normals();
+        }
+        if tangents.is_none() {
+            mesh.compute_tangents();
+        }
+
+        Ok(GltfPrimitive {
+            mesh,
+            material: index(primitive, "material", materials)?,
+        })
+    }
+
+    /// Elements of the accessor `i`, which must have `components` components, as a flat list.
+    /// Normalized integers are converted to [0, 1] or [-1, 1].
+    fn accessor(&self, i: usize, components: usize) -> Result<Vec<f64>, OglError> {
+        let accessor = array(self.json, "accessors")
+            .get(i)
+            .ok_or_else(|| invalid(format!("no accessor {i}")))?;
+
+        if accessor.get("sparse").is_some() {
+            return Err(unsupported("sparse accessors".to_owned()));
+        }
+
+        let kind = usize_of(accessor, "componentType").unwrap_or(0);
+        let count = usize_of(accessor, "count").unwrap_or(0);
+        let normalized = accessor.get("normalized").and_then(Json::as_bool) == Some(true);
+        let n = match accessor.get("type").and_then(Json::as_str) {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") => 4,
+            other => return Err(invalid(format!("accessor {i} has type {other:?}"))),
+        };
+        if n != components {
+            return Err(invalid(format!(
+                "accessor {i} has {n} components instead of {components}"
+            )));
+        }
+
+        let (size, scale) = match kind {
+            5120 => (1, 127.0),
+            5121 => (1, 255.0),
+            5122 => (2, 32767.0),
+            5123 => (2, 65535.0),
+            5125 => (4, 1.0),
+            5126 => (4, 1.0),
+            _ => return Err(invalid(format!("accessor {i} has component type {kind}"))),
+        };
+
+        let Some(view) = usize_of(accessor, "bufferView") else {
+            if count > MAX_ZERO_ACCESSOR_COUNT {
+                return Err(invalid(format!("accessor {i} has {count} elements")));
+            }
+            return Ok(vec![0.0; count * n]);
+        };
+        let (data, stride) = self.buffer_view(view)?;
+        let offset = usize_of(accessor, "byteOffset").unwrap_or(0);
+        let stride = stride.unwrap_or(size * n);
+        if stride < size * n {
+            return Err(invalid(format!("buffer view {view} has stride {stride}")));
+        }
+
+        let end = count
+            .checked_sub(1)
+            .map(|last| {
+                stride
+                    .checked_mul(last)
+                    .and_then(|a| a.checked_add(offset))
+                    .and_then(|a| a.checked_add(size * n))
+            })
+            .unwrap_or(Some(0));
+        if end.is_none_or(|end| end > data.len()) {
+            return Err(invalid(format!(
+                "accessor {i} is out of buffer view {view}"
+            )));
+        }
+
+        let mut values = Vec::with_capacity(count * n);
+        for element in 0..count {
+            for c in 0..n {
+                let b = &data[offset + element * stride + c * size..][..size];
+                let value = match kind {
+                    5120 => b[0] as i8 as f64,
+                    5121 => b[0] as f64,
+                    5122 => i16::from_le_bytes([b[0], b[1]]) as f64,
+                    5123 => u16::from_le_bytes([b[0], b[1]]) as f64,
+                    5125 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    _ => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                };
+                values.push(if normalized {
+                    (value / scale).max(-1.0)
+                } else {
+                    value
+                });
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Bytes and stride of a buffer view.
+    fn buffer_view(&self, i: usize) -> Result<(&[u8], Option<usize>), OglError> {
+        let view = array(self.json, "bufferViews")
+            .get(i)
+            .ok_or_else(|| invalid(format!("no buffer view {i}")))?;
+
+        let buffer = usize_of(view, "buffer")
+            .and_then(|b| self.buffers.get(b))
+            .ok_or_else(|| invalid(format!("buffer view {i} has no buffer")))?;
+        let offset = usize_of(view, "byteOffset").unwrap_or(0);
+        let length = usize_of(view, "byteLength").unwrap_or(0);
+
+        let data = offset
+            .checked_add(length)
+            .and_then(|end| buffer.get(offset..end))
+            .ok_or_else(|| invalid(format!("buffer view {i} is out of its buffer")))?;
+        Ok((data, usize_of(view, "byteStride")))
+    }
+
+    /// Content of a base64 data URI or of a file relative to the model.
+    fn read_uri(&self, uri: &str) -> Result<Vec<u8>, OglError> {
+        if let Some(data) = uri.strip_prefix("data:") {
+            let (header, payload) = data
+                .split_once(',')
+                .ok_or_else(|| invalid("data URI without ','".to_owned()))?;
+            if !header.ends_with(";base64") {
+                return Err(unsupported(format!("data URI {header}")));
+            }
+            return base64(payload).ok_or_else(|| invalid("invalid base64 data".to_owned()));
+        }
+
+        let path = self.dir.join(percent_decode(uri));
+        std::fs::read(&path).map_err(|e| {
+            Report::new(OglError::InvalidData)
+                .attach_printable(format!("Failed to read {}: {e}", path.display()))
+        })
+    }
+}
+
+fn material(m: &Json, textures: usize) -> Result<GltfMaterial, OglError> {
+    let mut material = GltfMaterial {
+        name: string_of(m, "name"),
+        ..Default::default()
+    };
+
+    let texture = |info: Option<&Json>| -> Result<Option<usize>, OglError> {
+        let Some(info) = info else {
+            return Ok(None);
+        };
+        if usize_of(info, "texCoord").is_some_and(|t| t != 0) {
+            jwarn!(
+                "Only TEXCOORD_0 is supported, {} uses another one",
+                material.name
+            );
+        }
+        index(info, "index", textures)
+    };
+
+    if let Some(pbr) = m.get("pbrMetallicRoughness") {
+        if let Some(c) = floats(pbr, "baseColorFactor", 4)? {
+            material.base_color = Vec4::from_slice(&c);
+        }
+        material.base_color_texture = texture(pbr.get("baseColorTexture"))?;
+        material.metallic = float_of(pbr, "metallicFactor").unwrap_or(1.0);
+        material.roughness = float_of(pbr, "roughnessFactor").unwrap_or(1.0);
+        material.metallic_roughness_texture = texture(pbr.get("metallicRoughnessTexture"))?;
+    }
+
+    let normal = m.get("normalTexture");
+    material.normal_texture = texture(normal)?;
+    material.normal_scale = normal.and_then(|n| float_of(n, "scale")).unwrap_or(1.0);
+
+    let occlusion = m.get("occlusionTexture");
+    material.occlusion_texture = texture(occlusion)?;
+    material.occlusion_strength = occlusion
+        .and_then(|o| float_of(o, "strength"))
+        .unwrap_or(1.0);
+
+    if let Some(e) = floats(m, "emissiveFactor", 3)? {
+        material.emissive = Vec3::from_slice(&e);
+    }
+    material.emissive_texture = texture(m.get("emissiveTexture"))?;
+
+    material.alpha_mode = match m.get("alphaMode").and_then(Json::as_str) {
+        None | Some("OPAQUE") => AlphaMode::Opaque,
+        Some("MASK") => AlphaMode::Mask(float_of(m, "alphaCutoff").unwrap_or(0.5)),
+        Some("BLEND") => AlphaMode::Blend,
+        Some(other) => return Err(invalid(format!("alpha mode {other}"))),
+    };
+    material.double_sided = m.get("doubleSided").and_then(Json::as_bool) == Some(true);
+
+    Ok(material)
+}
+
+fn node_of(node: &Json, meshes: usize, nodes: usize) -> Result<GltfNode, OglError> {
+    let transform = match floats(node, "matrix", 16)? {
+        Some(m) => Mat4::from_cols_slice(&m),
+        None => {
+            let t = floats(node, "translation", 3)?.map_or(Vec3::ZERO, |t| Vec3::from_slice(&t));
+            let r = floats(node, "rotation", 4)?
+                .map_or(Quat::IDENTITY, |r| Quat::from_slice(&r).normalize());
+            let s = floats(node, "scale", 3)?.map_or(Vec3::ONE, |s| Vec3::from_slice(&s));
+            Mat4::from_scale_rotation_translation(s, r, t)
+        }
+    };
+
+    let children = array(node, "children")
+        .iter()
+        .map(|c| c.as_usize().filter(|c| *c < nodes))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| invalid("invalid child node".to_owned()))?;
+
+    Ok(GltfNode {
+        name: string_of(node, "name"),
+        mesh: index(node, "mesh", meshes)?,
+        children,
+        transform,
+    })
+}
+
+/// Meshes of the trees under `roots` with their world transform, in depth-first order. The
+/// nodes are expected to have one parent at most.
+fn collect_instances(nodes: &[GltfNode], roots: &[usize]) -> Vec<(usize, Mat4)> {
+    let mut instances = Vec::new();
+    let mut stack: Vec<(usize, Mat4)> = roots.iter().rev().map(|&r| (r, Mat4::IDENTITY)).collect();
+
+    while let Some((i, parent)) = stack.pop() {
+        let node = &nodes[i];
+        let transform = parent * node.transform;
+        if let Some(mesh) = node.mesh {
+            instances.push((mesh, transform));
+        }
+        stack.extend(node.children.iter().rev().map(|&child| (child, transform)));
+    }
+
+    instances
+}
+
+/// Split the GLB container in its JSON and binary chunks.
+fn read_glb(data: &[u8]) -> Result<(&[u8], Option<&[u8]>), OglError> {
+    let u32_at = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    let version = u32_at(4).ok_or_else(|| invalid("truncated GLB header".to_owned()))?;
+    if version != 2 {
+        return Err(unsupported(format!("GLB version {version}")));
+    }
+
+    let length = (u32_at(8).unwrap_or(0) as usize).min(data.len());
+    let mut chunks = Vec::new();
+    let mut offset = 12;
+    while offset + 8 <= length {
+        let size = u32_at(offset).unwrap_or(0) as usize;
+        let kind = u32_at(offset + 4).unwrap_or(0);
+        let chunk = data
+            .get(offset + 8..offset + 8 + size)
+            .ok_or_else(|| invalid("truncated GLB chunk".to_owned()))?;
+        chunks.push((kind, chunk));
+        offset += 8 + size;
+    }
+
+    match chunks.as_slice() {
+        [(GLB_JSON, json), rest @ ..] => Ok((
+            json,
+            rest.iter()
+                .find(|(kind, _)| *kind == GLB_BIN)
+                .map(|(_, b)| *b),
+        )),
+        _ => Err(invalid("GLB without JSON chunk".to_owned())),
+    }
+}
+
+fn base64(data: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    let (mut bits, mut n) = (0u32, 0);
+
+    for c in data.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            _ => return None,
+        };
+
+        bits = (bits << 6) | value as u32;
+        n += 6;
+        if n >= 8 {
+            n -= 8;
+            out.push((bits >> n) as u8);
+            bits &= (1 << n) - 1;
+        }
+    }
+
+    Some(out)
+}
+
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn array<'a>(json: &'a Json, key: &str) -> &'a [Json] {
+    json.get(key).and_then(Json::as_array).unwrap_or_default()
+}
+
+fn usize_of(json: &Json, key: &str) -> Option<usize> {
+    json.get(key).and_then(Json::as_usize)
+}
+
+fn float_of(json: &Json, key: &str) -> Option<f32> {
+    json.get(key).and_then(Json::as_f32)
+}
+
+fn string_of(json: &Json, key: &str) -> String {
+    json.get(key)
+        .and_then(Json::as_str)
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// Array of `n` numbers.
+fn floats(json: &Json, key: &str, n: usize) -> Result<Option<Vec<f32>>, OglError> {
+    let Some(value) = json.get(key) else {
+        return Ok(None);
+    };
+
+    value
+        .as_array()
+        .filter(|a| a.len() == n)
+        .and_then(|a| a.iter().map(Json::as_f32).collect::<Option<Vec<_>>>())
+        .map(Some)
+        .ok_or_else(|| invalid(format!("{key} is not an array of {n} numbers")))
+}
+
+/// Index below `len` referred by `key`.
+fn index(json: &Json, key: &str, len: usize) -> Result<Option<usize>, OglError> {
+    match json.get(key) {
+        None => Ok(None),
+        Some(value) => value
+            .as_usize()
+            .filter(|i| *i < len)
+            .map(Some)
+            .ok_or_else(|| invalid(format!("invalid {key} {value:?}"))),
+    }
+}
+
+fn invalid(msg: String) -> Report<OglError> {
+    Report::new(OglError::InvalidData).attach_printable(format!("glTF: {msg}"))
+}
+
+fn unsupported(feature: String) -> Report<OglError> {
+    Report::new(OglError::Unsupported { feature })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64, GltfModel};
+    use crate::error::OglError;
+    use glam::{Mat4, Vec3, Vec4};
+    use std::path::Path;
+
+    // Positions of a triangle followed by its u16 indices.
+    const TRIANGLE: &str = "AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA=";
+
+    fn gltf(buffer: &str) -> String {
+        format!(
+            r#"{{
+                "asset": {{ "version": "2.0" }},
+                "scene": 0,
+                "scenes": [{{ "nodes": [0] }}],
+                "nodes": [
+                    {{ "name": "root", "translation": [1, 0, 0], "children": [1] }},
+                    {{ "mesh": 0, "scale": [2, 2, 2] }}
+                ],
+                "meshes": [{{
+                    "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}]
+                }}],
+                "materials": [{{
+                    "pbrMetallicRoughness": {{ "baseColorFactor": [1, 0.5, 0, 1], "metallicFactor": 0 }}
+                }}],
+                "accessors": [
+                    {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }},
+                    {{ "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }}
+                ],
+                "bufferViews": [
+                    {{ "buffer": 0, "byteLength": 36 }},
+                    {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }}
+                ],
+                "buffers": [{{ {buffer} "byteLength": 44 }}]
+            }}"#
+        )
+    }
+
+    #[test]
+    fn load_embedded_gltf_and_glb() {
+        let embedded = gltf(&format!(
+            r#""uri": "data:application/octet-stream;base64,{TRIANGLE}","#
+        ));
+
+        let bin = base64(TRIANGLE).unwrap();
+        assert_eq!(bin.len(), 44);
+        let mut json = gltf("").into_bytes();
+        json.resize(json.len().div_ceil(4) * 4, b' ');
+
+        let mut glb = b"glTF".to_vec();
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(12 + 8 + json.len() as u32 + 8 + 44).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(&json);
+        glb.extend_from_slice(&44u32.to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(&bin);
+
+        for data in [embedded.as_bytes(), &glb] {
+            let model = GltfModel::parse(data, Path::new(".")).unwrap();
+
+            let mesh = model.meshes()[0].primitives()[0].mesh();
+            assert_eq!(mesh.indices(), &[0, 1, 2]);
+            assert_eq!(mesh.vertices()[1].position, Vec3::X);
+            assert!(mesh.vertices().iter().all(|v| v.normal == Vec3::Z));
+
+            assert_eq!(model.nodes()[0].name(), "root");
+            assert_eq!(model.roots(), &[0]);
+            assert_eq!(
+                model.instances(),
+                &[(
+                    0,
+                    Mat4::from_translation(Vec3::X) * Mat4::from_scale(Vec3::splat(2.0))
+                )]
+            );
+
+            let material = &model.materials()[0];
+            assert_eq!(material.base_color(), Vec4::new(1.0, 0.5, 0.0, 1.0));
+            assert_eq!(material.metallic(), 0.0);
+            assert_eq!(material.roughness(), 1.0);
+        }
+    }
+
+    #[test]
+    fn required_extensions_are_unsupported() {
+        let json = gltf("").replacen(
+            "{",
+            r#"{ "extensionsRequired": ["KHR_draco_mesh_compression"],"#,
+            1,
+        );
+
+        let e = GltfModel::parse(json.as_bytes(), Path::new(".")).unwrap_err();
+        match e.current_context() {
+            OglError::Unsupported { feature } => {
+                assert!(feature.contains("KHR_draco_mesh_compression"))
+            }
+            other => panic!("{other:?}"),
+        }
+
+        // The buffer has no data.
+        assert!(GltfModel::parse(gltf("").as_bytes(), Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn malformed_sizes_are_rejected() {
+        let embedded = gltf(&format!(
+            r#""uri": "data:application/octet-stream;base64,{TRIANGLE}","#
+        ));
+
+        for (from, to) in [
+            (
+                r#""count": 3, "type": "VEC3""#,
+                r#""count": 1e30, "type": "VEC3""#,
+            ),
+            (r#""byteOffset": 36"#, r#""byteOffset": 1e30"#),
+            (r#""bufferView": 0, "#, r#""count": 1e30, "#),
+            (
+                r#""byteLength": 36 }"#,
+                r#""byteLength": 36, "byteStride": 0 }"#,
+            ),
+        ] {
+            let json = embedded.replacen(from, to, 1);
+            assert!(
+                GltfModel::parse(json.as_bytes(), Path::new(".")).is_err(),
+                "{to}"
+            );
+        }
+    }
+
+    #[test]
+    fn node_hierarchy_is_a_forest() {
+        let embedded = gltf(&format!(
+            r#""uri": "data:application/octet-stream;base64,{TRIANGLE}","#
+        ));
+
+        // Shared child, root with a parent, cycle and root listed twice.
+        for (from, to) in [
+            (r#""children": [1]"#, r#""children": [1, 1]"#),
+            (r#""nodes": [0] }"#, r#""nodes": [0, 1] }"#),
+            (r#""children": [1]"#, r#""children": [0]"#),
+            (r#""nodes": [0] }"#, r#""nodes": [0, 0] }"#),
+        ] {
+            let json = embedded.replacen(from, to, 1);
+            assert!(json != embedded, "{from}");
+            assert!(
+                GltfModel::parse(json.as_bytes(), Path::new(".")).is_err(),
+                "{to}"
+            );
+        }
+
+        // A deep hierarchy does not overflow the stack.
+        const DEPTH: usize = 100_000;
+        let chain = (1..DEPTH)
+            .map(|i| format!(r#"{{ "children": [{i}] }}"#))
+            .chain([r#"{ "mesh": 0 }"#.to_owned()])
+            .collect::<Vec<_>>()
+            .join(",");
+        let json = embedded.replacen(
+            r#"{ "name": "root", "translation": [1, 0, 0], "children": [1] },
+                    { "mesh": 0, "scale": [2, 2, 2] }"#,
+            &chain,
+            1,
+        );
+        assert!(json != embedded);
+        let model = GltfModel::parse(json.as_bytes(), Path::new(".")).unwrap();
+        assert_eq!(model.nodes().len(), DEPTH);
+        assert_eq!(model.instances(), &[(0, Mat4::IDENTITY)]);
+    }
+}
@@ -0,0 +1,199 @@
+#[allow(unused)]
+use {
+    error_stack::{Report, Result, ResultExt},
+    jlogger_tracing::{jdebug, jerror, jinfo, jtrace, jwarn},
+    libogl::demo::RunState,
+    libogl::error::OglError,
+    libogl::gl::GlContextOps,
+    std::cell::Cell,
+};
+
+// EGL_MESA_platform_surfaceless
+const EGL_PLATFORM_SURFACELESS_MESA: khronos_egl::Enum = 0x31DD;
+
+/// Offscreen EGL backend which renders into a pbuffer surface.
+///
+/// It does not need a display server nor a GPU, Mesa's software rasterizer (llvmpipe) is
+/// enough. The surfaceless platform is used when available, otherwise the default display is
+/// used.
+pub struct HeadlessState {
+    egl: khronos_egl::DynamicInstance<khronos_egl::EGL1_4>,
+    egl_display: khronos_egl::Display,
+    egl_surface: khronos_egl::Surface,
+    egl_context: khronos_egl::Context,
+    width: i32,
+    height: i32,
+    frames: Option<usize>,
+    rendered: Cell<usize>,
+}
+
+impl HeadlessState {
+    /// Create an offscreen surface of `width` x `height`.
+    ///
+    /// If `frames` is set, the run loop is stopped after that number of frames is rendered.
+    pub fn new(width: i32, height: i32, frames: Option<usize>) -> Result<Self, OglError> {
+        let lib = unsafe {
+            libloading::Library::new("libEGL.so.1").map_err(|e| {
+                Report::new(OglError::EglError)
+                    .attach_printable(format!("Failed to load libEGL.so.1: {e}"))
+            })?
+        };
+
+        let egl = unsafe {
+            khronos_egl::DynamicInstance::<khronos_egl::EGL1_4>::load_required_from(lib)
+                .map_err(|e| Report::new(OglError::EglError).attach_printable(format!("{e}")))?
+        };
+
+        let egl_display = Self::surfaceless_display(&egl)
+            .or_else(|| unsafe { egl.get_display(khronos_egl::DEFAULT_DISPLAY) })
+            .ok_or(Report::new(OglError::EglError).attach_printable("Failed to get EGL display"))?;
+
+        egl.initialize(egl_display)
+            .map_err(|e| Report::new(OglError::EglError).attach_printable(format!("{e}")))?;
+
+        egl.bind_api(khronos_egl::OPENGL_ES_API)
+            .map_err(|e| Report::new(OglError::EglError).attach_printable(format!("{e}")))?;
+
+        let config_attributes = [
+            khronos_egl::SURFACE_TYPE,
+            khronos_egl::PBUFFER_BIT,
+            khronos_egl::RED_SIZE,
+            8,
+            khronos_egl::GREEN_SIZE,
+            8,
+            khronos_egl::BLUE_SIZE,
+            8,
+            khronos_egl::ALPHA_SIZE,
+            8,
+            khronos_egl::DEPTH_SIZE,
+            16,
+            khronos_egl::RENDERABLE_TYPE,
+            khronos_egl::OPENGL_ES3_BIT,
+            khronos_egl::NONE,
+        ];
+
+        let context_attribute = [khronos_egl::CONTEXT_CLIENT_VERSION, 3, khronos_egl::NONE];
+
+        let config = egl
+            .choose_first_config(egl_display, &config_attributes)
+            .map_err(|e| Report::new(OglError::EglError).attach_printable(format!("{e}")))?
+            .ok_or(Report::new(OglError::EglError).attach_printable("No usable config found"))?;
+
+        let egl_context = egl
+            .create_context(egl_display, config, None, &context_attribute)
+            .map_err(|e| Report::new(OglError::EglError).attach_printable(format!("{e}")))?;
+
+        let surface_attributes = [
+            khronos_egl::WIDTH,
+            width,
+            khronos_egl::HEIGHT,
+            height,
+            khronos_egl::NONE,
+        ];
+
+        let egl_surface = egl
+            .create_pbuffer_surface(egl_display, config, &surface_attributes)
+            .map_err(|e| Report::new(OglError::EglError).attach_printable(format!("{e}")))?;
+
+        egl.make_current(
+            egl_display,
+            Some(egl_surface),
+            Some(egl_surface),
+            Some(egl_context),
+        )
+        .map_err(|e| Report::new(OglError::EglError).attach_printable(format!("{e}")))?;
+
+        jinfo!(
+            "Headless EGL initialized ({}x{}, {})",
+            width,
+            height,
+            egl.query_string(Some(egl_display), khronos_egl::VENDOR)
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        );
+
+        Ok(Self {
+            egl,
+            egl_display,
+            egl_surface,
+            egl_context,
+            width,
+            height,
+            frames,
+            rendered: Cell::new(0),
+        })
+    }
+
+    fn surfaceless_display(
+        egl: &khronos_egl::DynamicInstance<khronos_egl::EGL1_4>,
+    ) -> Option<khronos_egl::Display> {
+        let extensions = egl
+            .query_string(None, khronos_egl::EXTENSIONS)
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if !extensions.contains("EGL_MESA_platform_surfaceless") {
+            jdebug!("EGL_MESA_platform_surfaceless is not supported");
+            return None;
+        }
+
+        let egl = egl.upcast::<khronos_egl::EGL1_5>()?;
+        unsafe {
+            egl.get_platform_display(
+                EGL_PLATFORM_SURFACELESS_MESA,
+                khronos_egl::DEFAULT_DISPLAY,
+                &[khronos_egl::ATTRIB_NONE],
+            )
+            .ok()
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Number of frames which have been swapped so far.
+    pub fn rendered(&self) -> usize {
+        self.rendered.get()
+    }
+
+    pub fn swap_buffers(&self) -> Result<(), OglError> {
+        self.egl
+            .swap_buffers(self.egl_display, self.egl_surface)
+            .map_err(|e| Report::new(OglError::EglError).attach_printable(format!("{e}")))?;
+
+        let rendered = self.rendered.get() + 1;
+        self.rendered.set(rendered);
+        jtrace!(rendered = rendered);
+
+        if let Some(frames) = self.frames {
+            if rendered >= frames {
+                RunState::global_stop();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for HeadlessState {
+    fn drop(&mut self) {
+        let _ = self.egl.make_current(self.egl_display, None, None, None);
+        let _ = self.egl.destroy_surface(self.egl_display, self.egl_surface);
+        let _ = self.egl.destroy_context(self.egl_display, self.egl_context);
+        let _ = self.egl.terminate(self.egl_display);
+    }
+}
+
+impl GlContextOps for HeadlessState {
+    fn get_proc_address(&self, s: &str) -> *mut std::ffi::c_void {
+        self.egl
+            .get_proc_address(s)
+            .map(|f| f as *mut std::ffi::c_void)
+            .unwrap_or(core::ptr::null_mut())
+    }
+}
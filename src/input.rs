@@ -0,0 +1,39 @@
+/// Keys the demos and camera controllers are interested in. Letters and digits are reported
+/// as lowercase `Char`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Escape,
+    Space,
+    Enter,
+    Shift,
+    Control,
+    Alt,
+    Left,
+    Right,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    Other(u32),
+}
+
+/// Input event delivered by a window system backend to the running demo.
+///
+/// Pointer coordinates are in pixels relative to the top left corner of the surface. Scroll
+/// amounts are in wheel steps, positive `dy` when scrolling up / away from the user.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    Key { key: Key, pressed: bool },
+    PointerMotion { x: f32, y: f32 },
+    PointerButton { button: MouseButton, pressed: bool },
+    Scroll { dx: f32, dy: f32 },
+}
@@ -1,5 +1,34 @@
+// The derive macros refer to libogl, also from libogl itself.
+extern crate self as libogl;
+
+pub mod buffer;
+pub mod camera;
+pub mod clock;
+pub mod demo;
 pub mod error;
+pub mod gl;
+pub mod glapi;
+pub mod gltf;
+pub mod input;
+mod json;
+pub mod mesh;
+pub mod obj;
+pub mod ply;
+pub mod preprocess;
+pub mod program_cache;
+pub mod screenshot;
+pub mod shader;
+pub mod stl;
 pub mod texture2d;
+pub mod uniform;
+pub mod vertex;
+pub mod watch;
+
+/// Used by the code generated by the derive macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use error_stack::Result;
+}
 
 pub trait VertexOps {
     fn to_u8_slice(&self) -> &[u8];
@@ -27,6 +56,14 @@ impl VertexOps for [u16] {
     }
 }
 
+impl<V: vertex::Vertex> VertexOps for [V] {
+    fn to_u8_slice(&self) -> &[u8] {
+        unsafe {
+            core::slice::from_raw_parts(self.as_ptr() as *const u8, std::mem::size_of_val(self))
+        }
+    }
+}
+
 impl VertexOps for Vec<f32> {
     fn to_u8_slice(&self) -> &[u8] {
         unsafe {
@@ -1,28 +1,36 @@
 pub mod drawfunc;
 pub mod egl;
-pub mod gl;
+pub mod headless;
 pub mod sdl;
 pub mod wayland;
+pub mod xkb;
 
 #[allow(unused)]
 use {
     clap::{Args, Parser},
     core::borrow::Borrow,
-    drawfunc::{DrawContext, DrawContextOps, DrawFunc, RunState},
     egl::EglState,
     error_stack::{Report, Result, ResultExt},
-    gl::GlState,
+    headless::HeadlessState,
     jlogger_tracing::{
         jdebug, jerror, jinfo, jtrace, jwarn, JloggerBuilder, LevelFilter, LogTimeFormat,
     },
     libm::sqrt,
+    libogl::clock::{FrameClock, MAX_SPEED},
+    libogl::demo::{DemoSwitch, DrawContext, DrawContextOps, RunState},
     libogl::error::OglError,
+    libogl::gl::GlState,
+    libogl::input::{InputEvent, Key, MouseButton},
     sdl::Sdl2State,
+    std::cell::RefCell,
+    std::collections::VecDeque,
     std::f64::consts::PI,
+    std::path::PathBuf,
+    std::rc::Rc,
     std::sync::atomic::{AtomicBool, Ordering},
     std::{fs::File, os::fd::AsFd},
     std::{thread::sleep, time::Duration},
-    wayland::{WaylandEventCb, WaylandState},
+    wayland::{KeyEvent, PointerAxis, WaylandEventCb, WaylandState},
     wayland_client::{
         protocol::wl_keyboard::{self, KeyState},
         protocol::{
@@ -34,6 +42,119 @@ use {
     wayland_protocols::xdg::shell::client::{xdg_surface, xdg_toplevel, xdg_wm_base},
 };
 
+// Linux input event codes reported by wl_keyboard.
+const KEY_ESC: u32 = 1;
+const KEY_1: u32 = 2;
+const KEY_9: u32 = 10;
+const KEY_0: u32 = 11;
+const KEY_Q: u32 = 16;
+const KEY_W: u32 = 17;
+const KEY_E: u32 = 18;
+const KEY_P: u32 = 25;
+const KEY_ENTER: u32 = 28;
+const KEY_LEFTCTRL: u32 = 29;
+const KEY_A: u32 = 30;
+const KEY_S: u32 = 31;
+const KEY_D: u32 = 32;
+const KEY_LEFTSHIFT: u32 = 42;
+const KEY_N: u32 = 49;
+const KEY_RIGHTSHIFT: u32 = 54;
+const KEY_LEFTALT: u32 = 56;
+const KEY_SPACE: u32 = 57;
+const KEY_UP: u32 = 103;
+const KEY_PAGEUP: u32 = 104;
+const KEY_LEFT: u32 = 105;
+const KEY_RIGHT: u32 = 106;
+const KEY_DOWN: u32 = 108;
+const KEY_PAGEDOWN: u32 = 109;
+const BTN_LEFT: u32 = 0x110;
+const BTN_RIGHT: u32 = 0x111;
+const BTN_MIDDLE: u32 = 0x112;
+
+/// Surface-local scroll distance counted as one wheel step for touchpads.
+const SCROLL_STEP: f64 = 10.0;
+
+// Keysyms of the keys which do not type a character.
+const XKB_KEY_RETURN: u32 = 0xff0d;
+const XKB_KEY_ESCAPE: u32 = 0xff1b;
+const XKB_KEY_LEFT: u32 = 0xff51;
+const XKB_KEY_UP: u32 = 0xff52;
+const XKB_KEY_RIGHT: u32 = 0xff53;
+const XKB_KEY_DOWN: u32 = 0xff54;
+const XKB_KEY_PAGE_UP: u32 = 0xff55;
+const XKB_KEY_PAGE_DOWN: u32 = 0xff56;
+const XKB_KEY_KP_ENTER: u32 = 0xff8d;
+const XKB_KEY_SHIFT_L: u32 = 0xffe1;
+const XKB_KEY_SHIFT_R: u32 = 0xffe2;
+const XKB_KEY_CONTROL_L: u32 = 0xffe3;
+const XKB_KEY_CONTROL_R: u32 = 0xffe4;
+const XKB_KEY_ALT_L: u32 = 0xffe9;
+const XKB_KEY_ALT_R: u32 = 0xffea;
+
+/// Key of a wl_keyboard event, following the keymap of the compositor when there is one.
+fn wayland_key(event: &KeyEvent) -> Key {
+    if event.keysym == 0 {
+        return evdev_key(event.code);
+    }
+
+    match event.keysym {
+        XKB_KEY_ESCAPE => Key::Escape,
+        XKB_KEY_RETURN | XKB_KEY_KP_ENTER => Key::Enter,
+        XKB_KEY_SHIFT_L | XKB_KEY_SHIFT_R => Key::Shift,
+        XKB_KEY_CONTROL_L | XKB_KEY_CONTROL_R => Key::Control,
+        XKB_KEY_ALT_L | XKB_KEY_ALT_R => Key::Alt,
+        XKB_KEY_LEFT => Key::Left,
+        XKB_KEY_RIGHT => Key::Right,
+        XKB_KEY_UP => Key::Up,
+        XKB_KEY_DOWN => Key::Down,
+        XKB_KEY_PAGE_UP => Key::PageUp,
+        XKB_KEY_PAGE_DOWN => Key::PageDown,
+        _ => match event.symbol {
+            Some(' ') => Key::Space,
+            Some(c) if !c.is_control() => Key::Char(c.to_lowercase().next().unwrap_or(c)),
+            _ => Key::Unknown,
+        },
+    }
+}
+
+/// Key of a Linux input event code, assuming a US layout.
+fn evdev_key(code: u32) -> Key {
+    match code {
+        KEY_ESC => Key::Escape,
+        KEY_1..=KEY_9 => Key::Char(char::from(b'1' + (code - KEY_1) as u8)),
+        KEY_0 => Key::Char('0'),
+        KEY_Q => Key::Char('q'),
+        KEY_W => Key::Char('w'),
+        KEY_E => Key::Char('e'),
+        KEY_P => Key::Char('p'),
+        KEY_A => Key::Char('a'),
+        KEY_S => Key::Char('s'),
+        KEY_D => Key::Char('d'),
+        KEY_N => Key::Char('n'),
+        KEY_ENTER => Key::Enter,
+        KEY_SPACE => Key::Space,
+        KEY_LEFTSHIFT | KEY_RIGHTSHIFT => Key::Shift,
+        KEY_LEFTCTRL => Key::Control,
+        KEY_LEFTALT => Key::Alt,
+        KEY_UP => Key::Up,
+        KEY_DOWN => Key::Down,
+        KEY_LEFT => Key::Left,
+        KEY_RIGHT => Key::Right,
+        KEY_PAGEUP => Key::PageUp,
+        KEY_PAGEDOWN => Key::PageDown,
+        _ => Key::Unknown,
+    }
+}
+
+fn parse_speed(s: &str) -> std::result::Result<f64, String> {
+    let speed: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    if (0.0..=MAX_SPEED).contains(&speed) {
+        Ok(speed)
+    } else {
+        Err(format!("{speed} is out of [0, {MAX_SPEED}]"))
+    }
+}
+
 #[derive(Parser)]
 #[command(author, version, about, long_about= None)]
 struct Cli {
@@ -49,6 +170,38 @@ struct Cli {
     #[arg(short, long)]
     time_stamp: bool,
 
+    /// Render an OBJ, glTF, STL or PLY model with an orbiting camera instead of a draw function.
+    #[arg(long)]
+    model: Option<PathBuf>,
+
+    /// Save the rendered frame to a PNG file and exit.
+    #[arg(long)]
+    screenshot: Option<String>,
+
+    /// Frame number (counted from 1) to be saved by --screenshot.
+    #[arg(long, default_value_t = 1usize)]
+    at_frame: usize,
+
+    /// Pin the clock seen by draw functions to the given milliseconds.
+    #[arg(long, conflicts_with = "fixed_step")]
+    fixed_time: Option<u64>,
+
+    /// Advance the clock by the given milliseconds on every frame.
+    #[arg(long)]
+    fixed_step: Option<u64>,
+
+    /// Playback speed of the clock, e.g. 0.5 for slow motion.
+    #[arg(long, default_value_t = 1.0f64, value_parser = parse_speed)]
+    speed: f64,
+
+    /// Always compile shaders instead of loading linked programs from the disk cache.
+    #[arg(long)]
+    no_program_cache: bool,
+
+    /// Number of frames to render before exiting in headless mode.
+    #[arg(long, default_value_t = 1usize)]
+    frames: usize,
+
     #[arg(short, long, action=clap::ArgAction::Count)]
     verbose: u8,
 }
@@ -62,13 +215,40 @@ struct ExclusiveOption {
     #[arg(short = 'S', long)]
     sdl: bool,
 
+    #[arg(short = 'H', long)]
+    headless: bool,
+
     #[arg(short, long)]
     list_func: bool,
 }
 
+fn evdev_button(code: u32) -> MouseButton {
+    match code {
+        BTN_LEFT => MouseButton::Left,
+        BTN_RIGHT => MouseButton::Right,
+        BTN_MIDDLE => MouseButton::Middle,
+        _ => MouseButton::Other(code),
+    }
+}
+
+/// Scroll event of a wl_pointer frame, wheel steps are preferred to the continuous amount.
+fn scroll_event(axis: PointerAxis) -> InputEvent {
+    let steps = |value120: i32, value: f64| match value120 {
+        0 => value / SCROLL_STEP,
+        _ => value120 as f64 / 120.0,
+    };
+
+    // Wayland scrolls down with positive values.
+    InputEvent::Scroll {
+        dx: steps(axis.horizontal120, axis.horizontal) as f32,
+        dy: -steps(axis.vertical120, axis.vertical) as f32,
+    }
+}
+
 struct WaylandOps {
     pub ws: WaylandState,
     pub egl: EglState,
+    pub input: Rc<RefCell<VecDeque<InputEvent>>>,
 }
 
 impl DrawContextOps for WaylandOps {
@@ -78,6 +258,24 @@ impl DrawContextOps for WaylandOps {
     fn do_swap(&self) -> Result<(), OglError> {
         self.egl.swap_buffers()
     }
+
+    fn do_poll_input(&mut self) -> Option<InputEvent> {
+        self.input.borrow_mut().pop_front()
+    }
+
+    fn do_poll_resize(&mut self) -> Option<(i32, i32)> {
+        self.ws.take_resize()
+    }
+}
+
+impl DrawContextOps for HeadlessState {
+    fn do_dispatch(&mut self) -> Result<(), OglError> {
+        Ok(())
+    }
+
+    fn do_swap(&self) -> Result<(), OglError> {
+        self.swap_buffers()
+    }
 }
 
 impl DrawContextOps for Sdl2State {
@@ -88,6 +286,34 @@ impl DrawContextOps for Sdl2State {
     fn do_swap(&self) -> Result<(), OglError> {
         self.swap_window()
     }
+
+    fn do_poll_input(&mut self) -> Option<InputEvent> {
+        self.poll_input()
+    }
+}
+
+fn setup_draw_context(dt: &mut DrawContext, cli: &Cli) {
+    let mut clock = if let Some(ms) = cli.fixed_time {
+        let mut clock = FrameClock::manual();
+        clock.seek(Duration::from_millis(ms));
+        clock
+    } else if let Some(ms) = cli.fixed_step {
+        FrameClock::fixed_step(Duration::from_millis(ms))
+    } else {
+        FrameClock::real_time()
+    };
+
+    // Validated by parse_speed().
+    let _ = clock.set_speed(cli.speed);
+    dt.set_clock(clock);
+
+    if cli.no_program_cache {
+        dt.gl_mut().set_program_cache(None);
+    }
+
+    if let Some(path) = cli.screenshot.as_deref() {
+        dt.set_screenshot(path, cli.at_frame);
+    }
 }
 
 fn main() -> Result<(), OglError> {
@@ -120,57 +346,124 @@ fn main() -> Result<(), OglError> {
     let width = w[0];
     let height = w[1];
 
+    let mut registry = drawfunc::registry();
+
     if cli.exclusive.list_func {
         jinfo!("All functions:");
-        for func in (1_usize..100_usize).into_iter().map(|a| DrawFunc::from(a)) {
-            if func == DrawFunc::InvalidDrawFunc {
-                break;
-            }
-
-            jinfo!("{}", func);
+        for entry in registry.iter() {
+            jinfo!("{}", entry);
         }
 
         std::process::exit(0);
     }
 
-    match DrawFunc::from(cli.func) {
-        DrawFunc::InvalidDrawFunc => {
-            jerror!("Invalid draw function\n");
-            std::process::exit(1);
-        }
+    let func = match cli.model.clone() {
+        Some(path) => registry.register_with("DrawModel", move || {
+            Box::new(drawfunc::draw_model::DrawModel::new(path.clone()))
+        }),
+        None => cli.func,
+    };
 
-        DrawFunc::DrawTextureCubeMap => {
-            jerror!("Not implemented\n");
+    let entry = match registry.get(func) {
+        Some(entry) => entry,
+        None => {
+            jerror!("Invalid draw function\n");
             std::process::exit(1);
         }
-        _ => {}
-    }
+    };
 
     if cli.exclusive.wayland {
+        let input: Rc<RefCell<VecDeque<InputEvent>>> = Rc::default();
+        let queue = |input: &Rc<RefCell<VecDeque<InputEvent>>>| {
+            let input = input.clone();
+            move |event: InputEvent| input.borrow_mut().push_back(event)
+        };
+        let (pressed, released) = (queue(&input), queue(&input));
+        let (enter, motion, button, axis) =
+            (queue(&input), queue(&input), queue(&input), queue(&input));
         let ws_cb = WaylandEventCb {
-            key_pressed: Some(Box::new(|key: u32| {
-                if key == 1 {
-                    RunState::global_stop()
+            key_pressed: Some(Box::new(move |event: &KeyEvent| {
+                let key = wayland_key(event);
+                pressed(InputEvent::Key { key, pressed: true });
+                if event.repeat {
+                    return;
+                }
+
+                match (key, event.code) {
+                    (Key::Escape, _) => RunState::global_stop(),
+                    (Key::Char('p') | Key::Left, _) => {
+                        RunState::request_switch(DemoSwitch::Previous)
+                    }
+                    (Key::Char('n') | Key::Right, _) => RunState::request_switch(DemoSwitch::Next),
+                    // The digit row by position, the digits are shifted on some layouts.
+                    (_, KEY_1..=KEY_9) => {
+                        RunState::request_switch(DemoSwitch::Id((event.code - KEY_1 + 1) as usize))
+                    }
+                    (_, KEY_0) => RunState::request_switch(DemoSwitch::Id(10)),
+                    _ => {}
                 }
             })),
-            key_released: None,
+            key_released: Some(Box::new(move |event: &KeyEvent| {
+                released(InputEvent::Key {
+                    key: wayland_key(event),
+                    pressed: false,
+                })
+            })),
+            pointer_enter: Some(Box::new(move |x, y| {
+                enter(InputEvent::PointerMotion {
+                    x: x as f32,
+                    y: y as f32,
+                })
+            })),
+            pointer_leave: None,
+            pointer_motion: Some(Box::new(move |x, y| {
+                motion(InputEvent::PointerMotion {
+                    x: x as f32,
+                    y: y as f32,
+                })
+            })),
+            pointer_button: Some(Box::new(move |code, pressed| {
+                button(InputEvent::PointerButton {
+                    button: evdev_button(code),
+                    pressed,
+                })
+            })),
+            pointer_axis: Some(Box::new(move |a| axis(scroll_event(a)))),
         };
 
         let mut ws = WaylandState::new(Some(ws_cb))?;
         let egl = EglState::new(ws.display(), ws.egl_window(width, height)?)?;
-        let gl = GlState::new(&egl, None, None)?;
         egl.swap_interval(1)?;
 
+        // Declared before the DrawContext so that the GL objects are deleted while the EGL
+        // context still exists.
+        let mut w = WaylandOps { ws, egl, input };
+        let gl = GlState::new(&w.egl, None, None)?;
         let mut dt = DrawContext::new(gl, width, height);
-        let mut w = WaylandOps { ws, egl };
+        setup_draw_context(&mut dt, &cli);
 
-        dt.run(&mut w, cli.func.into())?;
+        dt.run_registry(&mut w, &registry, entry.id())?;
     } else if cli.exclusive.sdl {
         let mut sdl = Sdl2State::new(width, height)?;
         let gl = GlState::new(&sdl, None, None)?;
         let mut dt = DrawContext::new(gl, width, height);
+        setup_draw_context(&mut dt, &cli);
+
+        dt.run_registry(&mut sdl, &registry, entry.id())?;
+    } else if cli.exclusive.headless {
+        // The screenshot stops the run loop by itself.
+        let frames = match cli.screenshot {
+            Some(_) => None,
+            None => Some(cli.frames),
+        };
+
+        let mut headless = HeadlessState::new(width, height, frames)?;
+        let gl = GlState::new(&headless, None, None)?;
+        let mut dt = DrawContext::new(gl, width, height);
+        setup_draw_context(&mut dt, &cli);
 
-        dt.run(&mut sdl, cli.func.into())?;
+        dt.run_registry(&mut headless, &registry, entry.id())?;
+        jinfo!("{} frames rendered", headless.rendered());
     }
 
     Ok(())
@@ -0,0 +1,587 @@
+use super::buffer::{Buffer, VertexArray};
+use super::glapi;
+use super::vertex::Vertex;
+use glam::{Vec2, Vec3, Vec4};
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+use std::rc::Rc;
+
+/// Vertex of a Mesh. `tangent.w` is the handedness of the bitangent, cross(normal, tangent.xyz)
+/// * tangent.w. `color` is white unless the vertices are colored, e.g. in PLY files.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Vertex)]
+pub struct MeshVertex {
+    #[location = 0]
+    pub position: Vec3,
+    #[location = 1]
+    pub normal: Vec3,
+    #[location = 2]
+    pub uv: Vec2,
+    #[location = 3]
+    pub tangent: Vec4,
+    #[location = 4]
+    pub color: Vec4,
+}
+
+impl MeshVertex {
+    pub fn new(position: Vec3, normal: Vec3, uv: Vec2, tangent: Vec4) -> Self {
+        Self {
+            position,
+            normal,
+            uv,
+            tangent,
+            color: Vec4::ONE,
+        }
+    }
+
+    pub fn with_color(self, color: Vec4) -> Self {
+        Self { color, ..self }
+    }
+}
+
+impl Default for MeshVertex {
+    fn default() -> Self {
+        Self::new(Vec3::ZERO, Vec3::ZERO, Vec2::ZERO, Vec4::ZERO)
+    }
+}
+
+/// Indexed triangle list.
+///
+/// The generated shapes are centered on the origin with counter-clockwise front faces. Flat
+/// shapes (plane, grid and disc) lie in the XY plane facing +Z, the axis of the cylinder and the
+/// cone is Y.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+    vertices: Vec<MeshVertex>,
+    indices: Vec<u32>,
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<MeshVertex>, indices: Vec<u32>) -> Self {
+        Self { vertices, indices }
+    }
+
+    pub fn vertices(&self) -> &[MeshVertex] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// `size` x `size` square.
+    pub fn plane(size: f32) -> Self {
+        Self::grid(size, size, 1, 1)
+    }
+
+    /// `width` x `height` rectangle split in `columns` x `rows` cells.
+    pub fn grid(width: f32, height: f32, columns: u32, rows: u32) -> Self {
+        let mut mesh = Self::default();
+        mesh.push_face(
+            Vec3::new(-width / 2.0, -height / 2.0, 0.0),
+            Vec3::X * width,
+            Vec3::Y * height,
+            columns.max(1),
+            rows.max(1),
+        );
+        mesh
+    }
+
+    /// Cube of edge `size`, every face has its own vertices and UVs covering the whole texture.
+    pub fn cube(size: f32) -> Self {
+        let h = size / 2.0;
+        let mut mesh = Self::default();
+
+        // (normal, u, v) with cross(u, v) == normal.
+        for (n, u, v) in [
+            (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+            (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+            (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+            (Vec3::Z, Vec3::X, Vec3::Y),
+            (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y),
+        ] {
+            mesh.push_face((n - u - v) * h, u * size, v * size, 1, 1);
+        }
+
+        mesh
+    }
+
+    /// Sphere made of `segments` meridians and `rings` parallels, u goes around Y and v from the
+    /// bottom to the top.
+    pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Self {
+        let mut mesh = Self::default();
+        mesh.push_parametric(segments.max(3), rings.max(2), |u, v| {
+            let theta = u * TAU;
+            let phi = (1.0 - v) * PI;
+            let normal = Vec3::new(phi.sin() * theta.cos(), phi.cos(), -phi.sin() * theta.sin());
+            (normal * radius, normal, around_y(theta))
+        });
+        mesh
+    }
+
+    /// Sphere made by splitting every triangle of an icosahedron in four `subdivisions` times.
+    pub fn icosphere(radius: f32, subdivisions: u32) -> Self {
+        let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+        let mut points: Vec<Vec3> = [
+            (-1.0, t, 0.0),
+            (1.0, t, 0.0),
+            (-1.0, -t, 0.0),
+            (1.0, -t, 0.0),
+            (0.0, -1.0, t),
+            (0.0, 1.0, t),
+            (0.0, -1.0, -t),
+            (0.0, 1.0, -t),
+            (t, 0.0, -1.0),
+            (t, 0.0, 1.0),
+            (-t, 0.0, -1.0),
+            (-t, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|&(x, y, z)| Vec3::new(x, y, z).normalize())
+        .collect();
+
+        #[rustfmt::skip]
+        let mut triangles: Vec<[u32; 3]> = vec![
+            [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+            [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+            [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+            [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions {
+            let mut middles: HashMap<(u32, u32), u32> = HashMap::new();
+            let mut middle = |a: u32, b: u32, points: &mut Vec<Vec3>| {
+                *middles.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    points.push((points[a as usize] + points[b as usize]).normalize());
+                    points.len() as u32 - 1
+                })
+            };
+
+            triangles = triangles
+                .iter()
+                .flat_map(|&[a, b, c]| {
+                    let ab = middle(a, b, &mut points);
+                    let bc = middle(b, c, &mut points);
+                    let ca = middle(c, a, &mut points);
+                    [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        let mut mesh = Self::default();
+        for &p in &points {
+            let theta = (-p.z).atan2(p.x).rem_euclid(TAU);
+            mesh.vertices.push(MeshVertex::new(
+                p * radius,
+                p,
+                Vec2::new(theta / TAU, 0.5 + p.y.clamp(-1.0, 1.0).asin() / PI),
+                around_y(theta),
+            ));
+        }
+
+        // Triangles crossing the seam at u == 0 get copies of their vertices with u + 1 so that
+        // the UVs are not interpolated through the whole texture.
+        let mut seam: HashMap<u32, u32> = HashMap::new();
+        for triangle in &mut triangles {
+            let u = triangle.map(|i| mesh.vertices[i as usize].uv.x);
+            let max = u.iter().copied().fold(0.0, f32::max);
+            if max - u.iter().copied().fold(1.0, f32::min) <= 0.5 {
+                continue;
+            }
+
+            for (i, index) in triangle.iter_mut().enumerate() {
+                if max - u[i] > 0.5 {
+                    *index = *seam.entry(*index).or_insert_with(|| {
+                        let mut v = mesh.vertices[*index as usize];
+                        v.uv.x += 1.0;
+                        mesh.vertices.push(v);
+                        mesh.vertices.len() as u32 - 1
+                    });
+                }
+            }
+        }
+
+        mesh.indices = triangles.into_iter().flatten().collect();
+        mesh
+    }
+
+    /// Closed cylinder of `height` along Y.
+    pub fn cylinder(radius: f32, height: f32, segments: u32) -> Self {
+        let segments = segments.max(3);
+        let mut mesh = Self::default();
+
+        mesh.push_parametric(segments, 1, |u, v| {
+            let theta = u * TAU;
+            let normal = Vec3::new(theta.cos(), 0.0, -theta.sin());
+            let position = normal * radius + Vec3::Y * (v - 0.5) * height;
+            (position, normal, around_y(theta))
+        });
+
+        let h = Vec3::Y * height / 2.0;
+        mesh.push_disc(h, Vec3::X, Vec3::NEG_Z, radius, segments);
+        mesh.push_disc(-h, Vec3::X, Vec3::Z, radius, segments);
+        mesh
+    }
+
+    /// Cone of `height` along Y with its base closed.
+    pub fn cone(radius: f32, height: f32, segments: u32) -> Self {
+        let segments = segments.max(3);
+        let mut mesh = Self::default();
+
+        mesh.push_parametric(segments, 1, |u, v| {
+            let theta = u * TAU;
+            let around = Vec3::new(theta.cos(), 0.0, -theta.sin());
+            let position = around * radius * (1.0 - v) + Vec3::Y * (v - 0.5) * height;
+            let normal = (around * height + Vec3::Y * radius).normalize();
+            (position, normal, around_y(theta))
+        });
+
+        mesh.push_disc(
+            Vec3::NEG_Y * height / 2.0,
+            Vec3::X,
+            Vec3::Z,
+            radius,
+            segments,
+        );
+        mesh
+    }
+
+    /// Torus around Y. `radius` is the distance from the center to the middle of the tube.
+    pub fn torus(radius: f32, tube_radius: f32, segments: u32, sides: u32) -> Self {
+        let mut mesh = Self::default();
+        mesh.push_parametric(segments.max(3), sides.max(3), |u, v| {
+            let theta = u * TAU;
+            let phi = v * TAU;
+            let around = Vec3::new(theta.cos(), 0.0, -theta.sin());
+            let normal = around * phi.cos() + Vec3::Y * phi.sin();
+            (
+                around * radius + normal * tube_radius,
+                normal,
+                around_y(theta),
+            )
+        });
+        mesh
+    }
+
+    /// Disc made of `segments` triangles around its center.
+    pub fn disc(radius: f32, segments: u32) -> Self {
+        let mut mesh = Self::default();
+        mesh.push_disc(Vec3::ZERO, Vec3::X, Vec3::Y, radius, segments.max(3));
+        mesh
+    }
+
+    /// Compute smooth normals by averaging the faces around every position, weighted by their
+    /// area, e.g. for meshes read from files without them. Vertices sharing a position get the
+    /// same normal even if their UVs differ.
+    pub fn compute_normals(&mut self) {
+        self.smooth_normals(|_| true);
+    }
+
+    /// Compute smooth normals like compute_normals(), only for the vertices whose normal is zero,
+    /// e.g. for files in which some faces have normals and others do not.
+    pub fn compute_missing_normals(&mut self) {
+        self.smooth_normals(|v| v.normal == Vec3::ZERO);
+    }
+
+    fn smooth_normals<F>(&mut self, missing: F)
+    where
+        F: Fn(&MeshVertex) -> bool,
+    {
+        let key = |p: Vec3| p.to_array().map(f32::to_bits);
+        let mut normals: HashMap<[u32; 3], Vec3> = HashMap::new();
+
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| self.vertices[triangle[i] as usize].position);
+            let face = (b - a).cross(c - a);
+            for p in [a, b, c] {
+                *normals.entry(key(p)).or_default() += face;
+            }
+        }
+
+        for v in self.vertices.iter_mut().filter(|v| missing(v)) {
+            v.normal = normals
+                .get(&key(v.position))
+                .and_then(|n| n.try_normalize())
+                .unwrap_or(Vec3::Z);
+        }
+    }
+
+    /// Compute the tangents from the positions, normals and UVs, e.g. for meshes read from files
+    /// without them. Vertices without usable UVs get any tangent orthogonal to their normal.
+    pub fn compute_tangents(&mut self) {
+        let mut tangents = vec![Vec3::ZERO; self.vertices.len()];
+        let mut bitangents = vec![Vec3::ZERO; self.vertices.len()];
+
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| self.vertices[triangle[i] as usize]);
+            let (e1, e2) = (b.position - a.position, c.position - a.position);
+            let (d1, d2) = (b.uv - a.uv, c.uv - a.uv);
+
+            let det = d1.x * d2.y - d2.x * d1.y;
+            if det.abs() <= f32::EPSILON {
+                continue;
+            }
+
+            let t = (e1 * d2.y - e2 * d1.y) / det;
+            let b = (e2 * d1.x - e1 * d2.x) / det;
+            for &i in triangle {
+                tangents[i as usize] += t;
+                bitangents[i as usize] += b;
+            }
+        }
+
+        for (i, v) in self.vertices.iter_mut().enumerate() {
+            let n = v.normal;
+            let t = (tangents[i] - n * n.dot(tangents[i])).try_normalize();
+            v.tangent = match t {
+                Some(t) => {
+                    let w = if n.cross(t).dot(bitangents[i]) < 0.0 {
+                        -1.0
+                    } else {
+                        1.0
+                    };
+                    t.extend(w)
+                }
+                None => n.any_orthonormal_vector().extend(1.0),
+            };
+        }
+    }
+
+    /// Upload the mesh to a new vertex array.
+    pub fn upload(&self, gl: &Rc<glapi::GlFns>) -> GpuMesh {
+        let vao = VertexArray::new(gl);
+        let vertices = Buffer::with_data(
+            gl,
+            glapi::GL_ARRAY_BUFFER,
+            &self.vertices,
+            glapi::GL_STATIC_DRAW,
+        );
+        vao.set_vertices(&vertices);
+
+        // Recorded in the vertex array which is still bound.
+        let indices = Buffer::with_data(
+            gl,
+            glapi::GL_ELEMENT_ARRAY_BUFFER,
+            &self.indices,
+            glapi::GL_STATIC_DRAW,
+        );
+
+        vao.unbind();
+        vertices.unbind();
+        indices.unbind();
+
+        GpuMesh {
+            gl: Some(gl.clone()),
+            vao,
+            vertices,
+            indices,
+        }
+    }
+
+    /// Rectangle from `origin` to `origin + u + v` split in `columns` x `rows` cells, facing
+    /// cross(u, v).
+    fn push_face(&mut self, origin: Vec3, u: Vec3, v: Vec3, columns: u32, rows: u32) {
+        let normal = u.cross(v).normalize();
+        let tangent = u.normalize().extend(1.0);
+        self.push_parametric(columns, rows, |s, t| {
+            (origin + u * s + v * t, normal, tangent)
+        });
+    }
+
+    /// Grid of (`columns` + 1) x (`rows` + 1) vertices given by `f(u, v)` with u and v in [0, 1].
+    /// The front faces look at cross(d/du, d/dv). Triangles collapsed at a pole or an apex are
+    /// dropped.
+    fn push_parametric<F>(&mut self, columns: u32, rows: u32, f: F)
+    where
+        F: Fn(f32, f32) -> (Vec3, Vec3, Vec4),
+    {
+        let base = self.vertices.len() as u32;
+        for j in 0..=rows {
+            for i in 0..=columns {
+                let uv = Vec2::new(i as f32 / columns as f32, j as f32 / rows as f32);
+                let (position, normal, tangent) = f(uv.x, uv.y);
+                self.vertices
+                    .push(MeshVertex::new(position, normal, uv, tangent));
+            }
+        }
+
+        let stride = columns + 1;
+        for j in 0..rows {
+            for i in 0..columns {
+                let a = base + j * stride + i;
+                let (b, c, d) = (a + 1, a + stride, a + stride + 1);
+                for triangle in [[a, b, d], [a, d, c]] {
+                    if !self.is_degenerate(triangle) {
+                        self.indices.extend_from_slice(&triangle);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Disc of `segments` triangles around `center` facing cross(u, v).
+    fn push_disc(&mut self, center: Vec3, u: Vec3, v: Vec3, radius: f32, segments: u32) {
+        let normal = u.cross(v).normalize();
+        let tangent = u.extend(1.0);
+
+        let base = self.vertices.len() as u32;
+        self.vertices
+            .push(MeshVertex::new(center, normal, Vec2::splat(0.5), tangent));
+
+        for i in 0..=segments {
+            let theta = i as f32 * TAU / segments as f32;
+            let (sin, cos) = theta.sin_cos();
+            self.vertices.push(MeshVertex::new(
+                center + (u * cos + v * sin) * radius,
+                normal,
+                Vec2::new(0.5 + cos / 2.0, 0.5 + sin / 2.0),
+                tangent,
+            ));
+        }
+
+        for i in 0..segments {
+            self.indices
+                .extend_from_slice(&[base, base + 1 + i, base + 2 + i]);
+        }
+    }
+
+    fn is_degenerate(&self, triangle: [u32; 3]) -> bool {
+        let [a, b, c] = triangle.map(|i| self.vertices[i as usize].position);
+        let (e1, e2) = (b - a, c - a);
+        let longest = e1
+            .length_squared()
+            .max(e2.length_squared())
+            .max((c - b).length_squared());
+        e1.cross(e2).length_squared() <= longest * longest * 1e-10
+    }
+}
+
+/// Tangent of a surface of revolution around Y at angle `theta`, pointing to increasing theta.
+fn around_y(theta: f32) -> Vec4 {
+    Vec4::new(-theta.sin(), 0.0, -theta.cos(), 1.0)
+}
+
+/// Mesh uploaded by Mesh::upload(), drawn with a single call.
+#[derive(Default)]
+pub struct GpuMesh {
+    gl: Option<Rc<glapi::GlFns>>,
+    vao: VertexArray,
+    vertices: Buffer<MeshVertex>,
+    indices: Buffer<u32>,
+}
+
+impl GpuMesh {
+    pub fn vertex_array(&self) -> &VertexArray {
+        &self.vao
+    }
+
+    pub fn index_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Draw the triangles with the current program.
+    pub fn draw(&self) {
+        self.draw_range(0, self.indices.len());
+    }
+
+    /// Draw the triangles of `count` indices starting at index `first`.
+    pub fn draw_range(&self, first: usize, count: usize) {
+        if let Some(gl) = self.gl.as_ref() {
+            gl.BindVertexArray(self.vao.id());
+            unsafe {
+                gl.DrawElements(
+                    glapi::GL_TRIANGLES,
+                    count as i32,
+                    glapi::GL_UNSIGNED_INT,
+                    (first * std::mem::size_of::<u32>()) as *const std::ffi::c_void,
+                )
+            };
+            gl.BindVertexArray(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mesh;
+
+    fn shapes() -> Vec<(&'static str, Mesh)> {
+        vec![
+            ("plane", Mesh::plane(2.0)),
+            ("grid", Mesh::grid(2.0, 1.0, 4, 3)),
+            ("cube", Mesh::cube(1.0)),
+            ("uv_sphere", Mesh::uv_sphere(1.0, 16, 8)),
+            ("icosphere", Mesh::icosphere(1.0, 2)),
+            ("cylinder", Mesh::cylinder(0.5, 1.0, 12)),
+            ("cone", Mesh::cone(0.5, 1.0, 12)),
+            ("torus", Mesh::torus(1.0, 0.25, 16, 8)),
+            ("disc", Mesh::disc(1.0, 12)),
+        ]
+    }
+
+    #[test]
+    fn shapes_are_consistent() {
+        for (name, mesh) in shapes() {
+            assert!(!mesh.indices().is_empty(), "{name}");
+            assert_eq!(mesh.indices().len() % 3, 0, "{name}");
+
+            for v in mesh.vertices() {
+                assert!((v.normal.length() - 1.0).abs() < 1e-4, "{name}: {v:?}");
+                assert!(
+                    (v.tangent.truncate().length() - 1.0).abs() < 1e-4,
+                    "{name}: {v:?}"
+                );
+                assert!(
+                    v.normal.dot(v.tangent.truncate()).abs() < 1e-4,
+                    "{name}: {v:?}"
+                );
+            }
+
+            // Front faces are counter-clockwise seen from the side the normals point to.
+            for t in mesh.indices().chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices()[t[i] as usize]);
+                let face = (b.position - a.position).cross(c.position - a.position);
+                assert!(face.length() > 0.0, "{name}: degenerate {t:?}");
+                assert!(
+                    face.dot(a.normal + b.normal + c.normal) > 0.0,
+                    "{name}: {t:?}"
+                );
+            }
+        }
+
+        assert_eq!(Mesh::cube(1.0).vertices().len(), 24);
+        assert_eq!(Mesh::cube(1.0).indices().len(), 36);
+        assert_eq!(Mesh::icosphere(1.0, 0).indices().len(), 60);
+        assert_eq!(Mesh::icosphere(1.0, 1).indices().len(), 240);
+    }
+
+    #[test]
+    fn computed_tangents_follow_uvs() {
+        for (name, mesh) in shapes() {
+            let mut computed = mesh.clone();
+            computed.compute_tangents();
+
+            for (v, c) in mesh.vertices().iter().zip(computed.vertices()) {
+                // The UVs are distorted around the poles of the spheres and the apex of the cone.
+                let pole = name.ends_with("sphere") && v.normal.y.abs() > 0.9;
+                let apex = name == "cone" && v.position.y > 0.49;
+                if pole || apex {
+                    continue;
+                }
+                assert!(
+                    v.tangent.truncate().dot(c.tangent.truncate()) > 0.95
+                        && v.tangent.w == c.tangent.w,
+                    "{name}: {:?} != {:?} at {:?}",
+                    v.tangent,
+                    c.tangent,
+                    v.position
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,544 @@
+use super::error::OglError;
+use super::glapi;
+use super::mesh::{GpuMesh, Mesh, MeshVertex};
+use super::texture2d::{Texture2D, Texture2DFilter};
+use error_stack::{Report, Result};
+use glam::{Vec2, Vec3, Vec4};
+use jlogger_tracing::{jdebug, jwarn};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Material defined by `newmtl` in a MTL file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial {
+    name: String,
+    ambient: Vec3,
+    diffuse: Vec3,
+    specular: Vec3,
+    shininess: f32,
+    dissolve: f32,
+    diffuse_map: Option<PathBuf>,
+}
+
+impl ObjMaterial {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ambient: Vec3::ZERO,
+            diffuse: Vec3::splat(0.8),
+            specular: Vec3::ZERO,
+            shininess: 0.0,
+            dissolve: 1.0,
+            diffuse_map: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Ka
+    pub fn ambient(&self) -> Vec3 {
+        self.ambient
+    }
+
+    /// Kd
+    pub fn diffuse(&self) -> Vec3 {
+        self.diffuse
+    }
+
+    /// Ks
+    pub fn specular(&self) -> Vec3 {
+        self.specular
+    }
+
+    /// Ns, the specular exponent.
+    pub fn shininess(&self) -> f32 {
+        self.shininess
+    }
+
+    /// d, the opacity.
+    pub fn dissolve(&self) -> f32 {
+        self.dissolve
+    }
+
+    /// map_Kd, resolved against the directory of the MTL file.
+    pub fn diffuse_map(&self) -> Option<&Path> {
+        self.diffuse_map.as_deref()
+    }
+}
+
+/// Parse the materials of a MTL file, texture paths are relative to `dir`.
+pub fn parse_mtl(src: &str, dir: &Path) -> Result<Vec<ObjMaterial>, OglError> {
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+
+    for (n, line) in lines(src) {
+        let (keyword, args) = (line[0], &line[1..]);
+        if keyword == "newmtl" {
+            materials.push(ObjMaterial::new(&args.join(" ")));
+            continue;
+        }
+
+        let Some(m) = materials.last_mut() else {
+            return Err(invalid(n, format!("{keyword} before newmtl")));
+        };
+
+        match keyword {
+            "Ka" => m.ambient = Vec3::from(numbers::<3>(args, 1, n)?),
+            "Kd" => m.diffuse = Vec3::from(numbers::<3>(args, 1, n)?),
+            "Ks" => m.specular = Vec3::from(numbers::<3>(args, 1, n)?),
+            "Ns" => m.shininess = numbers::<1>(args, 1, n)?[0],
+            "d" => m.dissolve = numbers::<1>(args, 1, n)?[0],
+            "Tr" => m.dissolve = 1.0 - numbers::<1>(args, 1, n)?[0],
+            // Options such as `-s 2 2 1` come before the file name.
+            "map_Kd" => match args.last() {
+                Some(file) => m.diffuse_map = Some(dir.join(file)),
+                None => return Err(invalid(n, "map_Kd without file".to_owned())),
+            },
+            _ => jdebug!("Ignore {keyword} at line {n}"),
+        }
+    }
+
+    Ok(materials)
+}
+
+/// Triangles of an object or group sharing a material, `count` indices of the mesh from
+/// `first`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjGroup {
+    name: String,
+    material: Option<usize>,
+    first: usize,
+    count: usize,
+}
+
+impl ObjGroup {
+    /// Name given by the last `o` or `g`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Index in ObjModel::materials().
+    pub fn material(&self) -> Option<usize> {
+        self.material
+    }
+
+    pub fn first(&self) -> usize {
+        self.first
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Wavefront OBJ model.
+///
+/// Faces are triangulated as fans and the `v/vt/vn` tuples shared by faces become a single
+/// vertex of the mesh. Smooth normals are computed for the vertices without `vn`, the normals
+/// given by the file are kept.
+#[derive(Debug, Clone, Default)]
+pub struct ObjModel {
+    mesh: Mesh,
+    groups: Vec<ObjGroup>,
+    materials: Vec<ObjMaterial>,
+}
+
+impl ObjModel {
+    /// Load an OBJ file and the MTL files it refers to.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, OglError> {
+        let path = path.as_ref();
+        let src = std::fs::read_to_string(path).map_err(|e| {
+            Report::new(OglError::InvalidData)
+                .attach_printable(format!("Failed to read {}: {e}", path.display()))
+        })?;
+
+        let dir = path.parent().unwrap_or(Path::new("."));
+        Self::parse(&src, dir).map_err(|e| e.attach_printable(format!("in {}", path.display())))
+    }
+
+    /// Parse an OBJ file, the MTL files and textures are looked up in `dir`.
+    pub fn parse(src: &str, dir: &Path) -> Result<Self, OglError> {
+        let mut parser = ObjParser::default();
+
+        for (n, line) in lines(src) {
+            let (keyword, args) = (line[0], &line[1..]);
+            match keyword {
+                "v" => parser.positions.push(Vec3::from(numbers::<3>(args, 3, n)?)),
+                "vt" => parser
+                    .texcoords
+                    .push(Vec2::from_slice(&numbers::<2>(args, 1, n)?)),
+                "vn" => parser.normals.push(Vec3::from(numbers::<3>(args, 3, n)?)),
+                "f" => parser.face(args, n)?,
+                "o" | "g" => parser.name = args.join(" "),
+                "usemtl" => {
+                    let name = args.join(" ");
+                    parser.material = parser.materials.iter().position(|m| m.name == name);
+                    if parser.material.is_none() {
+                        jwarn!("Unknown material {name} at line {n}");
+                    }
+                }
+                "mtllib" => {
+                    for file in args {
+                        let path = dir.join(file);
+                        match std::fs::read_to_string(&path) {
+                            Ok(mtl) => parser.materials.extend(
+                                parse_mtl(&mtl, path.parent().unwrap_or(dir)).map_err(|e| {
+                                    e.attach_printable(format!("in {}", path.display()))
+                                })?,
+                            ),
+                            Err(e) => jwarn!("Failed to read {}: {e}", path.display()),
+                        }
+                    }
+                }
+                _ => jdebug!("Ignore {keyword} at line {n}"),
+            }
+        }
+
+        let mut mesh = Mesh::new(parser.vertices, parser.indices);
+        if parser.missing_normals {
+            mesh.compute_missing_normals();
+        }
+        mesh.compute_tangents();
+
+        Ok(Self {
+            mesh,
+            groups: parser.groups,
+            materials: parser.materials,
+        })
+    }
+
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    pub fn groups(&self) -> &[ObjGroup] {
+        &self.groups
+    }
+
+    pub fn materials(&self) -> &[ObjMaterial] {
+        &self.materials
+    }
+
+    /// Upload the mesh and the diffuse maps. A texture failing to load is skipped with a
+    /// warning and the material falls back to its diffuse color.
+    pub fn upload(&self, gl: &Rc<glapi::GlFns>) -> GpuObjModel {
+        let textures = self
+            .materials
+            .iter()
+            .map(|m| {
+                let path = m.diffuse_map.as_ref()?;
+                let mut texture = Texture2D::default();
+                let loaded = unsafe {
+                    texture.create_from_file(
+                        &path.to_string_lossy(),
+                        gl,
+                        Texture2DFilter::LinearMiMapLinear,
+                    )
+                };
+
+                match loaded {
+                    Ok(_) => {
+                        // UVs of OBJ files often tile the texture.
+                        unsafe {
+                            gl.BindTexture(glapi::GL_TEXTURE_2D, texture.id());
+                            for wrap in [glapi::GL_TEXTURE_WRAP_S, glapi::GL_TEXTURE_WRAP_T] {
+                                gl.TexParameteri(
+                                    glapi::GL_TEXTURE_2D,
+                                    wrap,
+                                    glapi::GL_REPEAT.0 as i32,
+                                );
+                            }
+                            gl.BindTexture(glapi::GL_TEXTURE_2D, 0);
+                        }
+                        Some(texture)
+                    }
+                    Err(e) => {
+                        jwarn!("Failed to load {}: {:?}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        GpuObjModel {
+            mesh: self.mesh.upload(gl),
+            groups: self.groups.clone(),
+            materials: self.materials.clone(),
+            textures,
+        }
+    }
+}
+
+/// ObjModel uploaded by ObjModel::upload().
+#[derive(Default)]
+pub struct GpuObjModel {
+    mesh: GpuMesh,
+    groups: Vec<ObjGroup>,
+    materials: Vec<ObjMaterial>,
+    textures: Vec<Option<Texture2D>>,
+}
+
+impl GpuObjModel {
+    pub fn mesh(&self) -> &GpuMesh {
+        &self.mesh
+    }
+
+    /// Draw the groups one by one, calling `f` with the material and the diffuse map of the
+    /// group to set up the program before each of them.
+    pub fn draw<F>(&self, mut f: F) -> Result<(), OglError>
+    where
+        F: FnMut(Option<&ObjMaterial>, Option<&Texture2D>) -> Result<(), OglError>,
+    {
+        for group in &self.groups {
+            let material = group.material.map(|i| &self.materials[i]);
+            let texture = group.material.and_then(|i| self.textures[i].as_ref());
+            f(material, texture)?;
+            self.mesh.draw_range(group.first, group.count);
+        }
+
+        Ok(())
+    }
+
+    /// Delete the textures, the mesh is deleted when dropped.
+    pub fn delete(&mut self, gl: &glapi::GlFns) {
+        for texture in self.textures.iter_mut().flatten() {
+            unsafe { texture.delete(gl) };
+        }
+    }
+}
+
+#[derive(Default)]
+struct ObjParser {
+    positions: Vec<Vec3>,
+    texcoords: Vec<Vec2>,
+    normals: Vec<Vec3>,
+    materials: Vec<ObjMaterial>,
+
+    name: String,
+    material: Option<usize>,
+
+    vertices: Vec<MeshVertex>,
+    indices: Vec<u32>,
+    groups: Vec<ObjGroup>,
+    tuples: HashMap<(usize, Option<usize>, Option<usize>), u32>,
+    missing_normals: bool,
+}
+
+impl ObjParser {
+    fn face(&mut self, args: &[&str], n: usize) -> Result<(), OglError> {
+        if args.len() < 3 {
+            return Err(invalid(n, format!("face of {} vertices", args.len())));
+        }
+
+        let corners = args
+            .iter()
+            .map(|tuple| self.vertex(tuple, n))
+            .collect::<Result<Vec<u32>, OglError>>()?;
+
+        let same_group = self
+            .groups
+            .last()
+            .is_some_and(|g| g.name == self.name && g.material == self.material);
+        if !same_group {
+            self.groups.push(ObjGroup {
+                name: self.name.clone(),
+                material: self.material,
+                first: self.indices.len(),
+                count: 0,
+            });
+        }
+
+        for i in 1..corners.len() - 1 {
+            self.indices
+                .extend_from_slice(&[corners[0], corners[i], corners[i + 1]]);
+        }
+        if let Some(group) = self.groups.last_mut() {
+            group.count += (corners.len() - 2) * 3;
+        }
+
+        Ok(())
+    }
+
+    /// Index of the vertex of a `v`, `v/vt`, `v//vn` or `v/vt/vn` tuple.
+    fn vertex(&mut self, tuple: &str, n: usize) -> Result<u32, OglError> {
+        let mut refs = tuple.split('/');
+        let position = index(refs.next(), self.positions.len(), n)?
+            .ok_or_else(|| invalid(n, format!("no position in {tuple}")))?;
+        let texcoord = index(refs.next(), self.texcoords.len(), n)?;
+        let normal = index(refs.next(), self.normals.len(), n)?;
+        if refs.next().is_some() {
+            return Err(invalid(n, format!("invalid vertex {tuple}")));
+        }
+
+        self.missing_normals |= normal.is_none();
+
+        let key = (position, texcoord, normal);
+        if let Some(&i) = self.tuples.get(&key) {
+            return Ok(i);
+        }
+
+        self.vertices.push(MeshVertex::new(
+            self.positions[position],
+            normal.map(|i| self.normals[i]).unwrap_or_default(),
+            texcoord.map(|i| self.texcoords[i]).unwrap_or_default(),
+            Vec4::ZERO,
+        ));
+        let i = self.vertices.len() as u32 - 1;
+        self.tuples.insert(key, i);
+
+        Ok(i)
+    }
+}
+
+/// Non-empty lines without comments, split in words and numbered from 1.
+fn lines(src: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    src.lines().enumerate().filter_map(|(i, line)| {
+        let line = line.split('#').next().unwrap_or_default();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        (!words.is_empty()).then_some((i + 1, words))
+    })
+}
+
+/// The first N numbers of `args`, at least `required` of them. The missing ones are 0, or
+/// copies of the first one for colors given as a single value.
+fn numbers<const N: usize>(args: &[&str], required: usize, n: usize) -> Result<[f32; N], OglError> {
+    if args.len() < required {
+        return Err(invalid(n, format!("expected {required} numbers")));
+    }
+
+    let mut values = [0.0; N];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = match args
+            .get(i)
+            .or((required == 1 && N == 3).then_some(&args[0]))
+        {
+            Some(s) => s
+                .parse()
+                .map_err(|_| invalid(n, format!("invalid number {s}")))?,
+            None => 0.0,
+        };
+    }
+
+    Ok(values)
+}
+
+/// Index from 0 of a reference counted from 1, or from the end of the `len` elements read so
+/// far when negative. None if the reference is empty.
+fn index(reference: Option<&str>, len: usize, n: usize) -> Result<Option<usize>, OglError> {
+    let reference = match reference {
+        Some(r) if !r.is_empty() => r,
+        _ => return Ok(None),
+    };
+
+    let i: i64 = reference
+        .parse()
+        .map_err(|_| invalid(n, format!("invalid index {reference}")))?;
+    let index = match i {
+        1.. => i - 1,
+        ..=-1 => len as i64 + i,
+        0 => -1,
+    };
+
+    if (0..len as i64).contains(&index) {
+        Ok(Some(index as usize))
+    } else {
+        Err(invalid(n, format!("index {reference} out of range")))
+    }
+}
+
+fn invalid(n: usize, msg: String) -> Report<OglError> {
+    Report::new(OglError::InvalidData).attach_printable(format!("line {n}: {msg}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ObjModel;
+    use glam::Vec3;
+
+    #[test]
+    fn parse_obj_with_materials() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("quad.mtl"),
+            "newmtl red\nKd 1 0 0\nmap_Kd -s 2 2 1 red.png\n\nnewmtl blue\nKd 0 0 1\nd 0.5\n",
+        )
+        .unwrap();
+
+        let src = r#"
+            # Quad and triangle
+            mtllib quad.mtl
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            vt 0 0
+            vt 1 0
+            vt 1 1
+            vt 0 1
+            vn 0 0 1
+
+            o quad
+            usemtl red
+            f 1/1/1 2/2/1 3/3/1 4/4/1
+            g triangle
+            usemtl blue
+            f -4//-1 -3//-1 -2//-1
+        "#;
+
+        let model = ObjModel::parse(src, dir.path()).unwrap();
+        let mesh = model.mesh();
+        assert_eq!(mesh.indices(), &[0, 1, 2, 0, 2, 3, 4, 5, 6]);
+        assert_eq!(mesh.vertices().len(), 7);
+        assert!(mesh.vertices().iter().all(|v| v.normal == Vec3::Z));
+
+        let groups: Vec<_> = model
+            .groups()
+            .iter()
+            .map(|g| (g.name(), g.material(), g.first(), g.count()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![("quad", Some(0), 0, 6), ("triangle", Some(1), 6, 3)]
+        );
+
+        let materials = model.materials();
+        assert_eq!(materials[0].diffuse(), Vec3::X);
+        assert_eq!(
+            materials[0].diffuse_map(),
+            Some(dir.path().join("red.png").as_path())
+        );
+        assert_eq!(materials[1].dissolve(), 0.5);
+
+        assert!(ObjModel::parse("v 0 0 0\nf 1 2 3\n", dir.path()).is_err());
+        assert!(ObjModel::parse("v 0 0 0\nf 1 0 1\n", dir.path()).is_err());
+    }
+
+    #[test]
+    fn missing_normals_are_smoothed() {
+        let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nf 1 3 2\nf 1 2 4\nf 1 4 3\nf 2 3 4\n";
+        let model = ObjModel::parse(src, std::path::Path::new(".")).unwrap();
+        let mesh = model.mesh();
+        assert_eq!(mesh.vertices().len(), 4);
+
+        // Every normal points away from the center of the tetrahedron.
+        let center = Vec3::splat(0.25);
+        for v in mesh.vertices() {
+            assert!((v.normal.length() - 1.0).abs() < 1e-4);
+            assert!(v.normal.dot(v.position - center) > 0.0, "{v:?}");
+        }
+
+        // The vertices with vn keep it when other faces have none.
+        let src = format!("{src}vn 0 0 -1\nf 1//1 2//1 3//1\n");
+        let model = ObjModel::parse(&src, std::path::Path::new(".")).unwrap();
+        let mesh = model.mesh();
+        assert_eq!(mesh.vertices().len(), 7);
+        for v in &mesh.vertices()[4..] {
+            assert_eq!(v.normal, Vec3::NEG_Z);
+        }
+        assert!(mesh.vertices()[..4]
+            .iter()
+            .all(|v| v.normal.dot(v.position - center) > 0.0));
+    }
+}
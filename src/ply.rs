@@ -0,0 +1,382 @@
+//! PLY files, ASCII or binary.
+//!
+//! The positions, normals, texture coordinates and colors of the vertices are read, and the
+//! faces are split in triangle fans. Other elements are skipped. Smooth normals are computed
+//! when the vertices have none.
+
+use super::error::OglError;
+use super::mesh::{Mesh, MeshVertex};
+use error_stack::{Report, Result};
+use glam::{Vec2, Vec3, Vec4};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+
+    /// Factor mapping a color component to [0, 1].
+    fn color_scale(self) -> f64 {
+        match self {
+            Scalar::U8 => 1.0 / 255.0,
+            Scalar::U16 => 1.0 / 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Property {
+    Scalar(String, Scalar),
+    /// Name, type of the count and type of the items.
+    List(String, Scalar, Scalar),
+}
+
+impl Property {
+    fn name(&self) -> &str {
+        match self {
+            Property::Scalar(name, _) | Property::List(name, _, _) => name,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Mesh, OglError> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).map_err(|e| {
+        Report::new(OglError::InvalidData)
+            .attach_printable(format!("Failed to read {}: {e}", path.display()))
+    })?;
+
+    parse(&data).map_err(|e| e.attach_printable(format!("in {}", path.display())))
+}
+
+/// Parse the content of a PLY file.
+pub fn parse(data: &[u8]) -> Result<Mesh, OglError> {
+    const END: &[u8] = b"end_header";
+    let end = data
+        .windows(END.len())
+        .position(|w| w == END)
+        .ok_or_else(|| invalid("no end_header".to_owned()))?;
+    let body = data[end..]
+        .iter()
+        .position(|c| *c == b'\n')
+        .map_or(&[][..], |n| &data[end + n + 1..]);
+
+    let header = std::str::from_utf8(&data[..end])
+        .map_err(|e| invalid(format!("header is not UTF-8: {e}")))?;
+    let (format, elements) = parse_header(header)?;
+
+    let mut reader = match format {
+        Format::Ascii => Reader::Ascii(
+            std::str::from_utf8(body)
+                .map_err(|e| invalid(format!("not UTF-8: {e}")))?
+                .split_ascii_whitespace(),
+        ),
+        _ => Reader::Binary {
+            data: body,
+            big_endian: format == Format::BigEndian,
+        },
+    };
+
+    let mut vertices: Vec<MeshVertex> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+    let mut has_normals = false;
+
+    for element in &elements {
+        let field = |names: &[&str]| {
+            element
+                .properties
+                .iter()
+                .position(|p| names.contains(&p.name()))
+        };
+
+        // Which properties of the element are used.
+        let position = ["x", "y", "z"].map(|n| field(&[n]));
+        let normal = ["nx", "ny", "nz"].map(|n| field(&[n]));
+        let uv = [
+            field(&["u", "s", "texture_u", "texture_s"]),
+            field(&["v", "t", "texture_v", "texture_t"]),
+        ];
+        let color = ["red", "green", "blue", "alpha"].map(|n| {
+            let diffuse = format!("diffuse_{n}");
+            field(&[n, &diffuse])
+        });
+        let face = field(&["vertex_indices", "vertex_index"]);
+
+        let is_vertex = element.name == "vertex";
+        if is_vertex {
+            if position.iter().any(Option::is_none) {
+                return Err(invalid("vertex without x, y and z".to_owned()));
+            }
+            has_normals = normal.iter().all(Option::is_some);
+        }
+
+        let mut values = vec![0.0; element.properties.len()];
+        let mut scales = vec![1.0; element.properties.len()];
+        let mut list = Vec::new();
+        for _ in 0..element.count {
+            for (i, property) in element.properties.iter().enumerate() {
+                match property {
+                    Property::Scalar(_, kind) => {
+                        values[i] = reader.read(*kind)?;
+                        scales[i] = kind.color_scale();
+                    }
+                    Property::List(_, count, kind) => {
+                        let count = reader.read(*count)? as usize;
+                        let items = (0..count).map(|_| reader.read(*kind));
+                        let items = items.collect::<Result<Vec<_>, OglError>>()?;
+                        if Some(i) == face {
+                            list = items;
+                        }
+                    }
+                }
+            }
+
+            let get = |i: Option<usize>| i.map(|i| values[i] as f32);
+            if is_vertex {
+                let [x, y, z] = position.map(|i| get(i).unwrap_or_default());
+                let [nx, ny, nz] = normal.map(|i| get(i).unwrap_or_default());
+                let [u, v] = uv.map(|i| get(i).unwrap_or_default());
+                let [r, g, b, a] = color.map(|i| match i {
+                    Some(i) => (values[i] * scales[i]) as f32,
+                    None => 1.0,
+                });
+
+                vertices.push(
+                    MeshVertex::new(
+                        Vec3::new(x, y, z),
+                        Vec3::new(nx, ny, nz),
+                        Vec2::new(u, v),
+                        Vec4::ZERO,
+                    )
+                    .with_color(Vec4::new(r, g, b, a)),
+                );
+            } else if face.is_some() {
+                if list.len() < 3 {
+                    return Err(invalid(format!("face of {} vertices", list.len())));
+                }
+                // Checked before the conversion, which saturates negative indices to 0.
+                if let Some(i) = list.iter().find(|i| !(0.0..).contains(*i)) {
+                    return Err(invalid(format!("invalid index {i}")));
+                }
+                for i in 1..list.len() - 1 {
+                    indices.extend([list[0], list[i], list[i + 1]].map(|i| i as u32));
+                }
+            }
+        }
+    }
+
+    if let Some(i) = indices.iter().find(|i| **i as usize >= vertices.len()) {
+        return Err(invalid(format!(
+            "index {i} out of {} vertices",
+            vertices.len()
+        )));
+    }
+
+    let mut mesh = Mesh::new(vertices, indices);
+    if !has_normals {
+        mesh.compute_normals();
+    }
+    mesh.compute_tangents();
+    Ok(mesh)
+}
+
+fn parse_header(header: &str) -> Result<(Format, Vec<Element>), OglError> {
+    let mut lines = header.lines().map(str::trim);
+    if lines.next() != Some("ply") {
+        return Err(invalid("no ply magic".to_owned()));
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for line in lines {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["format", name, _version] => {
+                format = Some(match *name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::LittleEndian,
+                    "binary_big_endian" => Format::BigEndian,
+                    _ => return Err(invalid(format!("unknown format {name}"))),
+                })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| invalid(format!("invalid count in {line}")))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let property = Property::List(name.to_string(), scalar(count)?, scalar(item)?);
+                element_of(&mut elements, line)?.properties.push(property);
+            }
+            ["property", kind, name] => {
+                let property = Property::Scalar(name.to_string(), scalar(kind)?);
+                element_of(&mut elements, line)?.properties.push(property);
+            }
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return Err(invalid(format!("invalid header line {line}"))),
+        }
+    }
+
+    let format = format.ok_or_else(|| invalid("no format".to_owned()))?;
+    Ok((format, elements))
+}
+
+fn element_of<'a>(elements: &'a mut [Element], line: &str) -> Result<&'a mut Element, OglError> {
+    elements
+        .last_mut()
+        .ok_or_else(|| invalid(format!("{line} before any element")))
+}
+
+fn scalar(name: &str) -> Result<Scalar, OglError> {
+    Scalar::from_name(name).ok_or_else(|| invalid(format!("unknown type {name}")))
+}
+
+enum Reader<'a> {
+    Ascii(std::str::SplitAsciiWhitespace<'a>),
+    Binary { data: &'a [u8], big_endian: bool },
+}
+
+impl Reader<'_> {
+    fn read(&mut self, kind: Scalar) -> Result<f64, OglError> {
+        match self {
+            Reader::Ascii(words) => words
+                .next()
+                .and_then(|w| w.parse().ok())
+                .ok_or_else(|| invalid("missing or invalid value".to_owned())),
+            Reader::Binary { data, big_endian } => {
+                if data.len() < kind.size() {
+                    return Err(invalid("unexpected end of data".to_owned()));
+                }
+
+                let (value, rest) = data.split_at(kind.size());
+                *data = rest;
+
+                let mut b = [0u8; 8];
+                b[..value.len()].copy_from_slice(value);
+                if *big_endian {
+                    b[..value.len()].reverse();
+                }
+
+                Ok(match kind {
+                    Scalar::I8 => b[0] as i8 as f64,
+                    Scalar::U8 => b[0] as f64,
+                    Scalar::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+                    Scalar::U16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+                    Scalar::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    Scalar::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    Scalar::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    Scalar::F64 => f64::from_le_bytes(b),
+                })
+            }
+        }
+    }
+}
+
+fn invalid(msg: String) -> Report<OglError> {
+    Report::new(OglError::InvalidData).attach_printable(format!("PLY: {msg}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use glam::{Vec3, Vec4};
+
+    const HEADER: &str = "comment unit square
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar int vertex_indices
+end_header
+";
+
+    #[test]
+    fn ascii_and_binary_give_the_same_mesh() {
+        let ascii = format!(
+            "ply\nformat ascii 1.0\n{HEADER}0 0 0 255 0 0\n1 0 0 0 255 0\n1 1 0 0 0 255\n\
+             0 1 0 255 255 255\n4 0 1 2 3\n"
+        );
+
+        let mut little = format!("ply\nformat binary_little_endian 1.0\n{HEADER}").into_bytes();
+        let mut big = format!("ply\nformat binary_big_endian 1.0\n{HEADER}").into_bytes();
+        for (p, c) in [
+            ([0.0f32, 0.0, 0.0], [255u8, 0, 0]),
+            ([1.0, 0.0, 0.0], [0, 255, 0]),
+            ([1.0, 1.0, 0.0], [0, 0, 255]),
+            ([0.0, 1.0, 0.0], [255, 255, 255]),
+        ] {
+            little.extend(p.iter().flat_map(|v| v.to_le_bytes()));
+            big.extend(p.iter().flat_map(|v| v.to_be_bytes()));
+            little.extend_from_slice(&c);
+            big.extend_from_slice(&c);
+        }
+        little.push(4);
+        big.push(4);
+        little.extend([0i32, 1, 2, 3].iter().flat_map(|i| i.to_le_bytes()));
+        big.extend([0i32, 1, 2, 3].iter().flat_map(|i| i.to_be_bytes()));
+
+        let mesh = parse(ascii.as_bytes()).unwrap();
+        assert_eq!(mesh, parse(&little).unwrap());
+        assert_eq!(mesh, parse(&big).unwrap());
+
+        assert_eq!(mesh.indices(), &[0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.vertices()[2].position, Vec3::new(1.0, 1.0, 0.0));
+        assert_eq!(mesh.vertices()[1].color, Vec4::new(0.0, 1.0, 0.0, 1.0));
+        assert!(mesh.vertices().iter().all(|v| v.normal == Vec3::Z));
+
+        assert!(parse(&little[..little.len() - 1]).is_err());
+        assert!(parse(ascii.replace("4 0 1 2 3", "4 0 1 2 -3").as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,379 @@
+use super::error::OglError;
+use error_stack::{Report, Result};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Shader source produced by Preprocessor::process().
+///
+/// Every line of text() remembers the file and the line it comes from so that the line numbers
+/// reported by the GLSL compiler can be mapped back by map_log().
+#[derive(Debug, Clone)]
+pub struct Source {
+    text: String,
+    names: Vec<String>,
+    files: Vec<PathBuf>,
+    lines: Vec<(usize, usize)>,
+}
+
+impl Source {
+    /// Source used as it is, without preprocessing.
+    pub fn new(text: &str) -> Self {
+        let mut source = Self {
+            text: String::new(),
+            names: vec!["<source>".to_owned()],
+            files: Vec::new(),
+            lines: Vec::new(),
+        };
+
+        for (i, line) in text.lines().enumerate() {
+            push_line(&mut source, line, 0, i + 1);
+        }
+
+        source
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Files read to build the source, the main file first if it is one.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// File name and line (counted from 1) of the line `line` of text().
+    pub fn origin(&self, line: usize) -> Option<(&str, usize)> {
+        let (name, line) = self.lines.get(line.checked_sub(1)?)?;
+        Some((self.names[*name].as_str(), *line))
+    }
+
+    /// Replace "0:12" in the messages of the GLSL compiler with the original file and line.
+    ///
+    /// Both "0:12(5): error" (Mesa) and "ERROR: 0:12: error" (most other drivers) are handled.
+    pub fn map_log(&self, log: &str) -> String {
+        log.lines()
+            .map(|l| {
+                let origin = log_location(l).and_then(|(start, end, line)| {
+                    let (name, line) = self.origin(line)?;
+                    Some(format!("{}{}:{}{}", &l[..start], name, line, &l[end..]))
+                });
+                origin.unwrap_or_else(|| l.to_owned())
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Listing of the lines reported in `log` with two lines around them. The reported lines
+    /// are marked with ">>" and labeled with the original file and line.
+    pub fn excerpt(&self, log: &str) -> String {
+        const CONTEXT: usize = 2;
+
+        let mut marked: Vec<usize> = log
+            .lines()
+            .filter_map(|l| log_location(l).map(|a| a.2))
+            .filter(|a| *a >= 1 && *a <= self.lines.len())
+            .collect();
+        marked.sort_unstable();
+        marked.dedup();
+
+        let text: Vec<&str> = self.text.lines().collect();
+        let mut excerpt = String::new();
+        let mut last = 0;
+
+        for &line in &marked {
+            let from = line.saturating_sub(CONTEXT).max(last + 1);
+            let to = (line + CONTEXT).min(text.len());
+
+            if last != 0 && from > last + 1 {
+                excerpt.push_str("   ...\n");
+            }
+
+            for i in from..=to {
+                let marker = if marked.contains(&i) { ">>" } else { "  " };
+                let (name, line_no) = self.origin(i).unwrap_or(("?", i));
+                let _ = writeln!(excerpt, "{marker} {name}:{line_no} | {}", text[i - 1]);
+            }
+
+            last = last.max(to);
+        }
+
+        excerpt
+    }
+}
+
+/// Position of "0:12" in a line of a GLSL compiler log and the line number it refers to.
+fn log_location(line: &str) -> Option<(usize, usize, usize)> {
+    let bytes = line.as_bytes();
+    let digits = |from: usize| {
+        bytes[from..]
+            .iter()
+            .take_while(|a| a.is_ascii_digit())
+            .count()
+    };
+
+    let mut start = 0;
+    while start < bytes.len() {
+        let n = digits(start);
+        let after = start + n;
+        let at_word = start == 0 || !bytes[start - 1].is_ascii_alphanumeric();
+
+        if n > 0 && at_word && bytes.get(after) == Some(&b':') {
+            let m = digits(after + 1);
+            let end = after + 1 + m;
+            if m > 0 && matches!(bytes.get(end), Some(b'(') | Some(b':')) {
+                let line_no = line[after + 1..end].parse::<usize>().ok()?;
+                return Some((start, end, line_no));
+            }
+        }
+
+        start += n.max(1);
+    }
+
+    None
+}
+
+/// Expand `#include "file"` and inject `#define`s in front of GlState::build().
+///
+/// `#version` is kept on the first line as required by GLSL and the defines follow it. Included
+/// files are searched in the directory of the including file and then in the search paths.
+#[derive(Debug, Clone, Default)]
+pub struct Preprocessor {
+    search_paths: Vec<PathBuf>,
+    defines: Vec<(String, String)>,
+}
+
+impl Preprocessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_search_path<P: AsRef<Path>>(&mut self, path: P) {
+        self.search_paths.push(path.as_ref().to_path_buf());
+    }
+
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.search_paths
+    }
+
+    /// Define `name` as `value` in every shader, replacing the previous value.
+    pub fn define<T: ToString>(&mut self, name: &str, value: T) {
+        let value = value.to_string();
+        match self.defines.iter_mut().find(|(n, _)| n == name) {
+            Some(define) => define.1 = value,
+            None => self.defines.push((name.to_owned(), value)),
+        }
+    }
+
+    pub fn undefine(&mut self, name: &str) {
+        self.defines.retain(|(n, _)| n != name);
+    }
+
+    pub fn defines(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.defines.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    /// Preprocess `src`. `path` is the file `src` was read from, if any.
+    pub fn process(&self, src: &str, path: Option<&Path>) -> Result<Source, OglError> {
+        let mut source = Source {
+            text: String::new(),
+            names: vec![path.map_or("<source>".to_owned(), |a| a.display().to_string())],
+            files: path.map(|a| a.to_path_buf()).into_iter().collect(),
+            lines: Vec::new(),
+        };
+
+        // #version can be preceded by comments and empty lines only.
+        let mut version = None;
+        let mut lines: Vec<&str> = src.lines().collect();
+        if let Some(i) = lines.iter().position(|a| !is_blank(a)) {
+            if lines[i].trim_start().starts_with("#version") {
+                version = Some((lines[i], i + 1));
+                lines[i] = "";
+            }
+        }
+
+        if let Some((line, line_no)) = version {
+            push_line(&mut source, line, 0, line_no);
+        }
+
+        for (name, value) in &self.defines {
+            let n = source.names.len();
+            source.names.push("<define>".to_owned());
+            push_line(&mut source, &format!("#define {name} {value}"), n, 1);
+        }
+
+        let mut stack = path.map(|a| a.to_path_buf()).into_iter().collect();
+        self.expand(
+            &mut source,
+            &lines,
+            0,
+            path.and_then(|a| a.parent()),
+            &mut stack,
+        )?;
+
+        Ok(source)
+    }
+
+    fn expand(
+        &self,
+        source: &mut Source,
+        lines: &[&str],
+        name: usize,
+        dir: Option<&Path>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<(), OglError> {
+        for (i, line) in lines.iter().enumerate() {
+            let directive = line.trim_start();
+            let Some(rest) = directive
+                .strip_prefix("#include")
+                .filter(|a| a.starts_with(char::is_whitespace))
+            else {
+                push_line(source, line, name, i + 1);
+                continue;
+            };
+
+            let location = || format!("{}:{}", source.names[name], i + 1);
+            let file = include_name(rest).ok_or(
+                Report::new(OglError::InvalidData).attach_printable(format!(
+                    "{}: invalid {}",
+                    location(),
+                    directive
+                )),
+            )?;
+
+            let path = self.resolve(file, dir).ok_or(
+                Report::new(OglError::InvalidData).attach_printable(format!(
+                    "{}: {} is not found",
+                    location(),
+                    file
+                )),
+            )?;
+
+            if stack.contains(&path) {
+                return Err(Report::new(OglError::InvalidData).attach_printable(format!(
+                    "{}: {} includes itself",
+                    location(),
+                    path.display()
+                )));
+            }
+
+            let src = std::fs::read_to_string(&path).map_err(|e| {
+                Report::new(OglError::InvalidData)
+                    .attach_printable(format!("Failed to read {}: {e}", path.display()))
+            })?;
+
+            let included = source.names.len();
+            source.names.push(path.display().to_string());
+            if !source.files.contains(&path) {
+                source.files.push(path.clone());
+            }
+
+            stack.push(path.clone());
+            let lines: Vec<&str> = src.lines().collect();
+            self.expand(source, &lines, included, path.parent(), stack)?;
+            stack.pop();
+        }
+
+        Ok(())
+    }
+
+    fn resolve(&self, file: &str, dir: Option<&Path>) -> Option<PathBuf> {
+        dir.into_iter()
+            .chain(self.search_paths.iter().map(|a| a.as_path()))
+            .map(|a| a.join(file))
+            .find(|a| a.is_file())
+    }
+}
+
+/// The quoted name of `#include "name"`, which may only be followed by a `//` comment.
+fn include_name(rest: &str) -> Option<&str> {
+    let (file, tail) = rest.trim_start().strip_prefix('"')?.split_once('"')?;
+    let tail = tail.trim_start();
+    (tail.is_empty() || tail.starts_with("//")).then_some(file)
+}
+
+fn is_blank(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with("//")
+}
+
+fn push_line(source: &mut Source, line: &str, name: usize, line_no: usize) {
+    let _ = writeln!(source.text, "{line}");
+    source.lines.push((name, line_no));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Preprocessor;
+
+    #[test]
+    fn includes_and_defines_are_expanded() {
+        let dir = tempfile::tempdir().unwrap();
+        let common = dir.path().join("common");
+        std::fs::create_dir(&common).unwrap();
+        std::fs::write(common.join("mvp.glsl"), "uniform mat4 u_mvpMatrix;\n").unwrap();
+        std::fs::write(
+            common.join("light.glsl"),
+            "#include \"mvp.glsl\" // u_mvpMatrix\nvec3 light() { return vec3(LIGHT); }\n",
+        )
+        .unwrap();
+
+        let mut pp = Preprocessor::new();
+        pp.add_search_path(&common);
+        pp.define("LIGHT", 0.5);
+
+        let src = "// sample\n#version 300 es\n#include \"light.glsl\"\nvoid main() {}\n";
+        let source = pp.process(src, None).unwrap();
+
+        assert_eq!(
+            source.text(),
+            "#version 300 es\n#define LIGHT 0.5\n// sample\n\nuniform mat4 u_mvpMatrix;\n\
+             vec3 light() { return vec3(LIGHT); }\nvoid main() {}\n"
+        );
+        assert_eq!(source.files().len(), 2);
+        assert_eq!(source.origin(1), Some(("<source>", 2)));
+        assert_eq!(source.origin(7), Some(("<source>", 4)));
+
+        let mvp = common.join("mvp.glsl").display().to_string();
+        assert_eq!(source.origin(5), Some((mvp.as_str(), 1)));
+
+        // Line 6 of the output comes from light.glsl.
+        let light = common.join("light.glsl").display().to_string();
+        assert_eq!(
+            source.map_log("0:6(10): error: `LIGHT' undeclared\nERROR: 0:7: 'x' : syntax error"),
+            format!(
+                "{light}:2(10): error: `LIGHT' undeclared\nERROR: <source>:4: 'x' : syntax error"
+            )
+        );
+
+        assert!(pp.process("#include \"missing.glsl\"\n", None).is_err());
+        assert!(pp.process("#include \"mvp.glsl\" x\n", None).is_err());
+        assert_eq!(
+            pp.process("#includefoo\n", None).unwrap().text(),
+            "#define LIGHT 0.5\n#includefoo\n"
+        );
+    }
+
+    #[test]
+    fn excerpt_marks_failing_lines() {
+        let src = "#version 300 es\nvoid main()\n{\n  x = 1;\n}\n";
+        let source = Preprocessor::new().process(src, None).unwrap();
+
+        assert_eq!(
+            source.excerpt("0:5(3): error: `x' undeclared\n0:5(3): error: type mismatch\n"),
+            "   <source>:2 | void main()\n   <source>:3 | {\n>> <source>:4 |   x = 1;\n   \
+             <source>:5 | }\n"
+        );
+        assert!(source.excerpt("error: no line").is_empty());
+    }
+
+    #[test]
+    fn recursive_include_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.glsl");
+        std::fs::write(&a, "#include \"b.glsl\"\n").unwrap();
+        std::fs::write(dir.path().join("b.glsl"), "#include \"a.glsl\"\n").unwrap();
+
+        let src = std::fs::read_to_string(&a).unwrap();
+        assert!(Preprocessor::new().process(&src, Some(&a)).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use super::gl::GlContextOps;
+use super::glapi;
+use super::shader::Program;
+use jlogger_tracing::{jdebug, jwarn};
+use std::ffi::{c_void, CStr};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+// Not provided by gl33, glProgramBinary() is part of GL 4.1 and GLES 3.0.
+const GL_PROGRAM_BINARY_LENGTH: glapi::GLenum = glapi::GLenum(0x8741);
+const GL_NUM_PROGRAM_BINARY_FORMATS: glapi::GLenum = glapi::GLenum(0x87FE);
+
+type GetProgramBinaryFn = unsafe extern "system" fn(u32, i32, *mut i32, *mut u32, *mut c_void);
+type ProgramBinaryFn = unsafe extern "system" fn(u32, u32, *const c_void, i32);
+
+const MAGIC: &[u8; 4] = b"OGLB";
+
+/// Linked programs saved on disk with glGetProgramBinary() and reloaded with glProgramBinary().
+///
+/// An entry is keyed by a hash of the preprocessed sources, GL_RENDERER and GL_VERSION so that a
+/// driver update never sees binaries of the previous one. A binary rejected by the driver is
+/// removed and the program has to be built again.
+pub struct ProgramCache {
+    gl: Rc<glapi::GlFns>,
+    dir: PathBuf,
+    driver: String,
+    get_program_binary: GetProgramBinaryFn,
+    program_binary: ProgramBinaryFn,
+}
+
+impl ProgramCache {
+    /// Cache in `dir`. None if the driver supports no binary format.
+    pub fn new<P: AsRef<Path>>(
+        gl: &Rc<glapi::GlFns>,
+        egl: &dyn GlContextOps,
+        dir: P,
+    ) -> Option<Self> {
+        let get_program_binary = egl.get_proc_address("glGetProgramBinary");
+        let program_binary = egl.get_proc_address("glProgramBinary");
+        if get_program_binary.is_null() || program_binary.is_null() {
+            return None;
+        }
+
+        let mut formats = 0;
+        unsafe { gl.GetIntegerv(GL_NUM_PROGRAM_BINARY_FORMATS, &mut formats) };
+        if formats <= 0 {
+            return None;
+        }
+
+        let driver = format!(
+            "{}\n{}",
+            gl_string(gl, glapi::GL_RENDERER),
+            gl_string(gl, glapi::GL_VERSION)
+        );
+
+        Some(Self {
+            gl: gl.clone(),
+            dir: dir.as_ref().to_path_buf(),
+            driver,
+            get_program_binary: unsafe {
+                std::mem::transmute::<*mut c_void, GetProgramBinaryFn>(get_program_binary)
+            },
+            program_binary: unsafe {
+                std::mem::transmute::<*mut c_void, ProgramBinaryFn>(program_binary)
+            },
+        })
+    }
+
+    /// $XDG_CACHE_HOME/ogl/programs or ~/.cache/ogl/programs.
+    pub fn default_dir() -> Option<PathBuf> {
+        std::env::var_os("XDG_CACHE_HOME")
+            .filter(|a| !a.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|a| Path::new(&a).join(".cache")))
+            .map(|a| a.join("ogl").join("programs"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Key of the program built from `sources`.
+    pub fn key(&self, sources: &[&str]) -> String {
+        cache_key(&self.driver, sources)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.bin"))
+    }
+
+    /// Program saved as `key`, if any and accepted by the driver.
+    pub fn load(&self, key: &str) -> Option<Program> {
+        let path = self.path(key);
+        let data = std::fs::read(&path).ok()?;
+
+        let accepted = decode(&data).and_then(|(format, binary)| {
+            let gl = &self.gl;
+            let id = gl.CreateProgram();
+
+            let mut success = 0;
+            unsafe {
+                (self.program_binary)(id, format, binary.as_ptr().cast(), binary.len() as i32);
+                gl.GetProgramiv(id, glapi::GL_LINK_STATUS, &mut success);
+            }
+
+            if success == 0 {
+                gl.DeleteProgram(id);
+                return None;
+            }
+
+            Some(Program::from_linked(gl, id))
+        });
+
+        if accepted.is_none() {
+            jwarn!("Program binary {} is rejected, rebuilding", path.display());
+            let _ = std::fs::remove_file(&path);
+        } else {
+            jdebug!("Program loaded from {}", path.display());
+        }
+
+        accepted
+    }
+
+    /// Save `program` as `key`. Failures are only logged, the cache is an optimization.
+    pub fn store(&self, key: &str, program: &Program) {
+        let mut length = 0;
+        unsafe {
+            self.gl
+                .GetProgramiv(program.id(), GL_PROGRAM_BINARY_LENGTH, &mut length)
+        };
+        if length <= 0 {
+            return;
+        }
+
+        let mut binary = vec![0_u8; length as usize];
+        let mut format = 0;
+        unsafe {
+            (self.get_program_binary)(
+                program.id(),
+                length,
+                &mut length,
+                &mut format,
+                binary.as_mut_ptr().cast(),
+            );
+        }
+        binary.truncate(length.max(0) as usize);
+
+        let path = self.path(key);
+        let saved = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| tempfile::NamedTempFile::new_in(&self.dir))
+            .and_then(|mut f| {
+                f.write_all(&encode(format, &binary))?;
+                f.persist(&path).map_err(|e| e.error)?;
+                Ok(())
+            });
+
+        match saved {
+            Ok(()) => jdebug!("Program saved to {}", path.display()),
+            Err(e) => jwarn!("Failed to save {}: {e}", path.display()),
+        }
+    }
+}
+
+fn gl_string(gl: &glapi::GlFns, name: glapi::StringName) -> String {
+    let s = unsafe { gl.GetString(name) };
+    if s.is_null() {
+        return String::new();
+    }
+
+    unsafe { CStr::from_ptr(s.cast()) }
+        .to_string_lossy()
+        .to_string()
+}
+
+/// FNV-1a, which unlike DefaultHasher is stable across Rust releases.
+fn cache_key(driver: &str, sources: &[&str]) -> String {
+    let mut hash = 0xcbf29ce484222325_u64;
+    for part in std::iter::once(driver).chain(sources.iter().copied()) {
+        for b in part.bytes().chain(std::iter::once(0)) {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    format!("{hash:016x}")
+}
+
+fn encode(format: u32, binary: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(MAGIC.len() + 4 + binary.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&format.to_le_bytes());
+    data.extend_from_slice(binary);
+    data
+}
+
+fn decode(data: &[u8]) -> Option<(u32, &[u8])> {
+    let data = data.strip_prefix(MAGIC)?;
+    let format = u32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    let binary = &data[4..];
+    (!binary.is_empty()).then_some((format, binary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cache_key, decode, encode};
+
+    #[test]
+    fn cache_keys_and_entries() {
+        let key = cache_key("llvmpipe\n4.5", &["vert", "frag"]);
+        assert_eq!(key.len(), 16);
+        assert_eq!(key, cache_key("llvmpipe\n4.5", &["vert", "frag"]));
+        assert_ne!(key, cache_key("llvmpipe\n4.6", &["vert", "frag"]));
+        assert_ne!(key, cache_key("llvmpipe\n4.5", &["ver", "tfrag"]));
+
+        let data = encode(0x8d64, &[1, 2, 3]);
+        assert_eq!(decode(&data), Some((0x8d64, &[1_u8, 2, 3][..])));
+        assert_eq!(decode(&data[..8]), None);
+        assert_eq!(decode(b"junk"), None);
+    }
+}
@@ -0,0 +1,210 @@
+use super::error::OglError;
+use error_stack::{Report, Result};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// RGBA8 image captured from a framebuffer, stored with the origin at top left.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Screenshot {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Screenshot {
+    /// Create a screenshot from RGBA8 pixels returned by glReadPixels.
+    ///
+    /// glReadPixels() returns rows with the origin at bottom left, which is the inverse of what
+    /// image files expect, so the rows are flipped here.
+    pub fn from_gl_pixels(width: u32, height: u32, pixels: &[u8]) -> Result<Self, OglError> {
+        let stride = width as usize * 4;
+        if pixels.len() != stride * height as usize {
+            return Err(Report::new(OglError::InvalidData).attach_printable(format!(
+                "{} bytes is not a {}x{} RGBA image",
+                pixels.len(),
+                width,
+                height
+            )));
+        }
+
+        let data = pixels
+            .chunks_exact(stride)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Create a screenshot from RGBA8 pixels with the origin at top left.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Result<Self, OglError> {
+        if data.len() != width as usize * height as usize * 4 {
+            return Err(Report::new(OglError::InvalidData).attach_printable(format!(
+                "{} bytes is not a {}x{} RGBA image",
+                data.len(),
+                width,
+                height
+            )));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
+    /// RGBA value of the pixel at (x, y), counted from top left.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+
+    pub fn load_png<P: AsRef<Path>>(path: P) -> Result<Self, OglError> {
+        let path = path.as_ref();
+        let f = File::open(path).map_err(|e| {
+            Report::new(OglError::InvalidData)
+                .attach_printable(format!("Failed to open {}: {e}", path.display()))
+        })?;
+
+        let mut decoder = png::Decoder::new(f);
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let mut reader = decoder.read_info().map_err(|e| {
+            Report::new(OglError::InvalidData)
+                .attach_printable(format!("Failed to decode {}: {e}", path.display()))
+        })?;
+
+        let mut buf = vec![0_u8; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(|e| {
+            Report::new(OglError::InvalidData)
+                .attach_printable(format!("Failed to decode {}: {e}", path.display()))
+        })?;
+        buf.truncate(info.buffer_size());
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => buf,
+            png::ColorType::Rgb => buf
+                .chunks_exact(3)
+                .flat_map(|a| [a[0], a[1], a[2], 0xff])
+                .collect(),
+            t => {
+                return Err(Report::new(OglError::InvalidData)
+                    .attach_printable(format!("Unsupported color type {:?}", t)))
+            }
+        };
+
+        Self::from_rgba(info.width, info.height, data)
+    }
+
+    /// Compare with `other` pixel by pixel.
+    ///
+    /// A pixel mismatches if any channel differs by more than `tolerance`. The number of
+    /// mismatched pixels is returned together with a diff image where mismatched pixels are red.
+    pub fn diff(&self, other: &Screenshot, tolerance: u8) -> Result<(usize, Screenshot), OglError> {
+        if self.width != other.width || self.height != other.height {
+            return Err(Report::new(OglError::InvalidData).attach_printable(format!(
+                "Size mismatch: {}x{} vs {}x{}",
+                self.width, self.height, other.width, other.height
+            )));
+        }
+
+        let mut mismatched = 0;
+        let mut data = Vec::with_capacity(self.data.len());
+
+        for (a, b) in self.data.chunks_exact(4).zip(other.data.chunks_exact(4)) {
+            if a.iter().zip(b).any(|(x, y)| x.abs_diff(*y) > tolerance) {
+                mismatched += 1;
+                data.extend_from_slice(&[0xff, 0, 0, 0xff]);
+            } else {
+                // Keep matched pixels as a dimmed gray for reference.
+                let gray = ((a[0] as u32 + a[1] as u32 + a[2] as u32) / 12) as u8;
+                data.extend_from_slice(&[gray, gray, gray, 0xff]);
+            }
+        }
+
+        Ok((mismatched, Self::from_rgba(self.width, self.height, data)?))
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), OglError> {
+        let path = path.as_ref();
+        let f = File::create(path).map_err(|e| {
+            Report::new(OglError::InvalidData)
+                .attach_printable(format!("Failed to create {}: {e}", path.display()))
+        })?;
+
+        let mut encoder = png::Encoder::new(BufWriter::new(f), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut w| w.write_image_data(&self.data))
+            .map_err(|e| {
+                Report::new(OglError::InvalidData)
+                    .attach_printable(format!("Failed to write {}: {e}", path.display()))
+            })
+    }
+}
+
+impl Display for Screenshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "width:{}, height:{}", self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Screenshot;
+
+    #[test]
+    fn gl_pixels_are_flipped() {
+        #[rustfmt::skip]
+        let pixels = [
+            1_u8, 1, 1, 1,    2, 2, 2, 2,     // bottom row
+            3_u8, 3, 3, 3,    4, 4, 4, 4,     // top row
+        ];
+
+        let s = Screenshot::from_gl_pixels(2, 2, &pixels).unwrap();
+        assert_eq!(s.pixel(0, 0), [3, 3, 3, 3]);
+        assert_eq!(s.pixel(1, 1), [2, 2, 2, 2]);
+
+        assert!(Screenshot::from_gl_pixels(3, 2, &pixels).is_err());
+    }
+
+    #[test]
+    fn diff_with_tolerance() {
+        let a = Screenshot::from_rgba(2, 1, vec![10, 10, 10, 255, 10, 10, 10, 255]).unwrap();
+        let b = Screenshot::from_rgba(2, 1, vec![12, 10, 10, 255, 40, 10, 10, 255]).unwrap();
+
+        let (mismatched, diff) = a.diff(&b, 2).unwrap();
+        assert_eq!(mismatched, 1);
+        assert_eq!(diff.pixel(1, 0), [0xff, 0, 0, 0xff]);
+
+        let (mismatched, _) = a.diff(&b, 30).unwrap();
+        assert_eq!(mismatched, 0);
+    }
+}
@@ -1,6 +1,5 @@
 #[allow(unused)]
 use {
-    super::gl::GlContextOps,
     clap::{Args, Parser},
     core::borrow::Borrow,
     error_stack::{Report, Result, ResultExt},
@@ -8,12 +7,18 @@ use {
         jdebug, jerror, jinfo, jtrace, jwarn, JloggerBuilder, LevelFilter, LogTimeFormat,
     },
     libm::sqrt,
+    libogl::demo::{DemoSwitch, RunState},
     libogl::error::OglError,
+    libogl::gl::GlContextOps,
+    libogl::input::{InputEvent, Key, MouseButton},
     sdl2::{
         event::Event,
+        keyboard::Keycode,
+        mouse::{MouseButton as SdlButton, MouseWheelDirection},
         video::{GLContext, Window},
         Sdl, VideoSubsystem,
     },
+    std::collections::VecDeque,
     std::f64::consts::PI,
     std::sync::atomic::{AtomicBool, Ordering},
     std::{fs::File, os::fd::AsFd},
@@ -25,6 +30,7 @@ pub struct Sdl2State {
     video: VideoSubsystem,
     window: Window,
     _gl_context: GLContext,
+    input: VecDeque<InputEvent>,
 }
 
 impl GlContextOps for Sdl2State {
@@ -72,10 +78,11 @@ impl Sdl2State {
             video,
             window,
             _gl_context,
+            input: VecDeque::new(),
         })
     }
 
-    pub fn dispatch(&self) -> Result<(), OglError> {
+    pub fn dispatch(&mut self) -> Result<(), OglError> {
         let mut event_pump = self
             .context
             .event_pump()
@@ -84,15 +91,58 @@ impl Sdl2State {
         for event in event_pump.poll_iter() {
             match event {
                 Event::Quit { timestamp: _ } => {
-                    super::RunState::global_stop();
+                    RunState::global_stop();
                 }
                 Event::KeyDown {
-                    keycode: Some(key), ..
+                    keycode: Some(key),
+                    repeat,
+                    ..
                 } => {
-                    if key == sdl2::keyboard::Keycode::Escape {
-                        super::RunState::global_stop();
+                    self.input.push_back(InputEvent::Key {
+                        key: input_key(key),
+                        pressed: true,
+                    });
+                    // Holding a key must not go through every demo.
+                    if !repeat {
+                        Self::switch_demo(key);
                     }
                 }
+                Event::KeyUp {
+                    keycode: Some(key), ..
+                } => self.input.push_back(InputEvent::Key {
+                    key: input_key(key),
+                    pressed: false,
+                }),
+                Event::MouseMotion { x, y, .. } => {
+                    self.input.push_back(InputEvent::PointerMotion {
+                        x: x as f32,
+                        y: y as f32,
+                    })
+                }
+                Event::MouseButtonDown { mouse_btn, .. } => {
+                    self.input.push_back(InputEvent::PointerButton {
+                        button: input_button(mouse_btn),
+                        pressed: true,
+                    })
+                }
+                Event::MouseButtonUp { mouse_btn, .. } => {
+                    self.input.push_back(InputEvent::PointerButton {
+                        button: input_button(mouse_btn),
+                        pressed: false,
+                    })
+                }
+                Event::MouseWheel {
+                    x, y, direction, ..
+                } => {
+                    let sign = match direction {
+                        MouseWheelDirection::Flipped => -1.0,
+                        _ => 1.0,
+                    };
+                    self.input.push_back(InputEvent::Scroll {
+                        dx: x as f32 * sign,
+                        dy: y as f32 * sign,
+                    });
+                }
                 _ => {}
             }
         }
@@ -100,8 +150,60 @@ impl Sdl2State {
         Ok(())
     }
 
+    pub fn poll_input(&mut self) -> Option<InputEvent> {
+        self.input.pop_front()
+    }
+
+    fn switch_demo(key: Keycode) {
+        match key {
+            Keycode::Escape => RunState::global_stop(),
+            Keycode::N | Keycode::Right => RunState::request_switch(DemoSwitch::Next),
+            Keycode::P | Keycode::Left => RunState::request_switch(DemoSwitch::Previous),
+            Keycode::Num0 => RunState::request_switch(DemoSwitch::Id(10)),
+            _ => {
+                let id = key as i32 - Keycode::Num0 as i32;
+                if (1..=9).contains(&id) {
+                    RunState::request_switch(DemoSwitch::Id(id as usize));
+                }
+            }
+        }
+    }
+
     pub fn swap_window(&self) -> Result<(), OglError> {
         self.window.gl_swap_window();
         Ok(())
     }
 }
+
+fn input_key(key: Keycode) -> Key {
+    match key {
+        Keycode::Escape => Key::Escape,
+        Keycode::Space => Key::Space,
+        Keycode::Return | Keycode::KpEnter => Key::Enter,
+        Keycode::LShift | Keycode::RShift => Key::Shift,
+        Keycode::LCtrl | Keycode::RCtrl => Key::Control,
+        Keycode::LAlt | Keycode::RAlt => Key::Alt,
+        Keycode::Left => Key::Left,
+        Keycode::Right => Key::Right,
+        Keycode::Up => Key::Up,
+        Keycode::Down => Key::Down,
+        Keycode::PageUp => Key::PageUp,
+        Keycode::PageDown => Key::PageDown,
+        // Printable keys use their character as key code.
+        _ => char::from_u32(key as i32 as u32)
+            .filter(|c| c.is_ascii_graphic())
+            .map(|c| Key::Char(c.to_ascii_lowercase()))
+            .unwrap_or(Key::Unknown),
+    }
+}
+
+fn input_button(button: SdlButton) -> MouseButton {
+    match button {
+        SdlButton::Left => MouseButton::Left,
+        SdlButton::Middle => MouseButton::Middle,
+        SdlButton::Right => MouseButton::Right,
+        SdlButton::X1 => MouseButton::Other(4),
+        SdlButton::X2 => MouseButton::Other(5),
+        SdlButton::Unknown => MouseButton::Other(0),
+    }
+}
@@ -0,0 +1,365 @@
+use super::error::OglError;
+use super::glapi;
+use super::preprocess::Source;
+use super::uniform::{glsl_type_name, UniformValue};
+use error_stack::{Report, Result};
+use jlogger_tracing::{jdebug, jerror};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fmt::Display;
+use std::rc::Rc;
+
+/// Compiled shader object, deleted when dropped.
+pub struct Shader {
+    gl: Rc<glapi::GlFns>,
+    id: u32,
+    stage: glapi::ShaderType,
+}
+
+impl Shader {
+    pub fn compile(
+        gl: &Rc<glapi::GlFns>,
+        stage: glapi::ShaderType,
+        src: &str,
+    ) -> Result<Self, OglError> {
+        Self::compile_source(gl, stage, &Source::new(src))
+    }
+
+    /// Compile a preprocessed source, the error log refers to the original files and lines.
+    ///
+    /// On failure OglError::ShaderCompile is returned with the lines reported by the compiler.
+    pub fn compile_source(
+        gl: &Rc<glapi::GlFns>,
+        stage: glapi::ShaderType,
+        source: &Source,
+    ) -> Result<Self, OglError> {
+        let shader = Self {
+            gl: gl.clone(),
+            id: gl.CreateShader(stage),
+            stage,
+        };
+        let src = source.text();
+
+        unsafe {
+            gl.ShaderSource(
+                shader.id,
+                1,
+                &src.as_bytes().as_ptr().cast(),
+                &src.len().try_into().unwrap(),
+            );
+
+            gl.CompileShader(shader.id);
+
+            let mut success = 0;
+            gl.GetShaderiv(shader.id, glapi::GL_COMPILE_STATUS, &mut success);
+
+            if success == 0 {
+                let mut log_len = 0_i32;
+                gl.GetShaderiv(shader.id, glapi::GL_INFO_LOG_LENGTH, &mut log_len);
+
+                let mut v = vec![0_u8; log_len.max(1) as usize];
+                gl.GetShaderInfoLog(
+                    shader.id,
+                    v.len() as i32,
+                    &mut log_len,
+                    v.as_mut_ptr().cast(),
+                );
+                v.truncate(log_len.max(0) as usize);
+
+                let log = String::from_utf8_lossy(&v);
+                let error = OglError::ShaderCompile {
+                    stage: stage_name(stage),
+                    log: source.map_log(&log),
+                    source_excerpt: source.excerpt(&log),
+                };
+                jerror!("{}", error);
+
+                return Err(Report::new(error));
+            }
+        }
+
+        Ok(shader)
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn stage(&self) -> glapi::ShaderType {
+        self.stage
+    }
+}
+
+/// Name of `stage` used in error messages.
+pub fn stage_name(stage: glapi::ShaderType) -> &'static str {
+    match stage {
+        glapi::GL_VERTEX_SHADER => "vertex",
+        glapi::GL_FRAGMENT_SHADER => "fragment",
+        glapi::GL_GEOMETRY_SHADER => "geometry",
+        _ => "unknown",
+    }
+}
+
+impl Drop for Shader {
+    fn drop(&mut self) {
+        self.gl.DeleteShader(self.id);
+    }
+}
+
+/// Active uniform or attribute of a linked program.
+#[derive(Debug, Clone)]
+pub struct ActiveVariable {
+    name: String,
+    location: i32,
+    kind: glapi::UniformType,
+    size: i32,
+}
+
+impl ActiveVariable {
+    /// Name without the "[0]" suffix of arrays.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn location(&self) -> i32 {
+        self.location
+    }
+
+    /// GLSL type such as GL_FLOAT_MAT4.
+    pub fn kind(&self) -> glapi::UniformType {
+        self.kind
+    }
+
+    /// Number of elements, larger than 1 for arrays.
+    pub fn size(&self) -> i32 {
+        self.size
+    }
+}
+
+impl Display for ActiveVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", glsl_type_name(self.kind), self.name)?;
+        if self.size > 1 {
+            write!(f, "[{}]", self.size)?;
+        }
+        write!(f, " (location {})", self.location)
+    }
+}
+
+/// Linked program object, deleted when dropped.
+///
+/// Active uniforms and attributes are enumerated after linking so that they can be looked up by
+/// name without querying GL.
+pub struct Program {
+    gl: Rc<glapi::GlFns>,
+    id: u32,
+    uniforms: HashMap<String, ActiveVariable>,
+    attributes: HashMap<String, ActiveVariable>,
+}
+
+impl Program {
+    /// Link `shaders` into a program. The shaders can be dropped afterwards.
+    pub fn link(gl: &Rc<glapi::GlFns>, shaders: &[&Shader]) -> Result<Self, OglError> {
+        let mut program = Self {
+            gl: gl.clone(),
+            id: gl.CreateProgram(),
+            uniforms: HashMap::new(),
+            attributes: HashMap::new(),
+        };
+
+        for shader in shaders {
+            gl.AttachShader(program.id, shader.id());
+        }
+
+        gl.LinkProgram(program.id);
+
+        unsafe {
+            for shader in shaders {
+                gl.DetachShader(program.id, shader.id());
+            }
+
+            let mut success = 0;
+            gl.GetProgramiv(program.id, glapi::GL_LINK_STATUS, &mut success);
+            if success == 0 {
+                let mut log_len = 0_i32;
+                gl.GetProgramiv(program.id, glapi::GL_INFO_LOG_LENGTH, &mut log_len);
+
+                let mut v = vec![0_u8; log_len.max(1) as usize];
+                gl.GetProgramInfoLog(
+                    program.id,
+                    v.len() as i32,
+                    &mut log_len,
+                    v.as_mut_ptr().cast(),
+                );
+                v.truncate(log_len.max(0) as usize);
+
+                let error = OglError::ProgramLink {
+                    log: String::from_utf8_lossy(&v).to_string(),
+                };
+                jerror!("{}", error);
+
+                return Err(Report::new(error));
+            }
+        }
+
+        program.reflect();
+
+        Ok(program)
+    }
+
+    /// Take the ownership of the linked program `id`, e.g. loaded by glProgramBinary().
+    pub(crate) fn from_linked(gl: &Rc<glapi::GlFns>, id: u32) -> Self {
+        let mut program = Self {
+            gl: gl.clone(),
+            id,
+            uniforms: HashMap::new(),
+            attributes: HashMap::new(),
+        };
+
+        program.reflect();
+        program
+    }
+
+    fn reflect(&mut self) {
+        let gl = &self.gl;
+
+        for uniform in [true, false] {
+            let (count_name, length_name) = if uniform {
+                (
+                    glapi::GL_ACTIVE_UNIFORMS,
+                    glapi::GL_ACTIVE_UNIFORM_MAX_LENGTH,
+                )
+            } else {
+                (
+                    glapi::GL_ACTIVE_ATTRIBUTES,
+                    glapi::GL_ACTIVE_ATTRIBUTE_MAX_LENGTH,
+                )
+            };
+
+            let mut count = 0;
+            let mut max_length = 0;
+            unsafe {
+                gl.GetProgramiv(self.id, count_name, &mut count);
+                gl.GetProgramiv(self.id, length_name, &mut max_length);
+            }
+
+            for index in 0..count as u32 {
+                let mut name = vec![0_u8; max_length.max(1) as usize];
+                let mut length = 0;
+                let mut size = 0;
+                let mut kind = glapi::GLenum(0);
+
+                let location = unsafe {
+                    if uniform {
+                        gl.GetActiveUniform(
+                            self.id,
+                            index,
+                            name.len() as i32,
+                            &mut length,
+                            &mut size,
+                            &mut kind,
+                            name.as_mut_ptr().cast(),
+                        );
+                    } else {
+                        gl.GetActiveAttrib(
+                            self.id,
+                            index,
+                            name.len() as i32,
+                            &mut length,
+                            &mut size,
+                            &mut kind,
+                            name.as_mut_ptr().cast(),
+                        );
+                    }
+
+                    name.truncate(length as usize);
+                    let c_name = CString::new(name.clone()).unwrap_or_default();
+                    if uniform {
+                        gl.GetUniformLocation(self.id, c_name.as_ptr().cast())
+                    } else {
+                        gl.GetAttribLocation(self.id, c_name.as_ptr().cast())
+                    }
+                };
+
+                let name = String::from_utf8_lossy(&name);
+                let variable = ActiveVariable {
+                    name: name.strip_suffix("[0]").unwrap_or(&name).to_owned(),
+                    location,
+                    kind,
+                    size,
+                };
+                jdebug!("program {}: {}", self.id, variable);
+
+                if uniform {
+                    self.uniforms.insert(variable.name.clone(), variable);
+                } else {
+                    self.attributes.insert(variable.name.clone(), variable);
+                }
+            }
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn use_program(&self) {
+        self.gl.UseProgram(self.id);
+    }
+
+    /// Active uniform `name`, "name[0]" is the same as "name" for arrays.
+    pub fn uniform(&self, name: &str) -> Option<&ActiveVariable> {
+        self.uniforms
+            .get(name)
+            .or_else(|| self.uniforms.get(name.strip_suffix("[0]")?))
+    }
+
+    pub fn uniforms(&self) -> impl Iterator<Item = &ActiveVariable> {
+        self.uniforms.values()
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&ActiveVariable> {
+        self.attributes.get(name)
+    }
+
+    pub fn attributes(&self) -> impl Iterator<Item = &ActiveVariable> {
+        self.attributes.values()
+    }
+
+    /// Location of the active uniform `name`.
+    pub fn uniform_location(&self, name: &str) -> Result<i32, OglError> {
+        self.uniform(name).map(|a| a.location).ok_or(
+            Report::new(OglError::UnknownUniform)
+                .attach_printable(format!("No active uniform {name} in program {}", self.id)),
+        )
+    }
+
+    /// Make the program current and set `value` to the uniform `name` after checking its type.
+    pub fn set_uniform<T: UniformValue>(&self, name: &str, value: T) -> Result<(), OglError> {
+        let uniform = self.uniform(name).ok_or(
+            Report::new(OglError::UnknownUniform)
+                .attach_printable(format!("No active uniform {name} in program {}", self.id)),
+        )?;
+
+        if !T::accepts(uniform.kind) {
+            return Err(
+                Report::new(OglError::UniformTypeMismatch).attach_printable(format!(
+                    "{name} is {} but {} is given",
+                    glsl_type_name(uniform.kind),
+                    T::glsl_type()
+                )),
+            );
+        }
+
+        self.gl.UseProgram(self.id);
+        unsafe { value.apply(&self.gl, uniform.location) };
+
+        Ok(())
+    }
+}
+
+impl Drop for Program {
+    fn drop(&mut self) {
+        self.gl.DeleteProgram(self.id);
+    }
+}
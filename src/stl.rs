@@ -0,0 +1,231 @@
+//! STL files, binary or ASCII.
+//!
+//! STL only stores triangles with a normal per facet. The facet normals are kept so that hard
+//! edges stay sharp. Facets without normal (0 0 0) share their corners by position and get smooth
+//! normals.
+
+use super::error::OglError;
+use super::mesh::{Mesh, MeshVertex};
+use error_stack::{Report, Result};
+use glam::{Vec2, Vec3, Vec4};
+use std::collections::HashMap;
+use std::path::Path;
+
+const HEADER_SIZE: usize = 80;
+const FACET_SIZE: usize = 50;
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Mesh, OglError> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).map_err(|e| {
+        Report::new(OglError::InvalidData)
+            .attach_printable(format!("Failed to read {}: {e}", path.display()))
+    })?;
+
+    parse(&data).map_err(|e| e.attach_printable(format!("in {}", path.display())))
+}
+
+/// Parse the content of a binary or ASCII STL file.
+pub fn parse(data: &[u8]) -> Result<Mesh, OglError> {
+    // Some binary files start with "solid" too, their size tells them apart. Some of them have
+    // trailing bytes, they are read as binary if they are not valid ASCII files.
+    let size = data.get(HEADER_SIZE..HEADER_SIZE + 4).map(|n| {
+        HEADER_SIZE + 4 + u32::from_le_bytes([n[0], n[1], n[2], n[3]]) as usize * FACET_SIZE
+    });
+
+    let corners = if size == Some(data.len()) || !data.starts_with(b"solid") {
+        parse_binary(data)?
+    } else {
+        match parse_ascii(data) {
+            Ok(corners) => corners,
+            Err(_) if size.is_some_and(|size| size <= data.len()) => parse_binary(data)?,
+            Err(e) => return Err(e),
+        }
+    };
+
+    let mut vertices = Vec::new();
+    let bits = |v: Vec3| v.to_array().map(f32::to_bits);
+    let mut keys: HashMap<([u32; 3], [u32; 3]), u32> = HashMap::new();
+    let indices = corners
+        .iter()
+        .map(|&(p, normal)| {
+            let normal = normal.normalize_or_zero();
+            *keys.entry((bits(p), bits(normal))).or_insert_with(|| {
+                vertices.push(MeshVertex::new(p, normal, Vec2::ZERO, Vec4::ZERO));
+                vertices.len() as u32 - 1
+            })
+        })
+        .collect();
+
+    let mut mesh = Mesh::new(vertices, indices);
+    mesh.compute_missing_normals();
+    mesh.compute_tangents();
+    Ok(mesh)
+}
+
+/// Corners of the triangles of a binary file with their facet normal.
+fn parse_binary(data: &[u8]) -> Result<Vec<(Vec3, Vec3)>, OglError> {
+    let count = data
+        .get(HEADER_SIZE..HEADER_SIZE + 4)
+        .map(|n| u32::from_le_bytes([n[0], n[1], n[2], n[3]]) as usize)
+        .ok_or_else(|| invalid("truncated header".to_owned()))?;
+
+    let facets = &data[HEADER_SIZE + 4..];
+    if facets.len() < count * FACET_SIZE {
+        return Err(invalid(format!(
+            "{count} facets need {} bytes, {} found",
+            count * FACET_SIZE,
+            facets.len()
+        )));
+    }
+
+    let float = |b: &[u8]| f32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+    let vec3_at = move |b: &[u8]| Vec3::new(float(b), float(&b[4..]), float(&b[8..]));
+    Ok(facets
+        .chunks_exact(FACET_SIZE)
+        .take(count)
+        .flat_map(|facet| {
+            // The facet normal comes first and the attribute byte count last.
+            let normal = vec3_at(facet);
+            (0..3).map(move |i| (vec3_at(&facet[12 + i * 12..]), normal))
+        })
+        .collect())
+}
+
+/// Corners of the triangles of an ASCII file with their facet normal. Loops of more than three
+/// vertices are split in fans.
+fn parse_ascii(data: &[u8]) -> Result<Vec<(Vec3, Vec3)>, OglError> {
+    let src = std::str::from_utf8(data).map_err(|e| invalid(format!("not UTF-8: {e}")))?;
+
+    let mut corners = Vec::new();
+    let mut normal = Vec3::ZERO;
+    let mut polygon: Vec<Vec3> = Vec::new();
+    for (n, line) in src.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("facet") => {
+                normal = match words.next() {
+                    Some("normal") => vec3(&mut words, "normal", n + 1)?,
+                    _ => Vec3::ZERO,
+                };
+            }
+            Some("outer") => polygon.clear(),
+            Some("vertex") => polygon.push(vec3(&mut words, "vertex", n + 1)?),
+            Some("endloop") => {
+                if polygon.len() < 3 {
+                    return Err(invalid(format!(
+                        "line {}: loop of {} vertices",
+                        n + 1,
+                        polygon.len()
+                    )));
+                }
+                for i in 1..polygon.len() - 1 {
+                    corners.extend([polygon[0], polygon[i], polygon[i + 1]].map(|p| (p, normal)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if corners.is_empty() {
+        return Err(invalid("no facet found".to_owned()));
+    }
+
+    Ok(corners)
+}
+
+/// The next three numbers of `words`, a `what` at line `n`.
+fn vec3<'a, I>(words: &mut I, what: &str, n: usize) -> Result<Vec3, OglError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut v = [0.0; 3];
+    for value in &mut v {
+        *value = words
+            .next()
+            .and_then(|w| w.parse().ok())
+            .ok_or_else(|| invalid(format!("line {n}: invalid {what}")))?;
+    }
+    Ok(Vec3::from(v))
+}
+
+fn invalid(msg: String) -> Report<OglError> {
+    Report::new(OglError::InvalidData).attach_printable(format!("STL: {msg}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use glam::Vec3;
+
+    // Tetrahedron with counter-clockwise faces seen from outside.
+    const TRIANGLES: [[[f32; 3]; 3]; 4] = [
+        [[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]],
+        [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+        [[0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]],
+        [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    ];
+
+    #[test]
+    fn binary_and_ascii_give_the_same_mesh() {
+        let mut ascii = String::from("solid tetrahedron\n");
+        // Binary files may start with "solid" as well.
+        let mut binary = format!("{:80}", "solid binary").into_bytes();
+        binary.extend_from_slice(&(TRIANGLES.len() as u32).to_le_bytes());
+
+        for triangle in TRIANGLES {
+            ascii.push_str("  facet normal 0 0 0\n    outer loop\n");
+            binary.extend_from_slice(&[0; 12]);
+            for p in triangle {
+                ascii.push_str(&format!("      vertex {} {} {}\n", p[0], p[1], p[2]));
+                binary.extend(p.iter().flat_map(|v| v.to_le_bytes()));
+            }
+            ascii.push_str("    endloop\n  endfacet\n");
+            binary.extend_from_slice(&[0; 2]);
+        }
+        ascii.push_str("endsolid tetrahedron\n");
+
+        let mesh = parse(ascii.as_bytes()).unwrap();
+        assert_eq!(mesh, parse(&binary).unwrap());
+        assert_eq!(mesh.vertices().len(), 4);
+        assert_eq!(mesh.indices().len(), 12);
+
+        let center = Vec3::splat(0.25);
+        for v in mesh.vertices() {
+            assert!(v.normal.dot(v.position - center) > 0.0, "{v:?}");
+        }
+
+        assert!(parse(&binary[..binary.len() - 1]).is_err());
+
+        // Padded binary files starting with "solid".
+        binary.extend_from_slice(&[0; 16]);
+        assert_eq!(mesh, parse(&binary).unwrap());
+    }
+
+    #[test]
+    fn facet_normals_are_kept() {
+        let mut ascii = String::from("solid tetrahedron\n");
+        for triangle in TRIANGLES {
+            let [a, b, c] = triangle.map(Vec3::from);
+            let n = (b - a).cross(c - a).normalize();
+            ascii.push_str(&format!(
+                "facet normal {} {} {}\nouter loop\n",
+                n.x, n.y, n.z
+            ));
+            for p in [a, b, c] {
+                ascii.push_str(&format!("vertex {} {} {}\n", p.x, p.y, p.z));
+            }
+            ascii.push_str("endloop\nendfacet\n");
+        }
+
+        // The edges stay hard, every facet has its own corners.
+        let mesh = parse(ascii.as_bytes()).unwrap();
+        assert_eq!(mesh.vertices().len(), 12);
+        for triangle in mesh.indices().chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices()[triangle[i] as usize]);
+            let face = (b.position - a.position).cross(c.position - a.position);
+            for v in [a, b, c] {
+                assert!(v.normal.abs_diff_eq(face.normalize(), 1e-6), "{v:?}");
+            }
+        }
+    }
+}
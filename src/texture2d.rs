@@ -1,4 +1,5 @@
 use super::error::OglError;
+use super::glapi;
 use error_stack::{Report, Result};
 use stb_image::stb_image::*;
 use std::fmt::{Debug, Display};
@@ -28,7 +29,7 @@ impl Texture2D {
     pub unsafe fn create_from_file(
         &mut self,
         file_name: &str,
-        gl: &gl33::GlFns,
+        gl: &glapi::GlFns,
         filter: Texture2DFilter,
     ) -> Result<(), OglError> {
         let mut f = OpenOptions::new()
@@ -52,7 +53,7 @@ impl Texture2D {
     pub unsafe fn create_from_buffer(
         &mut self,
         buffer: &[u8],
-        gl: &gl33::GlFns,
+        gl: &glapi::GlFns,
         filter: Texture2DFilter,
     ) -> Result<(), OglError> {
         // stb_image loads the image with the origin at top left. while the origin is at bottom
@@ -80,11 +81,11 @@ impl Texture2D {
 
         let mut id = 0;
         gl.GenTextures(1, &mut id);
-        gl.BindTexture(gl33::GL_TEXTURE_2D, id);
+        gl.BindTexture(glapi::GL_TEXTURE_2D, id);
 
         let format = match bpp {
-            3 => gl33::GL_RGB,
-            4 => gl33::GL_RGBA,
+            3 => glapi::GL_RGB,
+            4 => glapi::GL_RGBA,
             _ => {
                 return Err(Report::new(OglError::InvalidData)
                     .attach_printable(format!("Invalid bpp {bpp}")))
@@ -92,60 +93,60 @@ impl Texture2D {
         };
 
         gl.TexImage2D(
-            gl33::GL_TEXTURE_2D,
+            glapi::GL_TEXTURE_2D,
             0,
             format.0 as i32,
             width,
             height,
             0,
             format,
-            gl33::GL_UNSIGNED_BYTE,
+            glapi::GL_UNSIGNED_BYTE,
             data.as_ptr().cast(),
         );
 
         let use_filter = match filter {
-            Texture2DFilter::Linear => gl33::GL_LINEAR,
-            Texture2DFilter::Nearest => gl33::GL_NEAREST,
+            Texture2DFilter::Linear => glapi::GL_LINEAR,
+            Texture2DFilter::Nearest => glapi::GL_NEAREST,
             Texture2DFilter::NearestMiMapNearest => {
-                gl.GenerateMipmap(gl33::GL_TEXTURE_2D);
-                gl33::GL_NEAREST_MIPMAP_NEAREST
+                gl.GenerateMipmap(glapi::GL_TEXTURE_2D);
+                glapi::GL_NEAREST_MIPMAP_NEAREST
             }
             Texture2DFilter::NearestMiMapLinear => {
-                gl.GenerateMipmap(gl33::GL_TEXTURE_2D);
-                gl33::GL_NEAREST_MIPMAP_LINEAR
+                gl.GenerateMipmap(glapi::GL_TEXTURE_2D);
+                glapi::GL_NEAREST_MIPMAP_LINEAR
             }
             Texture2DFilter::LinearMiMapNearest => {
-                gl.GenerateMipmap(gl33::GL_TEXTURE_2D);
-                gl33::GL_LINEAR_MIPMAP_NEAREST
+                gl.GenerateMipmap(glapi::GL_TEXTURE_2D);
+                glapi::GL_LINEAR_MIPMAP_NEAREST
             }
             Texture2DFilter::LinearMiMapLinear => {
-                gl.GenerateMipmap(gl33::GL_TEXTURE_2D);
-                gl33::GL_LINEAR_MIPMAP_LINEAR
+                gl.GenerateMipmap(glapi::GL_TEXTURE_2D);
+                glapi::GL_LINEAR_MIPMAP_LINEAR
             }
         };
 
         gl.TexParameteri(
-            gl33::GL_TEXTURE_2D,
-            gl33::GL_TEXTURE_MIN_FILTER,
+            glapi::GL_TEXTURE_2D,
+            glapi::GL_TEXTURE_MIN_FILTER,
             use_filter.0 as i32,
         );
         gl.TexParameteri(
-            gl33::GL_TEXTURE_2D,
-            gl33::GL_TEXTURE_MAG_FILTER,
-            gl33::GL_LINEAR.0 as i32,
+            glapi::GL_TEXTURE_2D,
+            glapi::GL_TEXTURE_MAG_FILTER,
+            glapi::GL_LINEAR.0 as i32,
         );
         gl.TexParameteri(
-            gl33::GL_TEXTURE_2D,
-            gl33::GL_TEXTURE_WRAP_S,
-            gl33::GL_CLAMP_TO_EDGE.0 as i32,
+            glapi::GL_TEXTURE_2D,
+            glapi::GL_TEXTURE_WRAP_S,
+            glapi::GL_CLAMP_TO_EDGE.0 as i32,
         );
         gl.TexParameteri(
-            gl33::GL_TEXTURE_2D,
-            gl33::GL_TEXTURE_WRAP_T,
-            gl33::GL_CLAMP_TO_EDGE.0 as i32,
+            glapi::GL_TEXTURE_2D,
+            glapi::GL_TEXTURE_WRAP_T,
+            glapi::GL_CLAMP_TO_EDGE.0 as i32,
         );
 
-        gl.BindBuffer(gl33::GL_TEXTURE_2D, 0);
+        gl.BindBuffer(glapi::GL_TEXTURE_2D, 0);
 
         self.id = id;
         self.data = data;
@@ -180,16 +181,16 @@ impl Texture2D {
         self.id
     }
 
-    pub unsafe fn bind(&self, gl: &gl33::GlFns, slot: i32, location: i32) -> Result<(), OglError> {
+    pub unsafe fn bind(&self, gl: &glapi::GlFns, slot: i32, location: i32) -> Result<(), OglError> {
         let s = match slot {
-            0 => gl33::GL_TEXTURE0,
-            1 => gl33::GL_TEXTURE1,
-            2 => gl33::GL_TEXTURE2,
-            3 => gl33::GL_TEXTURE3,
-            4 => gl33::GL_TEXTURE4,
-            5 => gl33::GL_TEXTURE5,
-            6 => gl33::GL_TEXTURE6,
-            7 => gl33::GL_TEXTURE7,
+            0 => glapi::GL_TEXTURE0,
+            1 => glapi::GL_TEXTURE1,
+            2 => glapi::GL_TEXTURE2,
+            3 => glapi::GL_TEXTURE3,
+            4 => glapi::GL_TEXTURE4,
+            5 => glapi::GL_TEXTURE5,
+            6 => glapi::GL_TEXTURE6,
+            7 => glapi::GL_TEXTURE7,
             _ => {
                 return Err(Report::new(OglError::InvalidData)
                     .attach_printable(format!("slot {} is not supported", slot)))
@@ -197,16 +198,28 @@ impl Texture2D {
         };
 
         gl.ActiveTexture(s);
-        gl.BindTexture(gl33::GL_TEXTURE_2D, self.id);
+        gl.BindTexture(glapi::GL_TEXTURE_2D, self.id);
         gl.Uniform1i(location, slot);
 
         Ok(())
     }
 
-    pub unsafe fn unbind(&self, gl: &gl33::GlFns) -> Result<(), OglError> {
-        gl.BindTexture(gl33::GL_TEXTURE_2D, 0);
+    pub unsafe fn unbind(&self, gl: &glapi::GlFns) -> Result<(), OglError> {
+        gl.BindTexture(glapi::GL_TEXTURE_2D, 0);
         Ok(())
     }
+
+    /// Delete the texture object, the texture can be created again afterwards.
+    ///
+    /// # Safety
+    ///
+    /// The GL context the texture is created in must be current.
+    pub unsafe fn delete(&mut self, gl: &glapi::GlFns) {
+        if self.id != 0 {
+            gl.DeleteTextures(1, &self.id);
+            self.id = 0;
+        }
+    }
 }
 
 impl Display for Texture2D {
@@ -239,7 +252,7 @@ impl Texture2DCubeMap {
     pub unsafe fn create_from_file(
         &mut self,
         files: Vec<&str>,
-        gl: &gl33::GlFns,
+        gl: &glapi::GlFns,
         filter: Texture2DFilter,
     ) -> Result<(), OglError> {
         if files.len() < 6 {
@@ -263,7 +276,7 @@ impl Texture2DCubeMap {
     pub unsafe fn create_from_buffer(
         &mut self,
         buffers: Vec<&[u8]>,
-        gl: &gl33::GlFns,
+        gl: &glapi::GlFns,
         filter: Texture2DFilter,
     ) -> Result<(), OglError> {
         if buffers.len() < 6 {
@@ -271,7 +284,7 @@ impl Texture2DCubeMap {
         }
 
         gl.GenTextures(1, &mut self.id);
-        gl.BindTexture(gl33::GL_TEXTURE_CUBE_MAP, self.id);
+        gl.BindTexture(glapi::GL_TEXTURE_CUBE_MAP, self.id);
 
         for i in 0..6 {
             let mut width = 0;
@@ -293,29 +306,29 @@ impl Texture2DCubeMap {
             }
 
             let format = match bpp {
-                3 => gl33::GL_RGB,
-                4 => gl33::GL_RGBA,
+                3 => glapi::GL_RGB,
+                4 => glapi::GL_RGBA,
                 _ => {
                     return Err(Report::new(OglError::InvalidData)
                         .attach_printable(format!("Invalid bpp {bpp}")))
                 }
             };
 
-            // 0 => gl33::GL_TEXTURE_CUBE_MAP_POSITIVE_X,
-            // 1 => gl33::GL_TEXTURE_CUBE_MAP_NEGATIVE_X,
-            // 2 => gl33::GL_TEXTURE_CUBE_MAP_POSITIVE_Y,
-            // 3 => gl33::GL_TEXTURE_CUBE_MAP_NEGATIVE_Y,
-            // 4 => gl33::GL_TEXTURE_CUBE_MAP_POSITIVE_Z,
-            // 5 => gl33::GL_TEXTURE_CUBE_MAP_NEGATIVE_Z,
+            // 0 => glapi::GL_TEXTURE_CUBE_MAP_POSITIVE_X,
+            // 1 => glapi::GL_TEXTURE_CUBE_MAP_NEGATIVE_X,
+            // 2 => glapi::GL_TEXTURE_CUBE_MAP_POSITIVE_Y,
+            // 3 => glapi::GL_TEXTURE_CUBE_MAP_NEGATIVE_Y,
+            // 4 => glapi::GL_TEXTURE_CUBE_MAP_POSITIVE_Z,
+            // 5 => glapi::GL_TEXTURE_CUBE_MAP_NEGATIVE_Z,
             gl.TexImage2D(
-                gl33::GLenum(gl33::GL_TEXTURE_CUBE_MAP_NEGATIVE_X.0 + i as u32),
+                glapi::GLenum(glapi::GL_TEXTURE_CUBE_MAP_POSITIVE_X.0 + i as u32),
                 0,
                 format.0 as i32,
                 width,
                 height,
                 0,
                 format,
-                gl33::GL_UNSIGNED_BYTE,
+                glapi::GL_UNSIGNED_BYTE,
                 data.cast(),
             );
 
@@ -327,56 +340,56 @@ impl Texture2DCubeMap {
         }
 
         let use_filter = match filter {
-            Texture2DFilter::Linear => gl33::GL_LINEAR,
-            Texture2DFilter::Nearest => gl33::GL_NEAREST,
+            Texture2DFilter::Linear => glapi::GL_LINEAR,
+            Texture2DFilter::Nearest => glapi::GL_NEAREST,
             Texture2DFilter::NearestMiMapNearest => {
-                gl.GenerateMipmap(gl33::GL_TEXTURE_2D);
-                gl33::GL_NEAREST_MIPMAP_NEAREST
+                gl.GenerateMipmap(glapi::GL_TEXTURE_2D);
+                glapi::GL_NEAREST_MIPMAP_NEAREST
             }
             Texture2DFilter::NearestMiMapLinear => {
-                gl.GenerateMipmap(gl33::GL_TEXTURE_2D);
-                gl33::GL_NEAREST_MIPMAP_LINEAR
+                gl.GenerateMipmap(glapi::GL_TEXTURE_2D);
+                glapi::GL_NEAREST_MIPMAP_LINEAR
             }
             Texture2DFilter::LinearMiMapNearest => {
-                gl.GenerateMipmap(gl33::GL_TEXTURE_2D);
-                gl33::GL_LINEAR_MIPMAP_NEAREST
+                gl.GenerateMipmap(glapi::GL_TEXTURE_2D);
+                glapi::GL_LINEAR_MIPMAP_NEAREST
             }
             Texture2DFilter::LinearMiMapLinear => {
-                gl.GenerateMipmap(gl33::GL_TEXTURE_2D);
-                gl33::GL_LINEAR_MIPMAP_LINEAR
+                gl.GenerateMipmap(glapi::GL_TEXTURE_2D);
+                glapi::GL_LINEAR_MIPMAP_LINEAR
             }
         };
 
         gl.TexParameteri(
-            gl33::GL_TEXTURE_CUBE_MAP,
-            gl33::GL_TEXTURE_MIN_FILTER,
+            glapi::GL_TEXTURE_CUBE_MAP,
+            glapi::GL_TEXTURE_MIN_FILTER,
             use_filter.0 as i32,
         );
 
         gl.TexParameteri(
-            gl33::GL_TEXTURE_CUBE_MAP,
-            gl33::GL_TEXTURE_MAG_FILTER,
-            gl33::GL_LINEAR.0 as i32,
+            glapi::GL_TEXTURE_CUBE_MAP,
+            glapi::GL_TEXTURE_MAG_FILTER,
+            glapi::GL_LINEAR.0 as i32,
         );
 
         gl.TexParameteri(
-            gl33::GL_TEXTURE_CUBE_MAP,
-            gl33::GL_TEXTURE_WRAP_S,
-            gl33::GL_CLAMP_TO_EDGE.0 as i32,
+            glapi::GL_TEXTURE_CUBE_MAP,
+            glapi::GL_TEXTURE_WRAP_S,
+            glapi::GL_CLAMP_TO_EDGE.0 as i32,
         );
         gl.TexParameteri(
-            gl33::GL_TEXTURE_CUBE_MAP,
-            gl33::GL_TEXTURE_WRAP_T,
-            gl33::GL_CLAMP_TO_EDGE.0 as i32,
+            glapi::GL_TEXTURE_CUBE_MAP,
+            glapi::GL_TEXTURE_WRAP_T,
+            glapi::GL_CLAMP_TO_EDGE.0 as i32,
         );
 
         gl.TexParameteri(
-            gl33::GL_TEXTURE_CUBE_MAP,
-            gl33::GL_TEXTURE_WRAP_R,
-            gl33::GL_CLAMP_TO_EDGE.0 as i32,
+            glapi::GL_TEXTURE_CUBE_MAP,
+            glapi::GL_TEXTURE_WRAP_R,
+            glapi::GL_CLAMP_TO_EDGE.0 as i32,
         );
 
-        //gl.BindTexture(gl33::GL_TEXTURE_CUBE_MAP, 0);
+        //gl.BindTexture(glapi::GL_TEXTURE_CUBE_MAP, 0);
 
         Ok(())
     }
@@ -409,16 +422,16 @@ impl Texture2DCubeMap {
         self.id
     }
 
-    pub unsafe fn bind(&self, gl: &gl33::GlFns, slot: i32, location: i32) -> Result<(), OglError> {
+    pub unsafe fn bind(&self, gl: &glapi::GlFns, slot: i32, location: i32) -> Result<(), OglError> {
         let s = match slot {
-            0 => gl33::GL_TEXTURE0,
-            1 => gl33::GL_TEXTURE1,
-            2 => gl33::GL_TEXTURE2,
-            3 => gl33::GL_TEXTURE3,
-            4 => gl33::GL_TEXTURE4,
-            5 => gl33::GL_TEXTURE5,
-            6 => gl33::GL_TEXTURE6,
-            7 => gl33::GL_TEXTURE7,
+            0 => glapi::GL_TEXTURE0,
+            1 => glapi::GL_TEXTURE1,
+            2 => glapi::GL_TEXTURE2,
+            3 => glapi::GL_TEXTURE3,
+            4 => glapi::GL_TEXTURE4,
+            5 => glapi::GL_TEXTURE5,
+            6 => glapi::GL_TEXTURE6,
+            7 => glapi::GL_TEXTURE7,
             _ => {
                 return Err(Report::new(OglError::InvalidData)
                     .attach_printable(format!("slot {} is not supported", slot)))
@@ -426,16 +439,28 @@ impl Texture2DCubeMap {
         };
 
         gl.ActiveTexture(s);
-        gl.BindTexture(gl33::GL_TEXTURE_CUBE_MAP, self.id);
+        gl.BindTexture(glapi::GL_TEXTURE_CUBE_MAP, self.id);
         gl.Uniform1i(location, slot);
 
         Ok(())
     }
 
-    pub unsafe fn unbind(&self, _gl: &gl33::GlFns) -> Result<(), OglError> {
-        //gl.BindTexture(gl33::GL_TEXTURE_CUBE_MAP, 0);
+    pub unsafe fn unbind(&self, _gl: &glapi::GlFns) -> Result<(), OglError> {
+        //gl.BindTexture(glapi::GL_TEXTURE_CUBE_MAP, 0);
         Ok(())
     }
+
+    /// Delete the texture object, the texture can be created again afterwards.
+    ///
+    /// # Safety
+    ///
+    /// The GL context the texture is created in must be current.
+    pub unsafe fn delete(&mut self, gl: &glapi::GlFns) {
+        if self.id != 0 {
+            gl.DeleteTextures(1, &self.id);
+            self.id = 0;
+        }
+    }
 }
 
 impl Display for Texture2DCubeMap {
@@ -0,0 +1,190 @@
+use super::error::OglError;
+use super::glapi;
+use super::shader::Program;
+use error_stack::Result;
+use glam::{IVec2, IVec3, IVec4, Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
+
+/// Value which can be assigned to a uniform with Program::set_uniform().
+pub trait UniformValue {
+    /// Whether the value can be assigned to a uniform of GLSL type `kind`.
+    fn accepts(kind: glapi::UniformType) -> bool;
+
+    /// GLSL type name of the value, used in error messages.
+    fn glsl_type() -> &'static str;
+
+    /// Set the value to `location` of the current program.
+    ///
+    /// # Safety
+    ///
+    /// `location` must belong to the current program and accept the value.
+    unsafe fn apply(&self, gl: &glapi::GlFns, location: i32);
+}
+
+/// Set of uniforms set at once with GlState::set_uniforms(), usually implemented with
+/// `#[derive(Uniforms)]`.
+///
+/// ```
+/// use glam::{Mat4, Vec3};
+/// use libogl::uniform::Uniforms;
+///
+/// #[derive(Uniforms)]
+/// struct Light {
+///     #[uniform = "u_mvpMatrix"]
+///     mvp: Mat4,
+///     color: Vec3,
+/// }
+/// ```
+///
+/// Fields of types which cannot be set to a uniform are rejected:
+///
+/// ```compile_fail
+/// # use glam::Mat4;
+/// # use libogl::uniform::Uniforms;
+/// #[derive(Uniforms)]
+/// struct Light {
+///     #[uniform = "u_mvpMatrix"]
+///     mvp: Mat4,
+///     intensity: f64,
+/// }
+/// ```
+pub trait Uniforms {
+    fn upload(&self, program: &Program) -> Result<(), OglError>;
+}
+
+pub use ogl_derive::Uniforms;
+
+/// GLSL name of an uniform or attribute type reported by glGetActiveUniform()/glGetActiveAttrib().
+pub fn glsl_type_name(kind: glapi::UniformType) -> &'static str {
+    match kind {
+        glapi::GL_FLOAT => "float",
+        glapi::GL_FLOAT_VEC2 => "vec2",
+        glapi::GL_FLOAT_VEC3 => "vec3",
+        glapi::GL_FLOAT_VEC4 => "vec4",
+        glapi::GL_INT => "int",
+        glapi::GL_INT_VEC2 => "ivec2",
+        glapi::GL_INT_VEC3 => "ivec3",
+        glapi::GL_INT_VEC4 => "ivec4",
+        glapi::GL_UNSIGNED_INT => "uint",
+        glapi::GL_UNSIGNED_INT_VEC2 => "uvec2",
+        glapi::GL_UNSIGNED_INT_VEC3 => "uvec3",
+        glapi::GL_UNSIGNED_INT_VEC4 => "uvec4",
+        glapi::GL_BOOL => "bool",
+        glapi::GL_BOOL_VEC2 => "bvec2",
+        glapi::GL_BOOL_VEC3 => "bvec3",
+        glapi::GL_BOOL_VEC4 => "bvec4",
+        glapi::GL_FLOAT_MAT2 => "mat2",
+        glapi::GL_FLOAT_MAT3 => "mat3",
+        glapi::GL_FLOAT_MAT4 => "mat4",
+        glapi::GL_FLOAT_MAT2x3 => "mat2x3",
+        glapi::GL_FLOAT_MAT2x4 => "mat2x4",
+        glapi::GL_FLOAT_MAT3x2 => "mat3x2",
+        glapi::GL_FLOAT_MAT3x4 => "mat3x4",
+        glapi::GL_FLOAT_MAT4x2 => "mat4x2",
+        glapi::GL_FLOAT_MAT4x3 => "mat4x3",
+        glapi::GL_SAMPLER_2D => "sampler2D",
+        glapi::GL_SAMPLER_3D => "sampler3D",
+        glapi::GL_SAMPLER_CUBE => "samplerCube",
+        glapi::GL_SAMPLER_2D_SHADOW => "sampler2DShadow",
+        glapi::GL_SAMPLER_2D_ARRAY => "sampler2DArray",
+        glapi::GL_SAMPLER_2D_ARRAY_SHADOW => "sampler2DArrayShadow",
+        glapi::GL_SAMPLER_CUBE_SHADOW => "samplerCubeShadow",
+        glapi::GL_INT_SAMPLER_2D => "isampler2D",
+        glapi::GL_INT_SAMPLER_3D => "isampler3D",
+        glapi::GL_INT_SAMPLER_CUBE => "isamplerCube",
+        glapi::GL_INT_SAMPLER_2D_ARRAY => "isampler2DArray",
+        glapi::GL_UNSIGNED_INT_SAMPLER_2D => "usampler2D",
+        glapi::GL_UNSIGNED_INT_SAMPLER_3D => "usampler3D",
+        glapi::GL_UNSIGNED_INT_SAMPLER_CUBE => "usamplerCube",
+        glapi::GL_UNSIGNED_INT_SAMPLER_2D_ARRAY => "usampler2DArray",
+        _ => "unknown",
+    }
+}
+
+/// Whether `kind` is a sampler type, samplers are set with the texture unit as int.
+pub fn is_sampler(kind: glapi::UniformType) -> bool {
+    glsl_type_name(kind).contains("sampler")
+}
+
+macro_rules! uniform_value {
+    ($t:ty, $kind:ident, $name:expr, |$v:ident, $gl:ident, $l:ident| $apply:expr) => {
+        impl UniformValue for $t {
+            fn accepts(kind: glapi::UniformType) -> bool {
+                kind == glapi::$kind
+            }
+
+            fn glsl_type() -> &'static str {
+                $name
+            }
+
+            unsafe fn apply(&self, $gl: &glapi::GlFns, $l: i32) {
+                let $v = self;
+                $apply
+            }
+        }
+    };
+    // glam vectors and matrices are set through the pointer to their components.
+    ($t:ty, $kind:ident, $name:expr, $func:ident) => {
+        uniform_value!($t, $kind, $name, |v, gl, l| gl.$func(
+            l,
+            1,
+            v.as_ref().as_ptr()
+        ));
+    };
+    ($t:ty, $kind:ident, $name:expr, $func:ident, matrix) => {
+        uniform_value!($t, $kind, $name, |v, gl, l| gl.$func(
+            l,
+            1,
+            0,
+            v.as_ref().as_ptr()
+        ));
+    };
+}
+
+uniform_value!(f32, GL_FLOAT, "float", |v, gl, l| gl.Uniform1f(l, *v));
+uniform_value!(u32, GL_UNSIGNED_INT, "uint", |v, gl, l| gl
+    .Uniform1ui(l, *v));
+uniform_value!(bool, GL_BOOL, "bool", |v, gl, l| gl.Uniform1i(l, *v as i32));
+uniform_value!(Vec2, GL_FLOAT_VEC2, "vec2", Uniform2fv);
+uniform_value!(Vec3, GL_FLOAT_VEC3, "vec3", Uniform3fv);
+uniform_value!(Vec4, GL_FLOAT_VEC4, "vec4", Uniform4fv);
+uniform_value!(IVec2, GL_INT_VEC2, "ivec2", Uniform2iv);
+uniform_value!(IVec3, GL_INT_VEC3, "ivec3", Uniform3iv);
+uniform_value!(IVec4, GL_INT_VEC4, "ivec4", Uniform4iv);
+uniform_value!(Mat2, GL_FLOAT_MAT2, "mat2", UniformMatrix2fv, matrix);
+uniform_value!(Mat3, GL_FLOAT_MAT3, "mat3", UniformMatrix3fv, matrix);
+uniform_value!(Mat4, GL_FLOAT_MAT4, "mat4", UniformMatrix4fv, matrix);
+
+// int is also used to select the texture unit of samplers.
+impl UniformValue for i32 {
+    fn accepts(kind: glapi::UniformType) -> bool {
+        kind == glapi::GL_INT || is_sampler(kind)
+    }
+
+    fn glsl_type() -> &'static str {
+        "int"
+    }
+
+    unsafe fn apply(&self, gl: &glapi::GlFns, location: i32) {
+        gl.Uniform1i(location, *self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glapi, glsl_type_name, UniformValue};
+
+    #[test]
+    fn uniform_types_are_checked() {
+        assert!(glam::Mat4::accepts(glapi::GL_FLOAT_MAT4));
+        assert!(!glam::Mat4::accepts(glapi::GL_FLOAT_MAT3));
+        assert!(!glam::Vec4::accepts(glapi::GL_FLOAT_VEC3));
+        assert!(i32::accepts(glapi::GL_SAMPLER_CUBE));
+        assert!(!f32::accepts(glapi::GL_SAMPLER_2D));
+
+        assert_eq!(glsl_type_name(glapi::GL_SAMPLER_2D), "sampler2D");
+        assert_eq!(
+            glsl_type_name(glapi::GL_FLOAT_VEC3),
+            glam::Vec3::glsl_type()
+        );
+    }
+}
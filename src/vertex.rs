@@ -0,0 +1,329 @@
+use super::glapi;
+use glam::{Vec2, Vec3, Vec4};
+
+/// Type of a vertex attribute, giving the component count and type passed to
+/// glVertexAttribPointer().
+pub trait AttributeType {
+    const COMPONENTS: i32;
+    const KIND: glapi::VertexAttribPointerType;
+}
+
+macro_rules! attribute_type {
+    ($t:ty, $n:expr, $kind:ident) => {
+        impl AttributeType for $t {
+            const COMPONENTS: i32 = $n;
+            const KIND: glapi::VertexAttribPointerType = glapi::$kind;
+        }
+    };
+    ($t:ty, $kind:ident) => {
+        attribute_type!($t, 1, $kind);
+        attribute_type!([$t; 2], 2, $kind);
+        attribute_type!([$t; 3], 3, $kind);
+        attribute_type!([$t; 4], 4, $kind);
+    };
+}
+
+attribute_type!(f32, GL_FLOAT);
+attribute_type!(i8, GL_BYTE);
+attribute_type!(u8, GL_UNSIGNED_BYTE);
+attribute_type!(i16, GL_SHORT);
+attribute_type!(u16, GL_UNSIGNED_SHORT);
+attribute_type!(i32, GL_INT);
+attribute_type!(u32, GL_UNSIGNED_INT);
+attribute_type!(Vec2, 2, GL_FLOAT);
+attribute_type!(Vec3, 3, GL_FLOAT);
+attribute_type!(Vec4, 4, GL_FLOAT);
+
+/// One attribute of an interleaved vertex: `components` values of `kind` found `offset` bytes
+/// after the start of the vertex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VertexAttribute {
+    location: u32,
+    components: i32,
+    kind: glapi::VertexAttribPointerType,
+    normalized: bool,
+    offset: usize,
+}
+
+impl VertexAttribute {
+    pub fn new(
+        location: u32,
+        components: i32,
+        kind: glapi::VertexAttribPointerType,
+        normalized: bool,
+        offset: usize,
+    ) -> Self {
+        Self {
+            location,
+            components,
+            kind,
+            normalized,
+            offset,
+        }
+    }
+
+    pub fn location(&self) -> u32 {
+        self.location
+    }
+
+    pub fn components(&self) -> i32 {
+        self.components
+    }
+
+    pub fn kind(&self) -> glapi::VertexAttribPointerType {
+        self.kind
+    }
+
+    pub fn normalized(&self) -> bool {
+        self.normalized
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// Attributes of the vertices stored in a buffer, `stride` bytes apart.
+///
+/// ```ignore
+/// #[repr(C)]
+/// struct ColorVertex {
+///     position: Vec3,
+///     color: [u8; 4],
+/// }
+///
+/// let layout = VertexLayout::of::<ColorVertex>()
+///     .attribute::<Vec3>(0, offset_of!(ColorVertex, position))
+///     .normalized::<[u8; 4]>(1, offset_of!(ColorVertex, color));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VertexLayout {
+    stride: usize,
+    attributes: Vec<VertexAttribute>,
+}
+
+impl VertexLayout {
+    /// Empty layout of vertices `stride` bytes long.
+    pub fn new(stride: usize) -> Self {
+        Self {
+            stride,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Empty layout of vertices of type V.
+    pub fn of<V>() -> Self {
+        Self::new(std::mem::size_of::<V>())
+    }
+
+    /// Add an attribute of type T at `location`, converted to float by the GL if it is an
+    /// integer type.
+    pub fn attribute<T: AttributeType>(self, location: u32, offset: usize) -> Self {
+        self.push(VertexAttribute::new(
+            location,
+            T::COMPONENTS,
+            T::KIND,
+            false,
+            offset,
+        ))
+    }
+
+    /// Add an attribute of integer type T at `location`, mapped to [0, 1] or [-1, 1].
+    pub fn normalized<T: AttributeType>(self, location: u32, offset: usize) -> Self {
+        self.push(VertexAttribute::new(
+            location,
+            T::COMPONENTS,
+            T::KIND,
+            true,
+            offset,
+        ))
+    }
+
+    pub fn push(mut self, attribute: VertexAttribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+
+    /// Set and enable the pointer of every attribute, reading from the buffer bound to
+    /// GL_ARRAY_BUFFER. The pointers are recorded in the bound vertex array.
+    pub fn apply(&self, gl: &glapi::GlFns) {
+        for a in &self.attributes {
+            unsafe {
+                gl.VertexAttribPointer(
+                    a.location,
+                    a.components,
+                    a.kind,
+                    a.normalized as u8,
+                    self.stride as i32,
+                    a.offset as *const std::ffi::c_void,
+                );
+                gl.EnableVertexAttribArray(a.location);
+            }
+        }
+    }
+}
+
+/// Interleaved vertex whose attributes are described by layout(), usually implemented with
+/// `#[derive(Vertex)]`.
+///
+/// ```
+/// use glam::{Vec2, Vec3};
+/// use libogl::vertex::Vertex;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Vertex)]
+/// struct TexturedVertex {
+///     #[location = 0]
+///     position: Vec3,
+///     #[location = 1]
+///     uv: Vec2,
+/// }
+///
+/// assert_eq!(TexturedVertex::layout().stride(), 20);
+/// ```
+///
+/// The derive rejects structs without `#[repr(C)]`:
+///
+/// ```compile_fail
+/// # use glam::Vec3;
+/// # use libogl::vertex::Vertex;
+/// #[derive(Clone, Copy, Vertex)]
+/// struct NotReprC {
+///     #[location = 0]
+///     position: Vec3,
+/// }
+/// ```
+///
+/// fields without location:
+///
+/// ```compile_fail
+/// # use glam::{Vec2, Vec3};
+/// # use libogl::vertex::Vertex;
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Vertex)]
+/// struct NoLocation {
+///     #[location = 0]
+///     position: Vec3,
+///     uv: Vec2,
+/// }
+/// ```
+///
+/// locations used twice:
+///
+/// ```compile_fail
+/// # use glam::{Vec2, Vec3};
+/// # use libogl::vertex::Vertex;
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Vertex)]
+/// struct SameLocation {
+///     #[location = 0]
+///     position: Vec3,
+///     #[location = 0]
+///     uv: Vec2,
+/// }
+/// ```
+///
+/// and fields which are not attributes:
+///
+/// ```compile_fail
+/// # use glam::Vec3;
+/// # use libogl::vertex::Vertex;
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Vertex)]
+/// struct NotAttribute {
+///     #[location = 0]
+///     position: Vec3,
+///     #[location = 1]
+///     id: f64,
+/// }
+/// ```
+pub trait Vertex: Copy {
+    fn layout() -> VertexLayout;
+}
+
+pub use ogl_derive::Vertex;
+
+#[cfg(test)]
+mod tests {
+    use super::{glapi, Vertex, VertexLayout};
+    use glam::Vec4;
+    use glam::{Vec2, Vec3};
+    use std::mem::offset_of;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct TexturedVertex {
+        position: Vec3,
+        uv: Vec2,
+        color: [u8; 4],
+    }
+
+    impl Vertex for TexturedVertex {
+        fn layout() -> VertexLayout {
+            VertexLayout::of::<Self>()
+                .attribute::<Vec3>(0, offset_of!(Self, position))
+                .attribute::<Vec2>(1, offset_of!(Self, uv))
+                .normalized::<[u8; 4]>(2, offset_of!(Self, color))
+        }
+    }
+
+    #[test]
+    fn layout_follows_struct_fields() {
+        let layout = TexturedVertex::layout();
+        assert_eq!(layout.stride(), 24);
+
+        let a: Vec<_> = layout
+            .attributes()
+            .iter()
+            .map(|a| {
+                (
+                    a.location(),
+                    a.components(),
+                    a.kind(),
+                    a.normalized(),
+                    a.offset(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            a,
+            vec![
+                (0, 3, glapi::GL_FLOAT, false, 0),
+                (1, 2, glapi::GL_FLOAT, false, 12),
+                (2, 4, glapi::GL_UNSIGNED_BYTE, true, 20),
+            ]
+        );
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Vertex)]
+    struct DerivedVertex {
+        #[location = 0]
+        position: Vec3,
+        #[location = 1]
+        uv: Vec2,
+        #[location = 2]
+        #[normalized]
+        color: [u8; 4],
+        #[location = 4]
+        tangent: Vec4,
+    }
+
+    #[test]
+    fn derived_layout_matches_manual_one() {
+        let layout = VertexLayout::of::<DerivedVertex>()
+            .attribute::<Vec3>(0, offset_of!(DerivedVertex, position))
+            .attribute::<Vec2>(1, offset_of!(DerivedVertex, uv))
+            .normalized::<[u8; 4]>(2, offset_of!(DerivedVertex, color))
+            .attribute::<Vec4>(4, offset_of!(DerivedVertex, tangent));
+        assert_eq!(DerivedVertex::layout(), layout);
+    }
+}
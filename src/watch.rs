@@ -0,0 +1,148 @@
+use super::error::OglError;
+use error_stack::{Report, Result};
+use std::collections::HashMap;
+use std::ffi::{CString, OsStr};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// Watch files for modification through inotify.
+///
+/// The parent directories are watched instead of the files themselves because most editors save
+/// a file by writing a new one and renaming it over the old one.
+pub struct FileWatcher {
+    fd: OwnedFd,
+    dirs: HashMap<i32, PathBuf>,
+    files: Vec<PathBuf>,
+}
+
+impl FileWatcher {
+    pub fn new() -> Result<Self, OglError> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(Report::new(OglError::Unexpected).attach_printable(format!(
+                "inotify_init1 failed: {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+
+        Ok(Self {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            dirs: HashMap::new(),
+            files: Vec::new(),
+        })
+    }
+
+    /// Add `path` to the watched files. The file must exist.
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) -> Result<(), OglError> {
+        let path = path.as_ref().canonicalize().map_err(|e| {
+            Report::new(OglError::InvalidData)
+                .attach_printable(format!("Failed to watch {}: {e}", path.as_ref().display()))
+        })?;
+
+        if self.files.contains(&path) {
+            return Ok(());
+        }
+
+        let dir = path.parent().unwrap_or(Path::new("/")).to_path_buf();
+        if !self.dirs.values().any(|a| *a == dir) {
+            let c_dir = CString::new(dir.as_os_str().as_bytes()).map_err(|e| {
+                Report::new(OglError::InvalidData)
+                    .attach_printable(format!("Invalid path {}: {e}", dir.display()))
+            })?;
+
+            let wd = unsafe {
+                libc::inotify_add_watch(
+                    self.fd.as_raw_fd(),
+                    c_dir.as_ptr(),
+                    libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO,
+                )
+            };
+
+            if wd < 0 {
+                return Err(Report::new(OglError::Unexpected).attach_printable(format!(
+                    "Failed to watch {}: {}",
+                    dir.display(),
+                    std::io::Error::last_os_error()
+                )));
+            }
+
+            self.dirs.insert(wd, dir);
+        }
+
+        self.files.push(path);
+        Ok(())
+    }
+
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Watched files modified since the last call. Never blocks.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = Vec::new();
+        let mut buf = [0_u8; 4096];
+
+        loop {
+            let len = unsafe {
+                libc::read(
+                    self.fd.as_raw_fd(),
+                    buf.as_mut_ptr().cast(),
+                    buf.len() as libc::size_t,
+                )
+            };
+
+            if len <= 0 {
+                break;
+            }
+
+            let mut offset = 0;
+            while offset + std::mem::size_of::<libc::inotify_event>() <= len as usize {
+                let event: libc::inotify_event =
+                    unsafe { std::ptr::read_unaligned(buf.as_ptr().add(offset).cast()) };
+                let name_start = offset + std::mem::size_of::<libc::inotify_event>();
+                let name = &buf[name_start..name_start + event.len as usize];
+                offset = name_start + event.len as usize;
+
+                // The name is padded with NUL bytes.
+                let name = name.split(|a| *a == 0).next().unwrap_or_default();
+                if let Some(dir) = self.dirs.get(&event.wd) {
+                    let path = dir.join(OsStr::from_bytes(name));
+                    if self.files.contains(&path) && !changed.contains(&path) {
+                        changed.push(path);
+                    }
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileWatcher;
+
+    #[test]
+    fn watcher_reports_written_and_replaced_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let shader = dir.path().join("sample.vert");
+        let other = dir.path().join("other.vert");
+        std::fs::write(&shader, "#version 300 es\n").unwrap();
+
+        let mut watcher = FileWatcher::new().unwrap();
+        watcher.watch(&shader).unwrap();
+        let shader = shader.canonicalize().unwrap();
+        assert!(watcher.changed().is_empty());
+
+        std::fs::write(&other, "ignored").unwrap();
+        assert!(watcher.changed().is_empty());
+
+        std::fs::write(&shader, "#version 300 es\nvoid main() {}\n").unwrap();
+        assert_eq!(watcher.changed(), vec![shader.clone()]);
+
+        // Saved through rename like most editors do.
+        std::fs::rename(&other, &shader).unwrap();
+        assert_eq!(watcher.changed(), vec![shader]);
+    }
+}
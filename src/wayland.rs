@@ -1,21 +1,28 @@
 #[allow(unused)]
 use {
+    crate::xkb::{Modifiers, Xkb},
     core::borrow::Borrow,
     error_stack::{Report, Result, ResultExt},
     jlogger_tracing::{
         jdebug, jerror, jinfo, jtrace, jwarn, JloggerBuilder, LevelFilter, LogTimeFormat,
     },
+    libogl::demo::RunState,
     libogl::error::OglError,
     std::sync::{
         atomic::{AtomicBool, Ordering},
         Mutex,
     },
     std::{fs::File, os::fd::AsFd},
-    std::{thread::sleep, time::Duration},
+    std::{
+        thread::sleep,
+        time::{Duration, Instant},
+    },
     wayland_client::{
         backend::{Backend, ObjectId},
         delegate_noop,
+        globals::{registry_queue_init, GlobalList, GlobalListContents},
         protocol::wl_keyboard::{self, KeyState},
+        protocol::wl_pointer::{self, ButtonState},
         protocol::{
             wl_buffer, wl_compositor, wl_registry, wl_seat, wl_shm, wl_shm_pool, wl_surface,
         },
@@ -25,10 +32,55 @@ use {
     wayland_protocols::xdg::shell::client::{xdg_surface, xdg_toplevel, xdg_wm_base},
 };
 
+// Highest versions of the globals this code is written for.
+const WL_COMPOSITOR_VERSION: u32 = 4;
+const WL_SHM_VERSION: u32 = 1;
+const WL_SEAT_VERSION: u32 = 8;
+const XDG_WM_BASE_VERSION: u32 = 3;
+
+/// Time given to the compositor to send the first xdg_surface.configure.
+const CONFIGURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Scroll amounts of a wl_pointer frame, positive when scrolling down or right.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PointerAxis {
+    /// Continuous amount in surface-local coordinates.
+    pub horizontal: f64,
+    pub vertical: f64,
+    /// High-resolution wheel steps, 120 per detent. Zero for devices without a wheel.
+    pub horizontal120: i32,
+    pub vertical120: i32,
+}
+
+/// Key of a wl_keyboard.key event, or synthesized while the key is held.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyEvent {
+    /// Linux input event code (KEY_*).
+    pub code: u32,
+    /// Keysym of the current keymap and modifiers, 0 (NoSymbol) without keymap.
+    pub keysym: u32,
+    /// Character of the keysym, without the effect of Control.
+    pub symbol: Option<char>,
+    /// Text typed by the key.
+    pub utf8: String,
+    pub modifiers: Modifiers,
+    /// True for the key presses synthesized by key repeat.
+    pub repeat: bool,
+}
+
+pub type KeyCb = Box<dyn Fn(&KeyEvent)>;
+
+/// Callbacks of the input events. Pointer coordinates are surface-local, buttons are the Linux
+/// input event codes (BTN_LEFT...).
 #[derive(Default)]
 pub struct WaylandEventCb {
-    pub key_pressed: Option<Box<dyn Fn(u32)>>,
-    pub key_released: Option<Box<dyn Fn(u32)>>,
+    pub key_pressed: Option<KeyCb>,
+    pub key_released: Option<KeyCb>,
+    pub pointer_enter: Option<Box<dyn Fn(f64, f64)>>,
+    pub pointer_leave: Option<Box<dyn Fn()>>,
+    pub pointer_motion: Option<Box<dyn Fn(f64, f64)>>,
+    pub pointer_button: Option<Box<dyn Fn(u32, bool)>>,
+    pub pointer_axis: Option<Box<dyn Fn(PointerAxis)>>,
 }
 
 #[derive(Default)]
@@ -42,6 +94,176 @@ pub struct WaylandStateInner {
     pub configured: bool,
     pub egl_window: Option<WlEglSurface>,
     pub event_cb: Option<WaylandEventCb>,
+    /// Registry name and proxy of the seat providing the input.
+    pub seat: Option<(u32, wl_seat::WlSeat)>,
+    pub keyboard: Option<wl_keyboard::WlKeyboard>,
+    /// None if libxkbcommon is not available, key codes are given without keysyms then.
+    pub xkb: Option<Xkb>,
+    /// Key repeat rate in characters per second and delay in milliseconds.
+    pub repeat_info: (i32, i32),
+    /// Key being repeated and time of its next repetition.
+    pub key_repeat: Option<(u32, Instant)>,
+    pub pointer: Option<wl_pointer::WlPointer>,
+    /// Scrolling received since the last wl_pointer.frame.
+    pub pointer_axis: Option<PointerAxis>,
+    /// Size requested by the last xdg_toplevel.configure, applied by xdg_surface.configure.
+    pub pending_size: Option<(i32, i32)>,
+    /// Size of the EGL window.
+    pub size: (i32, i32),
+    /// Set when the EGL window has been resized, until taken by take_resize().
+    pub resized: Option<(i32, i32)>,
+}
+
+impl WaylandStateInner {
+    fn bind_seat(
+        &mut self,
+        registry: &wl_registry::WlRegistry,
+        name: u32,
+        version: u32,
+        qh: &QueueHandle<Self>,
+    ) {
+        let version = version.min(WL_SEAT_VERSION);
+        jinfo!(
+            name = "WaylandState",
+            bind = "wl_seat",
+            global = name,
+            version = version
+        );
+        let seat = registry.bind::<wl_seat::WlSeat, _, _>(name, version, qh, ());
+        self.seat = Some((name, seat));
+    }
+
+    fn key_event(&self, code: u32, repeat: bool) -> KeyEvent {
+        match self.xkb.as_ref() {
+            Some(xkb) => {
+                let keysym = xkb.keysym(code);
+                KeyEvent {
+                    code,
+                    keysym,
+                    symbol: xkb.keysym_char(keysym),
+                    utf8: xkb.utf8(code),
+                    modifiers: xkb.modifiers(),
+                    repeat,
+                }
+            }
+            None => KeyEvent {
+                code,
+                keysym: 0,
+                symbol: None,
+                utf8: String::new(),
+                modifiers: Modifiers::default(),
+                repeat,
+            },
+        }
+    }
+
+    fn key_pressed(&self, event: &KeyEvent) {
+        if let Some(key_pressed) = self
+            .event_cb
+            .as_ref()
+            .and_then(|cb| cb.key_pressed.as_ref())
+        {
+            key_pressed(event);
+        }
+    }
+
+    fn key_released(&self, event: &KeyEvent) {
+        if let Some(key_released) = self
+            .event_cb
+            .as_ref()
+            .and_then(|cb| cb.key_released.as_ref())
+        {
+            key_released(event);
+        }
+    }
+
+    /// Synthesize the presses of the held key which are due at `now`.
+    fn repeat_keys(&mut self, now: Instant) {
+        let (rate, _) = self.repeat_info;
+        let Some((code, mut next)) = self.key_repeat else {
+            return;
+        };
+
+        if rate <= 0 {
+            self.key_repeat = None;
+            return;
+        }
+
+        let interval = Duration::from_secs(1) / rate as u32;
+        while next <= now {
+            self.key_pressed(&self.key_event(code, true));
+            next += interval;
+        }
+        self.key_repeat = Some((code, next));
+    }
+
+    fn release_keyboard(&mut self) {
+        self.key_repeat = None;
+        if let Some(keyboard) = self.keyboard.take() {
+            if keyboard.version() >= 3 {
+                keyboard.release();
+            }
+        }
+    }
+
+    fn add_pointer_axis120(&mut self, axis: wl_pointer::Axis, value120: i32) {
+        let pending = self.pointer_axis.get_or_insert_with(PointerAxis::default);
+        match axis {
+            wl_pointer::Axis::HorizontalScroll => pending.horizontal120 += value120,
+            _ => pending.vertical120 += value120,
+        }
+    }
+
+    fn flush_pointer_axis(&mut self) {
+        let Some(axis) = self.pointer_axis.take() else {
+            return;
+        };
+
+        if let Some(pointer_axis) = self
+            .event_cb
+            .as_ref()
+            .and_then(|cb| cb.pointer_axis.as_ref())
+        {
+            pointer_axis(axis);
+        }
+    }
+
+    fn release_pointer(&mut self) {
+        if let Some(pointer) = self.pointer.take() {
+            if pointer.version() >= 3 {
+                pointer.release();
+            }
+        }
+        self.pointer_axis = None;
+    }
+
+    fn release_seat(&mut self) {
+        self.release_keyboard();
+        self.release_pointer();
+        if let Some((_, seat)) = self.seat.take() {
+            if seat.version() >= 5 {
+                seat.release();
+            }
+        }
+    }
+
+    /// Apply the size of the last toplevel configure to the EGL window. Without EGL window yet,
+    /// the size is kept until one is created.
+    fn apply_pending_size(&mut self) {
+        let Some(egl_window) = self.egl_window.as_ref() else {
+            return;
+        };
+
+        // A zero size means that the size is up to us.
+        if let Some((width, height)) = self.pending_size.take() {
+            if width > 0 && height > 0 && (width, height) != self.size {
+                jinfo!(name = "WaylandState", resize = format!("{width}x{height}"));
+                egl_window.resize(width, height, 0, 0);
+                self.size = (width, height);
+                self.resized = Some(self.size);
+            }
+        }
+    }
 }
 
 #[derive(Default)]
@@ -54,33 +276,123 @@ impl WaylandState {
     pub fn new(event_cb: Option<WaylandEventCb>) -> Result<Self, OglError> {
         let mut ws = WaylandState::default();
         ws.inner.event_cb = event_cb;
+        // Used until the compositor sends wl_keyboard.repeat_info.
+        ws.inner.repeat_info = (25, 600);
+        ws.inner.xkb = match Xkb::new() {
+            Ok(xkb) => Some(xkb),
+            Err(e) => {
+                jwarn!("Keys are handled without keymap: {:?}", e);
+                None
+            }
+        };
 
         let conn = Connection::connect_to_env().map_err(|e| {
             Report::new(OglError::WaylandError)
                 .attach_printable(format!("Failed to connect to wayland server: {e}"))
         })?;
 
-        let mut event_queue = conn.new_event_queue();
+        jdebug!("round trip.");
+        let (globals, event_queue) =
+            registry_queue_init::<WaylandStateInner>(&conn).map_err(|e| {
+                Report::new(OglError::WaylandError)
+                    .attach_printable(format!("Failed to get the globals: {e}"))
+            })?;
         let qh = event_queue.handle();
 
-        let display = conn.display();
+        // All globals are known at this point, whatever the order they were announced in.
+        let comp: wl_compositor::WlCompositor = bind_global(&globals, &qh, WL_COMPOSITOR_VERSION)?;
+        let xdg_base: xdg_wm_base::XdgWmBase = bind_global(&globals, &qh, XDG_WM_BASE_VERSION)?;
+        ws.inner.shm = bind_global(&globals, &qh, WL_SHM_VERSION).ok();
+        if ws.inner.shm.is_none() {
+            jwarn!("No wl_shm.");
+        }
 
-        display.get_registry(&qh, ());
-        jdebug!("round trip.");
-        event_queue.roundtrip(&mut ws.inner).map_err(|e| {
-            Report::new(OglError::WaylandError).attach_printable(format!("Failed to dispatch: {e}"))
-        })?;
+        let seat = globals.contents().with_list(|list| {
+            list.iter()
+                .find(|g| g.interface == wl_seat::WlSeat::interface().name)
+                .map(|g| (g.name, g.version))
+        });
+        match seat {
+            Some((name, version)) => ws.inner.bind_seat(globals.registry(), name, version, &qh),
+            None => jwarn!("No wl_seat, waiting for one to get input."),
+        }
 
-        assert_ne!(ws.inner.comp, None);
-        assert_ne!(ws.inner.surface, None);
-        assert_ne!(ws.inner.xdg_base, None);
-        assert_ne!(ws.inner.xdg_surface, None);
+        let surface = comp.create_surface(&qh, ());
+        let s = xdg_base.get_xdg_surface(&surface, &qh, ());
+        let t = s.get_toplevel(&qh, ());
+        t.set_title("xdg".into());
+        surface.commit();
+
+        ws.inner.comp = Some(comp);
+        ws.inner.surface = Some(surface);
+        ws.inner.xdg_base = Some(xdg_base);
+        ws.inner.xdg_surface = Some((s, t));
         ws.inner.conn = Some(conn);
         ws.event_queue = Some(event_queue);
+        ws.wait_configure(CONFIGURE_TIMEOUT)?;
 
         Ok(ws)
     }
 
+    /// Block until the first xdg_surface.configure has been acked, nothing may be drawn before.
+    fn wait_configure(&mut self, timeout: Duration) -> Result<(), OglError> {
+        let deadline = Instant::now() + timeout;
+        while !self.inner.configured {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(
+                    Report::new(OglError::WaylandError).attach_printable(format!(
+                        "No xdg_surface.configure received from the compositor within {}s",
+                        timeout.as_secs()
+                    )),
+                );
+            }
+
+            self.dispatch_timeout(deadline - now)?;
+        }
+
+        jdebug!("configured.");
+        self.event_queue
+            .as_mut()
+            .unwrap()
+            .flush()
+            .map_err(|e| Report::new(OglError::WaylandError).attach_printable(e))
+    }
+
+    /// Dispatch the events received within `timeout`.
+    fn dispatch_timeout(&mut self, timeout: Duration) -> Result<(), OglError> {
+        let event_queue = self.event_queue.as_mut().unwrap();
+        event_queue
+            .flush()
+            .map_err(|e| Report::new(OglError::WaylandError).attach_printable(e))?;
+
+        if let Some(guard) = event_queue.prepare_read() {
+            let fd = guard.connection_fd();
+            let mut fds = [rustix::event::PollFd::new(
+                &fd,
+                rustix::event::PollFlags::IN | rustix::event::PollFlags::ERR,
+            )];
+
+            let ready = match rustix::event::poll(&mut fds, timeout.as_millis() as i32) {
+                Ok(n) => n,
+                Err(rustix::io::Errno::INTR) => 0,
+                Err(e) => return Err(Report::new(OglError::WaylandError).attach_printable(e)),
+            };
+
+            // Dropping the guard without reading cancels the read.
+            if ready > 0 {
+                guard
+                    .read()
+                    .map_err(|e| Report::new(OglError::WaylandError).attach_printable(e))?;
+            }
+        }
+
+        event_queue
+            .dispatch_pending(&mut self.inner)
+            .map(|_| ())
+            .map_err(|e| Report::new(OglError::WaylandError).attach_printable(e))
+    }
+
     pub fn display(&self) -> *mut libc::c_void {
         self.inner.conn.as_ref().unwrap().backend().display_ptr() as *mut libc::c_void
     }
@@ -92,10 +404,20 @@ impl WaylandState {
                 .map_err(|e| Report::new(OglError::WaylandError).attach_printable(e))?,
         );
 
+        self.inner.size = (width, height);
+        self.inner.apply_pending_size();
+
         Ok(self.inner.egl_window.as_ref().unwrap().ptr() as *mut libc::c_void)
     }
 
+    /// New size of the EGL window if it changed since the last call.
+    pub fn take_resize(&mut self) -> Option<(i32, i32)> {
+        self.inner.resized.take()
+    }
+
     pub fn dispatch(&mut self) -> Result<(), OglError> {
+        self.inner.repeat_keys(Instant::now());
+
         //        let event_queue = self.event_queue.as_mut().unwrap();
         //
         //        let result = event_queue
@@ -147,61 +469,70 @@ impl WaylandState {
     }
 }
 
-impl Dispatch<wl_registry::WlRegistry, ()> for WaylandStateInner {
+/// Bind the global providing `I`, with the highest version supported by both sides up to `max`.
+fn bind_global<I>(
+    globals: &GlobalList,
+    qh: &QueueHandle<WaylandStateInner>,
+    max: u32,
+) -> Result<I, OglError>
+where
+    I: Proxy + 'static,
+    WaylandStateInner: Dispatch<I, ()>,
+{
+    globals.bind::<I, _, _>(qh, 1..=max, ()).map_err(|e| {
+        Report::new(OglError::WaylandError).attach_printable(format!(
+            "The compositor does not provide {} version 1 to {max}: {e}",
+            I::interface().name
+        ))
+    })
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for WaylandStateInner {
     fn event(
         state: &mut Self,
-        proxy: &wl_registry::WlRegistry,
+        registry: &wl_registry::WlRegistry,
         event: <wl_registry::WlRegistry as wayland_client::Proxy>::Event,
-        _data: &(),
+        data: &GlobalListContents,
         _conn: &Connection,
         qh: &QueueHandle<Self>,
     ) {
-        if let wl_registry::Event::Global {
-            name,
-            interface,
-            version,
-        } = event
-        {
-            match &interface[..] {
-                "wl_compositor" => {
-                    jinfo!(
-                        name = "WlRegistry",
-                        event = "Dispatch",
-                        interface = interface,
-                        version = version,
-                    );
-                    let comp =
-                        proxy.bind::<wl_compositor::WlCompositor, _, _>(name, version, qh, ());
-
-                    let surface = comp.create_surface(qh, ());
-
-                    state.comp = Some(comp);
-                    state.surface = Some(surface);
+        // Globals announced after the initial roundtrip, e.g. a seat being plugged in.
+        match event {
+            wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } => {
+                jinfo!(
+                    name = "WlRegistry",
+                    event = "Global",
+                    global = name,
+                    interface = interface,
+                    version = version
+                );
+                if interface == wl_seat::WlSeat::interface().name && state.seat.is_none() {
+                    state.bind_seat(registry, name, version, qh);
                 }
-                "xdg_wm_base" => {
-                    let wm_base = proxy.bind::<xdg_wm_base::XdgWmBase, _, _>(name, version, qh, ());
-                    if let Some(wl_surface) = state.surface.as_ref() {
-                        let s = wm_base.get_xdg_surface(wl_surface, qh, ());
-                        let t = s.get_toplevel(qh, ());
-                        t.set_title("xdg".into());
-                        wl_surface.commit();
-
-                        state.xdg_surface = Some((s, t));
+            }
+            wl_registry::Event::GlobalRemove { name } => {
+                jinfo!(name = "WlRegistry", event = "GlobalRemove", global = name);
+                if state.seat.as_ref().is_some_and(|(seat, _)| *seat == name) {
+                    state.release_seat();
+
+                    // Fall back to another seat if there is one.
+                    let other = data.with_list(|list| {
+                        list.iter()
+                            .find(|g| {
+                                g.name != name && g.interface == wl_seat::WlSeat::interface().name
+                            })
+                            .map(|g| (g.name, g.version))
+                    });
+                    if let Some((name, version)) = other {
+                        state.bind_seat(registry, name, version, qh);
                     }
-                    state.xdg_base = Some(wm_base);
-                }
-                "wl_shm" => {
-                    let shm = proxy.bind::<wl_shm::WlShm, _, _>(name, version, qh, ());
-                    state.shm = Some(shm);
-                }
-                "wl_seat" => {
-                    proxy.bind::<wl_seat::WlSeat, _, _>(name, version, qh, ());
-                }
-
-                _ => {
-                    jdebug!(name = name, interface = interface, version = version);
                 }
             }
+            _ => {}
         }
     }
 }
@@ -288,7 +619,7 @@ impl Dispatch<wl_buffer::WlBuffer, ()> for WaylandStateInner {
 
 impl Dispatch<wl_seat::WlSeat, ()> for WaylandStateInner {
     fn event(
-        _state: &mut Self,
+        state: &mut Self,
         seat: &wl_seat::WlSeat,
         event: <wl_seat::WlSeat as wayland_client::Proxy>::Event,
         _data: &(),
@@ -300,8 +631,18 @@ impl Dispatch<wl_seat::WlSeat, ()> for WaylandStateInner {
             capabilities: WEnum::Value(capabilities),
         } = event
         {
-            if capabilities.contains(wl_seat::Capability::Keyboard) {
-                seat.get_keyboard(qh, ());
+            let keyboard = capabilities.contains(wl_seat::Capability::Keyboard);
+            if keyboard && state.keyboard.is_none() {
+                state.keyboard = Some(seat.get_keyboard(qh, ()));
+            } else if !keyboard {
+                state.release_keyboard();
+            }
+
+            let pointer = capabilities.contains(wl_seat::Capability::Pointer);
+            if pointer && state.pointer.is_none() {
+                state.pointer = Some(seat.get_pointer(qh, ()));
+            } else if !pointer {
+                state.release_pointer();
             }
         }
     }
@@ -316,27 +657,140 @@ impl Dispatch<wl_keyboard::WlKeyboard, ()> for WaylandStateInner {
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
     ) {
-        if let wl_keyboard::Event::Key {
-            key,
-            state: WEnum::Value(key_state),
-            ..
-        } = event
-        {
-            if let Some(ref event_cb) = state.event_cb {
+        match event {
+            wl_keyboard::Event::Keymap {
+                format: WEnum::Value(wl_keyboard::KeymapFormat::XkbV1),
+                fd,
+                size,
+            } => {
+                if let Some(xkb) = state.xkb.as_mut() {
+                    match xkb.set_keymap(fd, size) {
+                        Ok(_) => jinfo!(name = "WlKeyboard", event = "Keymap", size = size),
+                        Err(e) => jwarn!("Keymap ignored: {:?}", e),
+                    }
+                }
+            }
+            wl_keyboard::Event::Leave { .. } => state.key_repeat = None,
+            wl_keyboard::Event::Modifiers {
+                mods_depressed,
+                mods_latched,
+                mods_locked,
+                group,
+                ..
+            } => {
+                if let Some(xkb) = state.xkb.as_mut() {
+                    xkb.update_modifiers(mods_depressed, mods_latched, mods_locked, group);
+                }
+            }
+            wl_keyboard::Event::RepeatInfo { rate, delay } => {
+                jinfo!(
+                    name = "WlKeyboard",
+                    event = "RepeatInfo",
+                    rate = rate,
+                    delay = delay
+                );
+                state.repeat_info = (rate, delay);
+            }
+            wl_keyboard::Event::Key {
+                key,
+                state: WEnum::Value(key_state),
+                ..
+            } => {
+                let event = state.key_event(key, false);
                 match key_state {
                     KeyState::Pressed => {
-                        if let Some(ref key_press) = event_cb.key_pressed {
-                            key_press(key);
+                        let (rate, delay) = state.repeat_info;
+                        let repeats = state.xkb.as_ref().is_none_or(|xkb| xkb.repeats(key));
+                        if rate > 0 && repeats {
+                            let delay = Duration::from_millis(delay.max(0) as u64);
+                            state.key_repeat = Some((key, Instant::now() + delay));
                         }
+                        state.key_pressed(&event);
                     }
                     KeyState::Released => {
-                        if let Some(ref key_released) = event_cb.key_released {
-                            key_released(key);
+                        if state.key_repeat.is_some_and(|(code, _)| code == key) {
+                            state.key_repeat = None;
                         }
+                        state.key_released(&event);
                     }
                     _ => {}
                 }
             }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_pointer::WlPointer, ()> for WaylandStateInner {
+    fn event(
+        state: &mut Self,
+        pointer: &wl_pointer::WlPointer,
+        event: <wl_pointer::WlPointer as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let event_cb = state.event_cb.as_ref();
+        match event {
+            wl_pointer::Event::Enter {
+                surface_x,
+                surface_y,
+                ..
+            } => {
+                if let Some(enter) = event_cb.and_then(|cb| cb.pointer_enter.as_ref()) {
+                    enter(surface_x, surface_y);
+                }
+            }
+            wl_pointer::Event::Leave { .. } => {
+                if let Some(leave) = event_cb.and_then(|cb| cb.pointer_leave.as_ref()) {
+                    leave();
+                }
+            }
+            wl_pointer::Event::Motion {
+                surface_x,
+                surface_y,
+                ..
+            } => {
+                if let Some(motion) = event_cb.and_then(|cb| cb.pointer_motion.as_ref()) {
+                    motion(surface_x, surface_y);
+                }
+            }
+            wl_pointer::Event::Button {
+                button,
+                state: WEnum::Value(button_state),
+                ..
+            } => {
+                if let Some(pointer_button) = event_cb.and_then(|cb| cb.pointer_button.as_ref()) {
+                    pointer_button(button, button_state == ButtonState::Pressed);
+                }
+            }
+            wl_pointer::Event::Axis {
+                axis: WEnum::Value(axis),
+                value,
+                ..
+            } => {
+                let pending = state.pointer_axis.get_or_insert_with(PointerAxis::default);
+                match axis {
+                    wl_pointer::Axis::HorizontalScroll => pending.horizontal += value,
+                    _ => pending.vertical += value,
+                }
+
+                // Before version 5 there is no frame event to wait for.
+                if pointer.version() < 5 {
+                    state.flush_pointer_axis();
+                }
+            }
+            // Replaced by axis_value120 from version 8.
+            wl_pointer::Event::AxisDiscrete {
+                axis: WEnum::Value(axis),
+                discrete,
+            } => state.add_pointer_axis120(axis, discrete * 120),
+            wl_pointer::Event::AxisValue120 {
+                axis: WEnum::Value(axis),
+                value120,
+            } => state.add_pointer_axis120(axis, value120),
+            wl_pointer::Event::Frame => state.flush_pointer_axis(),
+            _ => {}
         }
     }
 }
@@ -368,6 +822,8 @@ impl Dispatch<xdg_surface::XdgSurface, ()> for WaylandStateInner {
     ) {
         jinfo!(name = "XdgSurface", event = "Dispatch");
         if let xdg_surface::Event::Configure { serial, .. } = event {
+            // The new size is used by the next buffer, which is committed after the ack.
+            state.apply_pending_size();
             surface.ack_configure(serial);
             state.configured = true;
         }
@@ -376,13 +832,37 @@ impl Dispatch<xdg_surface::XdgSurface, ()> for WaylandStateInner {
 
 impl Dispatch<xdg_toplevel::XdgToplevel, ()> for WaylandStateInner {
     fn event(
-        _state: &mut Self,
+        state: &mut Self,
         _proxy: &xdg_toplevel::XdgToplevel,
-        _event: <xdg_toplevel::XdgToplevel as wayland_client::Proxy>::Event,
+        event: <xdg_toplevel::XdgToplevel as wayland_client::Proxy>::Event,
         _data: &(),
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
     ) {
-        jinfo!(name = "XdgToplevel", event = "Dispatch");
+        match event {
+            xdg_toplevel::Event::Configure {
+                width,
+                height,
+                states,
+            } => {
+                let states: Vec<u32> = states
+                    .chunks_exact(4)
+                    .map(|s| u32::from_ne_bytes([s[0], s[1], s[2], s[3]]))
+                    .collect();
+                jinfo!(
+                    name = "XdgToplevel",
+                    event = "Configure",
+                    width = width,
+                    height = height,
+                    states = format!("{:?}", states)
+                );
+                state.pending_size = Some((width, height));
+            }
+            xdg_toplevel::Event::Close => {
+                jinfo!(name = "XdgToplevel", event = "Close");
+                RunState::global_stop();
+            }
+            _ => jinfo!(name = "XdgToplevel", event = "Dispatch"),
+        }
     }
 }
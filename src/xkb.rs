@@ -0,0 +1,337 @@
+//! Keymaps of wl_keyboard, handled by libxkbcommon loaded at run time.
+
+use error_stack::{Report, Result};
+use libogl::error::OglError;
+use std::ffi::{c_char, c_int, c_void, CString};
+use std::os::fd::{AsRawFd, OwnedFd};
+
+const XKB_KEYMAP_FORMAT_TEXT_V1: c_int = 1;
+const XKB_STATE_MODS_EFFECTIVE: c_int = 1 << 3;
+
+/// Evdev codes are offset by 8 in XKB keymaps.
+const EVDEV_OFFSET: u32 = 8;
+
+/// Modifiers in effect, latched and locked ones included.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub logo: bool,
+    pub caps_lock: bool,
+    pub num_lock: bool,
+}
+
+type Ptr = *mut c_void;
+
+/// Functions of libxkbcommon, valid as long as `_lib` is loaded.
+struct XkbLib {
+    _lib: libloading::Library,
+    context_new: unsafe extern "C" fn(c_int) -> Ptr,
+    context_unref: unsafe extern "C" fn(Ptr),
+    keymap_new_from_buffer: unsafe extern "C" fn(Ptr, *const c_char, usize, c_int, c_int) -> Ptr,
+    keymap_unref: unsafe extern "C" fn(Ptr),
+    keymap_key_repeats: unsafe extern "C" fn(Ptr, u32) -> c_int,
+    state_new: unsafe extern "C" fn(Ptr) -> Ptr,
+    state_unref: unsafe extern "C" fn(Ptr),
+    state_update_mask: unsafe extern "C" fn(Ptr, u32, u32, u32, u32, u32, u32) -> c_int,
+    state_key_get_one_sym: unsafe extern "C" fn(Ptr, u32) -> u32,
+    state_key_get_utf8: unsafe extern "C" fn(Ptr, u32, *mut c_char, usize) -> c_int,
+    state_mod_name_is_active: unsafe extern "C" fn(Ptr, *const c_char, c_int) -> c_int,
+    keysym_to_utf32: unsafe extern "C" fn(u32) -> u32,
+}
+
+impl XkbLib {
+    fn load() -> Result<Self, OglError> {
+        let lib = unsafe {
+            libloading::Library::new("libxkbcommon.so.0").map_err(|e| {
+                Report::new(OglError::WaylandError)
+                    .attach_printable(format!("Failed to load libxkbcommon.so.0: {e}"))
+            })?
+        };
+
+        Ok(Self {
+            context_new: symbol(&lib, "xkb_context_new")?,
+            context_unref: symbol(&lib, "xkb_context_unref")?,
+            keymap_new_from_buffer: symbol(&lib, "xkb_keymap_new_from_buffer")?,
+            keymap_unref: symbol(&lib, "xkb_keymap_unref")?,
+            keymap_key_repeats: symbol(&lib, "xkb_keymap_key_repeats")?,
+            state_new: symbol(&lib, "xkb_state_new")?,
+            state_unref: symbol(&lib, "xkb_state_unref")?,
+            state_update_mask: symbol(&lib, "xkb_state_update_mask")?,
+            state_key_get_one_sym: symbol(&lib, "xkb_state_key_get_one_sym")?,
+            state_key_get_utf8: symbol(&lib, "xkb_state_key_get_utf8")?,
+            state_mod_name_is_active: symbol(&lib, "xkb_state_mod_name_is_active")?,
+            keysym_to_utf32: symbol(&lib, "xkb_keysym_to_utf32")?,
+            _lib: lib,
+        })
+    }
+}
+
+fn symbol<T: Copy>(lib: &libloading::Library, name: &str) -> Result<T, OglError> {
+    unsafe { lib.get::<T>(name.as_bytes()) }
+        .map(|s| *s)
+        .map_err(|e| {
+            Report::new(OglError::WaylandError)
+                .attach_printable(format!("Failed to load {name}: {e}"))
+        })
+}
+
+/// Keymap and modifier state of a keyboard. Keys are given as Linux input event codes.
+pub struct Xkb {
+    lib: XkbLib,
+    context: Ptr,
+    keymap: Ptr,
+    state: Ptr,
+}
+
+impl Xkb {
+    pub fn new() -> Result<Self, OglError> {
+        let lib = XkbLib::load()?;
+        let context = unsafe { (lib.context_new)(0) };
+        if context.is_null() {
+            return Err(Report::new(OglError::WaylandError)
+                .attach_printable("Failed to create the xkb context"));
+        }
+
+        Ok(Self {
+            lib,
+            context,
+            keymap: std::ptr::null_mut(),
+            state: std::ptr::null_mut(),
+        })
+    }
+
+    /// Compile the XKB v1 keymap of `size` bytes sent by wl_keyboard.keymap. The modifiers are
+    /// reset.
+    pub fn set_keymap(&mut self, fd: OwnedFd, size: u32) -> Result<(), OglError> {
+        let size = size as usize;
+        let data = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+        if data == libc::MAP_FAILED {
+            return Err(
+                Report::new(OglError::WaylandError).attach_printable(format!(
+                    "Failed to map the keymap: {}",
+                    std::io::Error::last_os_error()
+                )),
+            );
+        }
+
+        let keymap = unsafe {
+            // The keymap is terminated by a NUL byte, which is not part of the buffer.
+            let text = std::slice::from_raw_parts(data as *const u8, size);
+            let len = text.iter().position(|c| *c == 0).unwrap_or(size);
+            let keymap = (self.lib.keymap_new_from_buffer)(
+                self.context,
+                data as *const c_char,
+                len,
+                XKB_KEYMAP_FORMAT_TEXT_V1,
+                0,
+            );
+            libc::munmap(data, size);
+            keymap
+        };
+
+        if keymap.is_null() {
+            return Err(Report::new(OglError::WaylandError)
+                .attach_printable("Failed to compile the keymap"));
+        }
+
+        let state = unsafe { (self.lib.state_new)(keymap) };
+        if state.is_null() {
+            unsafe { (self.lib.keymap_unref)(keymap) };
+            return Err(Report::new(OglError::WaylandError)
+                .attach_printable("Failed to create the xkb state"));
+        }
+
+        self.release_keymap();
+        self.keymap = keymap;
+        self.state = state;
+
+        Ok(())
+    }
+
+    pub fn has_keymap(&self) -> bool {
+        !self.state.is_null()
+    }
+
+    /// Apply wl_keyboard.modifiers.
+    pub fn update_modifiers(&mut self, depressed: u32, latched: u32, locked: u32, group: u32) {
+        if self.has_keymap() {
+            unsafe {
+                (self.lib.state_update_mask)(self.state, depressed, latched, locked, 0, 0, group)
+            };
+        }
+    }
+
+    /// Keysym produced by `code` with the current modifiers, 0 (NoSymbol) if there is none.
+    pub fn keysym(&self, code: u32) -> u32 {
+        if !self.has_keymap() {
+            return 0;
+        }
+
+        unsafe { (self.lib.state_key_get_one_sym)(self.state, code + EVDEV_OFFSET) }
+    }
+
+    /// Text typed by `code` with the current modifiers.
+    pub fn utf8(&self, code: u32) -> String {
+        if !self.has_keymap() {
+            return String::new();
+        }
+
+        let mut buffer = [0u8; 64];
+        let len = unsafe {
+            (self.lib.state_key_get_utf8)(
+                self.state,
+                code + EVDEV_OFFSET,
+                buffer.as_mut_ptr() as *mut c_char,
+                buffer.len(),
+            )
+        };
+
+        let len = (len.max(0) as usize).min(buffer.len() - 1);
+        String::from_utf8_lossy(&buffer[..len]).into_owned()
+    }
+
+    /// Character of `keysym`, without the effect of Control unlike utf8().
+    pub fn keysym_char(&self, keysym: u32) -> Option<char> {
+        match unsafe { (self.lib.keysym_to_utf32)(keysym) } {
+            0 => None,
+            c => char::from_u32(c),
+        }
+    }
+
+    /// True if holding `code` repeats it, false for modifiers for instance.
+    pub fn repeats(&self, code: u32) -> bool {
+        !self.has_keymap()
+            || unsafe { (self.lib.keymap_key_repeats)(self.keymap, code + EVDEV_OFFSET) } != 0
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        let active = |name: &str| {
+            let name = CString::new(name).unwrap();
+            self.has_keymap()
+                && unsafe {
+                    (self.lib.state_mod_name_is_active)(
+                        self.state,
+                        name.as_ptr(),
+                        XKB_STATE_MODS_EFFECTIVE,
+                    )
+                } > 0
+        };
+
+        Modifiers {
+            shift: active("Shift"),
+            ctrl: active("Control"),
+            alt: active("Mod1"),
+            logo: active("Mod4"),
+            caps_lock: active("Lock"),
+            num_lock: active("Mod2"),
+        }
+    }
+
+    fn release_keymap(&mut self) {
+        unsafe {
+            if !self.state.is_null() {
+                (self.lib.state_unref)(self.state);
+            }
+            if !self.keymap.is_null() {
+                (self.lib.keymap_unref)(self.keymap);
+            }
+        }
+        self.state = std::ptr::null_mut();
+        self.keymap = std::ptr::null_mut();
+    }
+}
+
+impl Drop for Xkb {
+    fn drop(&mut self) {
+        self.release_keymap();
+        unsafe { (self.lib.context_unref)(self.context) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Xkb;
+    use std::ffi::{c_char, c_int, c_void, CStr, CString};
+    use std::io::Write;
+    use std::os::fd::OwnedFd;
+
+    #[repr(C)]
+    struct RuleNames {
+        rules: *const c_char,
+        model: *const c_char,
+        layout: *const c_char,
+        variant: *const c_char,
+        options: *const c_char,
+    }
+
+    /// Keymap text of `layout`, as a compositor would send it.
+    fn keymap_text(layout: &str) -> Option<Vec<u8>> {
+        unsafe {
+            let lib = libloading::Library::new("libxkbcommon.so.0").ok()?;
+            let context_new: libloading::Symbol<unsafe extern "C" fn(c_int) -> *mut c_void> =
+                lib.get(b"xkb_context_new").ok()?;
+            let from_names: libloading::Symbol<
+                unsafe extern "C" fn(*mut c_void, *const RuleNames, c_int) -> *mut c_void,
+            > = lib.get(b"xkb_keymap_new_from_names").ok()?;
+            let as_string: libloading::Symbol<
+                unsafe extern "C" fn(*mut c_void, c_int) -> *mut c_char,
+            > = lib.get(b"xkb_keymap_get_as_string").ok()?;
+
+            let layout = CString::new(layout).unwrap();
+            let names = RuleNames {
+                rules: std::ptr::null(),
+                model: std::ptr::null(),
+                layout: layout.as_ptr(),
+                variant: std::ptr::null(),
+                options: std::ptr::null(),
+            };
+
+            let keymap = from_names(context_new(0), &names, 0);
+            if keymap.is_null() {
+                return None;
+            }
+            let text = as_string(keymap, 1);
+            let bytes = CStr::from_ptr(text).to_bytes_with_nul().to_vec();
+            libc::free(text as *mut c_void);
+            Some(bytes)
+        }
+    }
+
+    #[test]
+    fn keysyms_follow_the_layout_and_modifiers() {
+        let (Ok(mut xkb), Some(text)) = (Xkb::new(), keymap_text("fr")) else {
+            eprintln!("libxkbcommon or its keymaps are not installed, skipped");
+            return;
+        };
+
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(&text).unwrap();
+        xkb.set_keymap(OwnedFd::from(file), text.len() as u32)
+            .unwrap();
+
+        // KEY_Q is 'a' on an AZERTY keyboard.
+        const KEY_Q: u32 = 16;
+        const KEY_LEFTSHIFT: u32 = 42;
+        assert_eq!(xkb.keysym(KEY_Q), 'a' as u32);
+        assert_eq!(xkb.utf8(KEY_Q), "a");
+        assert!(xkb.repeats(KEY_Q));
+        assert!(!xkb.repeats(KEY_LEFTSHIFT));
+
+        // Shift is the first modifier of the keymaps of xkeyboard-config.
+        xkb.update_modifiers(1, 0, 0, 0);
+        assert!(xkb.modifiers().shift);
+        assert_eq!(xkb.utf8(KEY_Q), "A");
+        assert_eq!(xkb.keysym_char(xkb.keysym(KEY_Q)), Some('A'));
+    }
+}
@@ -0,0 +1,166 @@
+//! Golden image tests for draw functions.
+//!
+//! Every draw function is rendered offscreen through the headless EGL backend at a pinned time
+//! and compared with the reference images under `tests/golden/`. Mesa's software rasterizer
+//! (llvmpipe) is used so that the result does not depend on the GPU.
+//!
+//! Run with `OGL_BLESS=1` to (re)generate the reference images. On failure the actual and
+//! diff images are written under `target/golden/`.
+
+use libogl::screenshot::Screenshot;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const WINDOW: &str = "128x128";
+const FIXED_TIME_MS: &str = "1000";
+const AT_FRAME: &str = "2";
+
+/// Maximum difference per color channel.
+const TOLERANCE: u8 = 8;
+
+/// Number of pixels allowed to exceed TOLERANCE, e.g. for rasterization differences of edges.
+const MAX_MISMATCHED: usize = 16;
+
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn check(func: usize, name: &str, at_frame: &str) {
+    check_env(func, name, at_frame, &[]);
+}
+
+fn check_env(func: usize, name: &str, at_frame: &str, envs: &[(&str, &Path)]) {
+    let out_dir = root().join("target").join("golden");
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    let actual_path = out_dir.join(format!("{name}.actual.png"));
+    let _ = std::fs::remove_file(&actual_path);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ogl"))
+        .args(["--headless", "--window", WINDOW])
+        .args(["--func", &func.to_string()])
+        .args(["--fixed-time", FIXED_TIME_MS])
+        .args(["--at-frame", at_frame])
+        .arg("--screenshot")
+        .arg(&actual_path)
+        // Compile every shader instead of using the binaries cached under the home directory.
+        .arg("--no-program-cache")
+        .env("EGL_PLATFORM", "surfaceless")
+        .env("LIBGL_ALWAYS_SOFTWARE", "1")
+        .env("GALLIUM_DRIVER", "llvmpipe")
+        .envs(envs.iter().copied())
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{name} failed to render:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = Screenshot::load_png(&actual_path).unwrap();
+    let golden_path = root().join("tests").join("golden").join(format!("{name}.png"));
+
+    if std::env::var_os("OGL_BLESS").is_some() {
+        std::fs::create_dir_all(golden_path.parent().unwrap()).unwrap();
+        actual.save_png(&golden_path).unwrap();
+        return;
+    }
+
+    let golden = Screenshot::load_png(&golden_path).unwrap_or_else(|e| {
+        panic!("No reference image for {name}, run with OGL_BLESS=1 to create it: {e:?}")
+    });
+
+    let (mismatched, diff) = actual.diff(&golden, TOLERANCE).unwrap();
+    if mismatched > MAX_MISMATCHED {
+        let diff_path = out_dir.join(format!("{name}.diff.png"));
+        diff.save_png(&diff_path).unwrap();
+
+        panic!(
+            "{name}: {mismatched} pixels differ from {}, see {} and {}",
+            golden_path.display(),
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+/// Entries are `test => (func, name)`, or `test => (func, name, at_frame)` for the draw
+/// functions which need more frames than AT_FRAME to show something.
+macro_rules! golden {
+    (@frame) => { AT_FRAME };
+    (@frame $frame:expr) => { $frame };
+    ($($test:ident => ($func:expr, $name:expr $(, $frame:expr)?),)*) => {
+        $(
+            #[test]
+            fn $test() {
+                check($func, $name, golden!(@frame $($frame)?));
+            }
+        )*
+    };
+}
+
+// DrawCircle grows its viewport by 5 pixels per frame, the circle is 100x100 at frame 20.
+golden! {
+    draw_vbo => (1, "DrawVbo"),
+    draw_vbo2 => (2, "DrawVbo2"),
+    draw_vbo_vertex_color => (3, "DrawVboVertexColor"),
+    draw_vbo_vertex_color2 => (4, "DrawVboVertexColor2"),
+    draw_vao_vertex_color => (5, "DrawVaoVertexColor"),
+    draw_vao_vertex_color2 => (6, "DrawVaoVertexColor2"),
+    draw_vao_elements => (7, "DrawVaoVertexColorElement2"),
+    draw_circle => (8, "DrawCircle", "20"),
+    draw_complex => (9, "DrawComplex"),
+    draw_without_vbo => (10, "DrawWithoutVbo"),
+    draw_lines => (11, "DrawLines"),
+    draw_primitive_restart => (12, "DrawPrimitiveRestart"),
+    draw_provoking_vertex => (13, "DrawProvokingVertex"),
+    draw_instance => (14, "DrawInstance"),
+    draw_instance2 => (15, "DrawInstance2"),
+    draw_triangle_strip => (16, "DrawTriangleStrip"),
+    draw_model_view_projection => (17, "DrawModelViewProjection"),
+    draw_texture => (18, "DrawTexture"),
+    draw_texture2 => (19, "DrawTexture2"),
+    draw_texture3 => (20, "DrawTexture3"),
+    draw_texture_mipmapping => (21, "DrawTextureMipMapping"),
+    draw_meshes => (23, "DrawMeshes"),
+}
+
+/// DrawTextureCubeMap loads its faces from `OGL_IMAGES`, so they are generated here: a 2x2
+/// checkerboard of a distinct color per face. stb_image detects the format from the content,
+/// so PNG data under the `.jpg` names the demo expects is fine.
+#[test]
+fn draw_texture_cubemap() {
+    const FACES: [(&str, [u8; 3]); 6] = [
+        ("right", [255, 0, 0]),
+        ("left", [0, 255, 255]),
+        ("top", [0, 255, 0]),
+        ("bottom", [255, 0, 255]),
+        ("back", [0, 0, 255]),
+        ("front", [255, 255, 0]),
+    ];
+    const SIZE: u32 = 16;
+
+    let images = root().join("target").join("golden").join("cubemap");
+    std::fs::create_dir_all(&images).unwrap();
+
+    for (face, color) in FACES {
+        let data = (0..SIZE * SIZE)
+            .flat_map(|i| {
+                let (x, y) = (i % SIZE, i / SIZE);
+                if (x < SIZE / 2) == (y < SIZE / 2) {
+                    [color[0], color[1], color[2], 255]
+                } else {
+                    [color[0] / 2, color[1] / 2, color[2] / 2, 255]
+                }
+            })
+            .collect();
+
+        Screenshot::from_rgba(SIZE, SIZE, data)
+            .unwrap()
+            .save_png(images.join(format!("{face}.jpg")))
+            .unwrap();
+    }
+
+    check_env(22, "DrawTextureCubeMap", AT_FRAME, &[("OGL_IMAGES", &images)]);
+}